@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE workflow_versions
+  DROP COLUMN IF EXISTS input_schema,
+  DROP COLUMN IF EXISTS output_schema,
+  DROP COLUMN IF EXISTS activity_schemas;
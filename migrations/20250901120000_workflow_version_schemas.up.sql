@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE workflow_versions
+  ADD COLUMN input_schema       JSONB,                          -- JSON Schema for start-workflow input
+  ADD COLUMN output_schema      JSONB,                          -- JSON Schema for the workflow result
+  ADD COLUMN activity_schemas   JSONB NOT NULL DEFAULT '{}';    -- activity name -> JSON Schema for its result
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE workflow_versions
+  DROP COLUMN IF EXISTS manifest;
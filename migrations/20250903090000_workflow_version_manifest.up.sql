@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE workflow_versions
+  ADD COLUMN manifest JSONB NOT NULL DEFAULT '{}';   -- workflows, activities, signals and queries provided by the component
@@ -0,0 +1,2 @@
+-- Add down migration script here
+-- The backfilled keys are still valid, nothing to revert.
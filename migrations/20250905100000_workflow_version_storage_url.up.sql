@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Versions uploaded before blob keys were recorded were stored as '<key>.wasm' in the bucket root.
+UPDATE workflow_versions wv
+SET storage_url = w.key || '.wasm'
+FROM workflows w
+WHERE w.id = wv.workflow_id
+  AND (wv.storage_url IS NULL OR wv.storage_url = '');
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS namespace_slug_aliases_ns_id_idx;
+DROP TABLE IF EXISTS namespace_slug_aliases;
+ALTER TABLE namespaces
+  DROP COLUMN IF EXISTS archived_at;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE namespaces
+  ADD COLUMN archived_at TIMESTAMPTZ;                                 -- archived namespaces are read-only
+
+-- Old slugs keep resolving to the namespace after a rename.
+CREATE TABLE namespace_slug_aliases (
+  slug          TEXT PRIMARY KEY,
+  namespace_id  UUID NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX namespace_slug_aliases_ns_id_idx ON namespace_slug_aliases (namespace_id);
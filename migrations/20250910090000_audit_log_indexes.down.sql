@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS audit_logs_user_id_idx;
+DROP INDEX IF EXISTS audit_logs_ns_id_idx;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Audit queries page newest first by id, scoped to a namespace or an actor.
+CREATE INDEX audit_logs_ns_id_idx ON audit_logs (namespace_id, id DESC);
+CREATE INDEX audit_logs_user_id_idx ON audit_logs (user_id, id DESC);
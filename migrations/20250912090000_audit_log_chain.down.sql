@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS audit_checkpoints_chain_idx;
+DROP TABLE IF EXISTS audit_checkpoints;
+DROP INDEX IF EXISTS audit_logs_chain_seq_idx;
+ALTER TABLE audit_logs
+  DROP COLUMN IF EXISTS hash,
+  DROP COLUMN IF EXISTS prev_hash,
+  DROP COLUMN IF EXISTS actor_id,
+  DROP COLUMN IF EXISTS seq,
+  DROP COLUMN IF EXISTS chain_id;
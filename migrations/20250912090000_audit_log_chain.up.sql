@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- Every entry written from now on is linked into a hash chain per namespace.
+-- Entries written before have no chain columns and are not covered by verification.
+ALTER TABLE audit_logs
+  ADD COLUMN chain_id   UUID,         -- namespace at write time, nil uuid for global entries; no FK so it outlives the namespace
+  ADD COLUMN seq        BIGINT,       -- position in the chain, starting at 1
+  ADD COLUMN actor_id   UUID,         -- user_id at write time; user_id itself is nulled when the user goes
+  ADD COLUMN prev_hash  BYTEA,
+  ADD COLUMN hash       BYTEA;
+
+CREATE UNIQUE INDEX audit_logs_chain_seq_idx ON audit_logs (chain_id, seq);
+
+-- Signed chain heads, exported so the chain can be checked against a copy kept elsewhere.
+CREATE TABLE audit_checkpoints (
+  id          BIGSERIAL PRIMARY KEY,
+  chain_id    UUID NOT NULL,
+  seq         BIGINT NOT NULL,
+  hash        BYTEA NOT NULL,
+  key_id      TEXT NOT NULL,
+  signature   BYTEA NOT NULL,
+  created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_checkpoints_chain_idx ON audit_checkpoints (chain_id, seq DESC);
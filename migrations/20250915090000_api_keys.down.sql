@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS api_keys_ns_id_idx;
+DROP TABLE IF EXISTS api_keys;
+ALTER TABLE users
+  DROP COLUMN IF EXISTS service_account;
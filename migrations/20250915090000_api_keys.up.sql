@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- Service accounts are users that can only authenticate with an API key.
+ALTER TABLE users
+  ADD COLUMN service_account BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE api_keys (
+  id            UUID PRIMARY KEY,                                     -- uuidv7, will be generated on code side
+  namespace_id  UUID NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+  user_id       UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE, -- the service account the key acts as
+  name          TEXT NOT NULL,
+  role          namespace_role NOT NULL,                              -- role given on creation, the membership is what counts
+  prefix        TEXT UNIQUE NOT NULL,                                 -- public part of the key, used for lookup
+  secret_hash   TEXT NOT NULL,                                        -- sha256 of the secret part
+  created_by    UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+  expires_at    TIMESTAMPTZ,
+  last_used_at  TIMESTAMPTZ,
+  revoked_at    TIMESTAMPTZ
+);
+
+CREATE INDEX api_keys_ns_id_idx ON api_keys (namespace_id);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS revoked_tokens;
+DROP INDEX IF EXISTS user_sessions_user_id_idx;
+DROP TABLE IF EXISTS user_sessions;
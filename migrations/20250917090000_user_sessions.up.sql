@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- A sign-in starts a session; access tokens name it and are refreshed through it.
+CREATE TABLE user_sessions (
+  id                 UUID PRIMARY KEY,                                     -- uuidv7, will be generated on code side
+  user_id            UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  refresh_hash       TEXT NOT NULL,                                        -- sha256 of the current refresh secret
+  user_agent         TEXT,
+  ip                 TEXT,
+  created_at         TIMESTAMPTZ NOT NULL DEFAULT now(),
+  last_refreshed_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+  expires_at         TIMESTAMPTZ NOT NULL,                                 -- when the refresh token stops working
+  revoked_at         TIMESTAMPTZ
+);
+
+CREATE INDEX user_sessions_user_id_idx ON user_sessions (user_id);
+
+-- Single access tokens killed before they expire; rows are useless after expires_at.
+CREATE TABLE revoked_tokens (
+  jti         UUID PRIMARY KEY,
+  expires_at  TIMESTAMPTZ NOT NULL,
+  revoked_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
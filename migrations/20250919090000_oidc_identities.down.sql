@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS oidc_logins;
+DROP INDEX IF EXISTS user_identities_user_id_idx;
+DROP TABLE IF EXISTS user_identities;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- Accounts at the identity provider linked to a user; `subject` is stable where emails are not.
+CREATE TABLE user_identities (
+  issuer      TEXT NOT NULL,
+  subject     TEXT NOT NULL,
+  user_id     UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+  last_login  TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (issuer, subject)
+);
+
+CREATE INDEX user_identities_user_id_idx ON user_identities (user_id);
+
+-- Logins sent to the identity provider and not back yet; a row is consumed by the callback.
+CREATE TABLE oidc_logins (
+  state          TEXT PRIMARY KEY,
+  nonce          TEXT NOT NULL,
+  code_verifier  TEXT NOT NULL,                                            -- PKCE verifier, the challenge went to the provider
+  created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
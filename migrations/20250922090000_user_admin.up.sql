@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Disabled users can't sign in and lose their sessions; the row stays for the audit trail.
+ALTER TABLE users ADD COLUMN disabled_at TIMESTAMPTZ;
+
+CREATE INDEX users_super_admin_idx ON users (id) WHERE super_admin;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS namespace_invitations_pending_idx;
+DROP TABLE IF EXISTS namespace_invitations;
@@ -0,0 +1,19 @@
+-- Add up migration script here
+-- Invitations into a namespace; accepting one creates the account when the email has none.
+CREATE TABLE namespace_invitations (
+  id            UUID PRIMARY KEY,                                          -- uuidv7, will be generated on code side
+  namespace_id  UUID NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+  email         CITEXT NOT NULL,
+  role          namespace_role NOT NULL,
+  token_hash    TEXT NOT NULL,                                             -- sha256 of the invitation secret
+  invited_by    UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+  expires_at    TIMESTAMPTZ NOT NULL,
+  accepted_at   TIMESTAMPTZ,
+  accepted_by   UUID REFERENCES users(id) ON DELETE SET NULL,
+  revoked_at    TIMESTAMPTZ
+);
+
+-- one open invitation per email and namespace, a new one replaces it
+CREATE UNIQUE INDEX namespace_invitations_pending_idx ON namespace_invitations (namespace_id, email)
+  WHERE accepted_at IS NULL AND revoked_at IS NULL;
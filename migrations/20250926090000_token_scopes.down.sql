@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE api_keys DROP COLUMN scopes;
+
+ALTER TABLE user_sessions
+  DROP COLUMN namespace_ids,
+  DROP COLUMN scopes;
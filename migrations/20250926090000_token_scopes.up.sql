@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- Scope names like 'workflows:run'; NULL leaves a token unrestricted.
+ALTER TABLE user_sessions
+  ADD COLUMN scopes         TEXT[],
+  ADD COLUMN namespace_ids  UUID[];                                          -- namespaces the session's tokens can be used in
+
+-- A key is bound to its namespace already, only its scopes can be narrowed.
+ALTER TABLE api_keys
+  ADD COLUMN scopes  TEXT[];
@@ -0,0 +1,21 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS workflow_run_events;
+
+DROP INDEX IF EXISTS workflow_runs_namespace_idx;
+DROP INDEX IF EXISTS workflow_runs_due_idx;
+
+ALTER TABLE workflow_runs
+  ALTER COLUMN state DROP NOT NULL,
+  ALTER COLUMN state DROP DEFAULT,
+  DROP COLUMN claimed_until,
+  DROP COLUMN completed_at,
+  DROP COLUMN updated_at,
+  DROP COLUMN started_by,
+  DROP COLUMN failure,
+  DROP COLUMN result,
+  DROP COLUMN seq,
+  DROP COLUMN version,
+  DROP COLUMN workflow_type,
+  DROP COLUMN workflow_key,
+  DROP COLUMN workflow_id,
+  DROP COLUMN namespace_id;
@@ -0,0 +1,43 @@
+-- Add up migration script here
+-- Runs are driven by the engine now: they know what they run and keep their outcome.
+ALTER TABLE workflow_runs
+  ADD COLUMN namespace_id   UUID REFERENCES namespaces(id) ON DELETE CASCADE,
+  ADD COLUMN workflow_id    UUID REFERENCES workflows(id) ON DELETE SET NULL,
+  ADD COLUMN workflow_key   TEXT,
+  ADD COLUMN workflow_type  TEXT,                                          -- workflow inside the component
+  ADD COLUMN version        TEXT,
+  ADD COLUMN seq            BIGSERIAL,                                     -- numeric id the guest knows the run by
+  ADD COLUMN result         JSONB,
+  ADD COLUMN failure        JSONB,
+  ADD COLUMN started_by     UUID REFERENCES users(id) ON DELETE SET NULL,
+  ADD COLUMN updated_at     TIMESTAMPTZ NOT NULL DEFAULT now(),
+  ADD COLUMN completed_at   TIMESTAMPTZ,
+  ADD COLUMN claimed_until  TIMESTAMPTZ;                                   -- lease of the executor driving the run
+
+UPDATE workflow_runs wr
+SET namespace_id = w.namespace_id, workflow_id = w.id, workflow_key = w.key, workflow_type = w.key, version = wv.version
+FROM workflow_versions wv
+    JOIN workflows w
+    ON w.id = wv.workflow_id
+WHERE wv.id = wr.workflow_version_id;
+
+-- runs started by clients calling guest functions directly can't be resumed
+UPDATE workflow_runs SET state = 'failed', completed_at = now()
+WHERE state IS NULL OR state IN ('pending', 'running');
+
+ALTER TABLE workflow_runs
+  ALTER COLUMN state SET DEFAULT 'pending',
+  ALTER COLUMN state SET NOT NULL;
+
+CREATE INDEX workflow_runs_due_idx ON workflow_runs (started_at) WHERE state IN ('pending', 'running');
+CREATE INDEX workflow_runs_namespace_idx ON workflow_runs (namespace_id, id DESC);
+
+-- What happened in a run, in order; guests are replayed from it.
+CREATE TABLE workflow_run_events (
+  run_id      UUID NOT NULL REFERENCES workflow_runs(id) ON DELETE CASCADE,
+  seq         INT NOT NULL,
+  kind        TEXT NOT NULL,                                             -- run.started, activity.scheduled, activity.completed, ...
+  payload     JSONB NOT NULL DEFAULT '{}',
+  created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (run_id, seq)
+);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS idempotency_keys;
+
+DROP INDEX IF EXISTS workflow_runs_business_id_idx;
+DROP INDEX IF EXISTS workflow_runs_business_id_open_idx;
+
+ALTER TABLE workflow_runs
+  DROP COLUMN IF EXISTS reuse_policy,
+  DROP COLUMN IF EXISTS business_id;
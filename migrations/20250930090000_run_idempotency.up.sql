@@ -0,0 +1,26 @@
+-- Add up migration script here
+-- Callers name their runs with a business id; the reuse policy says whether another run may take it.
+ALTER TABLE workflow_runs
+  ADD COLUMN business_id   TEXT,
+  ADD COLUMN reuse_policy  TEXT NOT NULL DEFAULT 'reject_if_running';    -- reject_if_running, allow_if_failed, allow_always
+
+-- at most one open run per business id, unless it was started to run alongside others
+CREATE UNIQUE INDEX workflow_runs_business_id_open_idx ON workflow_runs (namespace_id, business_id)
+  WHERE business_id IS NOT NULL AND state IN ('pending', 'running') AND reuse_policy <> 'allow_always';
+CREATE INDEX workflow_runs_business_id_idx ON workflow_runs (namespace_id, business_id, id DESC)
+  WHERE business_id IS NOT NULL;
+
+-- First response to each Idempotency-Key, replayed when the same caller sends the key again.
+-- Expired keys are taken over by the next request using them.
+CREATE TABLE idempotency_keys (
+  user_id       UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  key           TEXT NOT NULL,
+  request_hash  TEXT NOT NULL,                                           -- sha256 of method, uri and body
+  status        SMALLINT,                                                -- null while the first request is in flight
+  content_type  TEXT,
+  location      TEXT,
+  body          BYTEA,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+  expires_at    TIMESTAMPTZ NOT NULL,
+  PRIMARY KEY (user_id, key)
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS workflow_runs_state_idx;
+DROP INDEX IF EXISTS workflow_runs_workflow_key_idx;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Runs are listed newest first, often for one workflow or in one state.
+CREATE INDEX workflow_runs_workflow_key_idx ON workflow_runs (namespace_id, workflow_key, id DESC);
+CREATE INDEX workflow_runs_state_idx ON workflow_runs (namespace_id, state, id DESC);
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS workflow_runs_search_attributes_idx;
+
+ALTER TABLE workflow_runs
+  DROP COLUMN IF EXISTS search_attributes;
+
+DROP TABLE IF EXISTS namespace_search_attributes;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- Typed attributes runs can be searched by, registered per namespace.
+CREATE TABLE namespace_search_attributes (
+  namespace_id  UUID NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+  name          TEXT NOT NULL,
+  kind          TEXT NOT NULL,                                           -- keyword, int, datetime, bool
+  created_by    UUID REFERENCES users(id) ON DELETE SET NULL,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (namespace_id, name)
+);
+
+-- values as the definitions say; datetimes are RFC 3339 strings in UTC
+ALTER TABLE workflow_runs
+  ADD COLUMN search_attributes JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX workflow_runs_search_attributes_idx ON workflow_runs USING GIN (search_attributes jsonb_path_ops);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS workflow_runs_parent_idx;
+
+ALTER TABLE workflow_runs
+  DROP COLUMN IF EXISTS cancel_requested_at,
+  DROP COLUMN IF EXISTS parent_run_id;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Runs can be started as children of another run; cancelling or terminating it reaches them too.
+ALTER TABLE workflow_runs
+  ADD COLUMN parent_run_id        UUID REFERENCES workflow_runs(id) ON DELETE SET NULL,
+  ADD COLUMN cancel_requested_at  TIMESTAMPTZ;                           -- graceful cancellation asked for, the guest decides what is left
+
+CREATE INDEX workflow_runs_parent_idx ON workflow_runs (parent_run_id) WHERE parent_run_id IS NOT NULL;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- The current attempt of each scheduled activity and the last heartbeat it sent, handed to the next attempt.
+CREATE TABLE activity_attempts (
+  run_id             UUID NOT NULL REFERENCES workflow_runs(id) ON DELETE CASCADE,
+  scheduled          BIGINT NOT NULL,                  -- seq of the activity.scheduled event
+  attempt            INT NOT NULL DEFAULT 1,
+  started_at         TIMESTAMPTZ NOT NULL DEFAULT now(),
+  last_heartbeat_at  TIMESTAMPTZ,
+  heartbeat_details  TEXT,
+  PRIMARY KEY (run_id, scheduled)
+);
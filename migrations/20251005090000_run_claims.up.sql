@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Each claim of a run gets a fresh token; executor writes name it, so one that lost its lease is refused.
+ALTER TABLE workflow_runs
+  ADD COLUMN claim_token UUID;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- An in-flight key is only held for a short while, so a request that died with it doesn't block retries until it expires.
+ALTER TABLE idempotency_keys
+  ADD COLUMN locked_until TIMESTAMPTZ NOT NULL DEFAULT now();
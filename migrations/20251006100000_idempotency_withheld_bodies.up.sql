@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Responses carrying credentials keep only their status; their keys can't be replayed.
+ALTER TABLE idempotency_keys
+  ADD COLUMN withheld BOOLEAN NOT NULL DEFAULT false;
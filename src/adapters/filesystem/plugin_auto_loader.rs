@@ -18,7 +18,8 @@ use std::sync::Arc;
 use tracing::{info, error, warn, debug};
 use anyhow::{Result, Context, bail, ensure};
 
-use crate::adapters::wasmtime::wit_runtime::WitPluginRuntime;
+use crate::adapters::wasmtime::wit_runtime::{PluginKey, WitPluginRuntime};
+use crate::core::domain::schema::{CompiledSchemas, WorkflowSchemas};
 
 #[derive(Debug)]
 pub enum PluginEvent {
@@ -317,24 +318,40 @@ impl PluginAutoLoader {
         
         ensure!(!wasm_bytes.is_empty(), "Plugin file is empty: {:?}", path);
         
-        // check WASM magic number
-        if wasm_bytes.len() >= 4 {
-            let magic = &wasm_bytes[0..4];
-            ensure!(magic == b"\0asm", "Invalid WASM file format: {:?}", path);
-        }
-        
         // calculate hash
         let digest = md5::compute(&wasm_bytes);
         info!("🔐 WASM MD5: {:x} (size: {} bytes)", digest, wasm_bytes.len());
         
+        let schemas = Self::read_schemas(path).await?;
+
         // load plugin
-        workflow_manager.load_wit_plugin(&plugin_name, &wasm_bytes).await
+        workflow_manager.load_wit_plugin(&PluginKey::local(&plugin_name), &wasm_bytes, schemas).await
             .with_context(|| format!("Failed to load plugin '{}' from {:?}", plugin_name, path))?;
         
         info!("✅ Plugin '{}' loaded successfully", plugin_name);
         Ok(())
     }
 
+    /// Reads the optional `<name>.schemas.json` file placed next to the wasm.
+    async fn read_schemas(path: &Path) -> Result<CompiledSchemas> {
+        let schemas_path = path.with_extension("schemas.json");
+
+        if !schemas_path.exists() {
+            return Ok(CompiledSchemas::default());
+        }
+
+        info!("📐 Loading schemas from: {:?}", schemas_path);
+
+        let raw = tokio::fs::read(&schemas_path).await
+            .with_context(|| format!("Failed to read schemas file: {:?}", schemas_path))?;
+
+        let schemas: WorkflowSchemas = serde_json::from_slice(&raw)
+            .with_context(|| format!("Invalid schemas file: {:?}", schemas_path))?;
+
+        schemas.compile()
+            .with_context(|| format!("Invalid schemas file: {:?}", schemas_path))
+    }
+
     fn extract_workflow_name(path: &Path) -> String {
         path.file_stem()
             .and_then(|s| s.to_str())
@@ -0,0 +1,359 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use anyhow::Context;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, OriginalUri, RawPathParams},
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use super::{auth::Claims, ApiError};
+use crate::core::{
+    domain::namespace::{NamespaceError, NamespaceRole, Permission},
+    domain::scope::{Scope, ScopeError},
+    domain::session::SessionOrigin,
+    services::namespace::NamespaceService,
+};
+
+/// Path parameter every namespace-scoped route uses for the namespace slug.
+const NAMESPACE_PARAM: &str = "slug";
+
+/// Path segment the namespace slug follows, `/api/ns/{slug}/...`.
+const NAMESPACE_SEGMENT: &str = "ns";
+
+/// The caller's role in the namespace addressed by the request path.
+///
+/// Extracting it authenticates the caller, resolves the slug and checks membership;
+/// handlers then call [`NamespaceAccess::require`] for the permission the endpoint needs,
+/// which a restricted token must also hold the matching [`Scope`] for.
+/// A slug the namespace had before a rename is answered with a permanent redirect.
+pub struct NamespaceAccess {
+    pub claims: Claims,
+    pub namespace_id: Uuid,
+    pub slug: String,
+    pub role: NamespaceRole,
+    pub archived: bool,
+}
+
+impl NamespaceAccess {
+    pub fn require(&self, permission: Permission) -> Result<(), ApiError> {
+        self.require_scoped(permission, Scope::for_permission(permission))
+    }
+
+    /// Like [`NamespaceAccess::require`], for endpoints whose scope differs from their permission's.
+    pub fn require_scoped(&self, permission: Permission, scope: Scope) -> Result<(), ApiError> {
+        if !self.claims.scopes().allows(scope) {
+            return Err(ApiError::forbidden(ScopeError::Missing(scope).to_string()));
+        }
+
+        if !self.role.allows(permission) {
+            return Err(ApiError::forbidden(format!(
+                "Role '{:?}' is not allowed to {:?} in this namespace", self.role, permission,
+            )));
+        }
+
+        if self.archived && permission.is_write() {
+            return Err(ApiError::new(StatusCode::CONFLICT, NamespaceError::Archived.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.claims.get_user_id()
+    }
+}
+
+impl<S> FromRequestParts<S> for NamespaceAccess
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let slug = params.iter()
+            .find(|(name, _)| *name == NAMESPACE_PARAM)
+            .map(|(_, value)| value.to_string())
+            .ok_or_else(|| ApiError::internal_error("Route has no namespace parameter").into_response())?;
+
+        let namespace_service = parts.extensions.get::<Arc<NamespaceService>>()
+            .cloned()
+            .ok_or_else(|| ApiError::internal_error("Namespace service is not configured").into_response())?;
+
+        let resolved = namespace_service.resolve_slug(&slug)
+            .await
+            .map_err(|e| ApiError::from(e).into_response())?;
+        let namespace = resolved.namespace;
+        let namespace_id = namespace.id;
+
+        // super admins act as owners everywhere
+        let role = if claims.is_super_admin() {
+            Some(NamespaceRole::Owner)
+        } else {
+            namespace_service.ns_role_by_uid(claims.get_user_id(), namespace_id)
+                .await
+                .map_err(|e| ApiError::from(e).into_response())?
+        };
+
+        let role = role.ok_or_else(|| {
+            ApiError::forbidden("You are not a member of this namespace").into_response()
+        })?;
+
+        if !claims.scopes().allows_namespace(namespace_id) {
+            return Err(ApiError::forbidden(ScopeError::NamespaceNotAllowed.to_string()).into_response());
+        }
+
+        // only members learn where a renamed namespace went
+        if resolved.alias {
+            return Err(redirect_to_slug(parts, &slug, &namespace.slug));
+        }
+
+        let archived = namespace.is_archived();
+
+        Ok(NamespaceAccess {
+            claims,
+            namespace_id,
+            slug: namespace.slug,
+            role,
+            archived,
+        })
+    }
+}
+
+/// A caller with the `super_admin` flag, which every `/api/admin` route requires.
+///
+/// API keys never qualify, their service accounts can't be promoted, and neither do restricted tokens.
+pub struct SuperAdmin {
+    pub claims: Claims,
+}
+
+impl SuperAdmin {
+    pub fn user_id(&self) -> Uuid {
+        self.claims.get_user_id()
+    }
+}
+
+impl<S> FromRequestParts<S> for SuperAdmin
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        if !claims.is_super_admin() || claims.is_service_account() {
+            return Err(ApiError::forbidden("Only super admins can use the administration API").into_response());
+        }
+
+        claims.require_unrestricted().map_err(IntoResponse::into_response)?;
+
+        Ok(SuperAdmin { claims })
+    }
+}
+
+/// Proxies whose `X-Forwarded-For` is believed; from anyone else it could name any address.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpAddr>);
+
+impl TrustedProxies {
+    /// Parses a comma separated list of IP addresses.
+    pub fn parse(list: Option<&str>) -> Result<Self, anyhow::Error> {
+        let proxies = list.unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| ip.parse().with_context(|| format!("Invalid trusted proxy address '{}'", ip)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(TrustedProxies(proxies))
+    }
+
+    /// The client behind the proxies: forwarded addresses are walked back from the nearest hop,
+    /// the first one that isn't a trusted proxy is the client.
+    fn client_ip(&self, peer: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        let mut client = peer;
+
+        for hop in forwarded_for.unwrap_or_default().rsplit(',') {
+            if !self.0.contains(&client) {
+                break;
+            }
+            let Ok(ip) = hop.trim().parse() else {
+                break;
+            };
+            client = ip;
+        }
+
+        client
+    }
+}
+
+/// Where a request comes from, as sessions record it and sign-ins are limited by.
+///
+/// The address is the peer's, unless the peer is a trusted proxy that forwarded the request.
+pub struct ClientOrigin(pub SessionOrigin);
+
+impl<S> FromRequestParts<S> for ClientOrigin
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = |name| parts.headers.get(name)
+            .and_then(|v| v.to_str().ok());
+
+        let proxies = parts.extensions.get::<Arc<TrustedProxies>>().cloned().unwrap_or_default();
+        let ip = parts.extensions.get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(peer)| proxies.client_ip(peer.ip(), header("x-forwarded-for")));
+
+        Ok(ClientOrigin(SessionOrigin {
+            user_agent: header(header::USER_AGENT.as_str()).map(str::to_string),
+            ip: ip.map(|ip| ip.to_string()),
+        }))
+    }
+}
+
+/// Same request path and query with the old slug replaced by the current one.
+fn redirect_to_slug(parts: &Parts, old: &str, current: &str) -> Response {
+    // nested routers see the path without their prefix, the original one is needed here
+    let uri = parts.extensions.get::<OriginalUri>()
+        .map(|OriginalUri(uri)| uri)
+        .unwrap_or(&parts.uri);
+
+    let mut replaced = false;
+    let mut segments: Vec<&str> = uri.path().split('/').collect();
+
+    for i in 1..segments.len() {
+        if !replaced && segments[i - 1] == NAMESPACE_SEGMENT && segments[i] == old {
+            segments[i] = current;
+            replaced = true;
+        }
+    }
+
+    let mut location = segments.join("/");
+    if let Some(query) = uri.query() {
+        location.push('?');
+        location.push_str(query);
+    }
+
+    (StatusCode::PERMANENT_REDIRECT, [(header::LOCATION, location)]).into_response()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access(role: NamespaceRole, archived: bool, scopes: Option<&[&str]>) -> NamespaceAccess {
+        let claims = serde_json::from_value(serde_json::json!({
+            "uid": Uuid::now_v7(),
+            "email": "member@example.com",
+            "super_admin": false,
+            "exp": i64::MAX,
+            "scopes": scopes,
+        })).unwrap();
+
+        NamespaceAccess { claims, namespace_id: Uuid::now_v7(), slug: "acme-ops".to_string(), role, archived }
+    }
+
+    fn status(access: &NamespaceAccess, permission: Permission) -> Option<StatusCode> {
+        access.require(permission).err().map(|e| e.status)
+    }
+
+    #[test]
+    fn viewer_cannot_start_run() {
+        // start_run
+        assert_eq!(status(&access(NamespaceRole::Viewer, false, None), Permission::Run), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Editor, false, None), Permission::Run), None);
+    }
+
+    #[test]
+    fn viewer_cannot_stop_run() {
+        // cancel_run, terminate_run
+        assert_eq!(status(&access(NamespaceRole::Viewer, false, None), Permission::Stop), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Editor, false, None), Permission::Stop), None);
+    }
+
+    #[test]
+    fn viewer_cannot_upload_or_unload_workflow() {
+        // create_workflow, remove_plugin_endpoint
+        assert_eq!(status(&access(NamespaceRole::Viewer, false, None), Permission::Upload), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Editor, false, None), Permission::Upload), None);
+    }
+
+    #[test]
+    fn editor_cannot_manage_members() {
+        // add_member, create_invitation, create_api_key
+        assert_eq!(status(&access(NamespaceRole::Editor, false, None), Permission::ManageMembers), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Admin, false, None), Permission::ManageMembers), None);
+    }
+
+    #[test]
+    fn editor_cannot_read_audit_log() {
+        // get_namespace_audit, rename_namespace, create_search_attribute
+        assert_eq!(status(&access(NamespaceRole::Editor, false, None), Permission::Administer), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Admin, false, None), Permission::Administer), None);
+    }
+
+    #[test]
+    fn admin_cannot_delete_namespace() {
+        // delete_namespace
+        assert_eq!(status(&access(NamespaceRole::Admin, false, None), Permission::Delete), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status(&access(NamespaceRole::Owner, false, None), Permission::Delete), None);
+    }
+
+    #[test]
+    fn every_member_can_read() {
+        // get_workflows, get_run
+        for role in [NamespaceRole::Owner, NamespaceRole::Admin, NamespaceRole::Editor, NamespaceRole::Viewer] {
+            assert_eq!(status(&access(role, false, None), Permission::Read), None);
+        }
+    }
+
+    #[test]
+    fn archived_namespace_refuses_writes() {
+        let owner = access(NamespaceRole::Owner, true, None);
+        assert_eq!(status(&owner, Permission::Run), Some(StatusCode::CONFLICT));
+        assert_eq!(status(&owner, Permission::Upload), Some(StatusCode::CONFLICT));
+        assert_eq!(status(&owner, Permission::Read), None);
+    }
+
+    #[test]
+    fn archived_namespace_still_stops_runs() {
+        let editor = access(NamespaceRole::Editor, true, None);
+        assert_eq!(status(&editor, Permission::Stop), None);
+    }
+
+    #[test]
+    fn restricted_token_needs_the_scope_as_well_as_the_role() {
+        let owner = access(NamespaceRole::Owner, false, Some(&["workflows:read"]));
+        assert_eq!(status(&owner, Permission::Read), None);
+        assert_eq!(status(&owner, Permission::Run), Some(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use axum::{
+    Json as JsonResponse,
+    Extension,
+    http::StatusCode,
+    response::IntoResponse,
+    extract::{Json, Path, Query},
+};
+use uuid::Uuid;
+
+use super::ApiError;
+use crate::{
+    adapters::http::access::SuperAdmin,
+    core::{
+        domain::admin::{ForceCancelRequest, NamespaceStatsFilter, SuperAdminUpdate, UnloadRequest, UserFilter, UserStatusUpdate},
+        services::admin::AdminService,
+    },
+};
+
+pub(super) async fn get_users(
+    _admin: SuperAdmin,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    Query(filter): Query<UserFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = admin_service.users(&filter).await?;
+
+    Ok(JsonResponse(page))
+}
+
+pub(super) async fn update_user_status(
+    admin: SuperAdmin,
+    Path(user_id): Path<Uuid>,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    Json(req): Json<UserStatusUpdate>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = admin_service.set_disabled(admin.user_id(), user_id, req.disabled).await?;
+
+    Ok(JsonResponse(user))
+}
+
+pub(super) async fn update_super_admin(
+    admin: SuperAdmin,
+    Path(user_id): Path<Uuid>,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    Json(req): Json<SuperAdminUpdate>,
+) -> Result<impl IntoResponse, ApiError> {
+    let user = admin_service.set_super_admin(admin.user_id(), user_id, req.super_admin).await?;
+
+    Ok(JsonResponse(user))
+}
+
+pub(super) async fn get_all_namespaces(
+    _admin: SuperAdmin,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    Query(filter): Query<NamespaceStatsFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stats = admin_service.namespaces(&filter).await?;
+
+    Ok(JsonResponse(stats))
+}
+
+pub(super) async fn force_cancel_run(
+    admin: SuperAdmin,
+    Path(run_id): Path<Uuid>,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    req: Option<Json<ForceCancelRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+
+    let run = admin_service.cancel_run(admin.user_id(), run_id, req.reason.as_deref()).await?;
+
+    Ok(JsonResponse(run))
+}
+
+pub(super) async fn unload_plugins(
+    admin: SuperAdmin,
+    Extension(admin_service): Extension<Arc<AdminService>>,
+    Json(req): Json<UnloadRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    admin_service.unload_plugins(admin.user_id(), &req).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
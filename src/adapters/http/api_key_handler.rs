@@ -0,0 +1,82 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use axum::{
+    Json as JsonResponse,
+    Extension,
+    http::StatusCode,
+    response::IntoResponse,
+    extract::{Json, Path},
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{idempotency::carrying_credentials, ApiError};
+use crate::{
+    adapters::http::access::NamespaceAccess,
+    core::{
+        domain::{api_key::NewApiKey, namespace::Permission, scope::{ScopeError, TokenScopes}},
+        services::api_key::ApiKeyService,
+    },
+};
+
+pub(super) async fn get_api_keys(
+    access: NamespaceAccess,
+    Extension(api_key_service): Extension<Arc<ApiKeyService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    let keys = api_key_service.find_all(access.namespace_id).await?;
+
+    Ok(JsonResponse(keys))
+}
+
+/// The response is the only time the token is shown.
+pub(super) async fn create_api_key(
+    access: NamespaceAccess,
+    Extension(api_key_service): Extension<Arc<ApiKeyService>>,
+    Json(req): Json<NewApiKey>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+    req.validate()?;
+
+    // a key minting keys would outlive its own revocation
+    if access.claims.is_service_account() {
+        return Err(ApiError::forbidden("API keys can not create API keys"));
+    }
+
+    // a restricted token can not mint a key that does more than itself
+    let asked = TokenScopes { scopes: req.scopes.clone(), namespaces: None };
+    let granted = TokenScopes { scopes: access.claims.scopes().scopes.clone(), namespaces: None };
+    if !asked.is_within(&granted) {
+        return Err(ApiError::forbidden(ScopeError::Escalation.to_string()));
+    }
+
+    let created = api_key_service.create(access.user_id(), access.role, access.namespace_id, &req).await?;
+
+    Ok(carrying_credentials((StatusCode::CREATED, JsonResponse(created))))
+}
+
+pub(super) async fn revoke_api_key(
+    access: NamespaceAccess,
+    Path((_, key_id)): Path<(String, Uuid)>,
+    Extension(api_key_service): Extension<Arc<ApiKeyService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    let key = api_key_service.revoke(access.user_id(), access.namespace_id, key_id).await?;
+
+    Ok(JsonResponse(key))
+}
@@ -0,0 +1,107 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use serde::Deserialize;
+use uuid::Uuid;
+use axum::{
+    Json as JsonResponse,
+    Extension,
+    response::IntoResponse,
+    extract::Query,
+};
+
+use super::ApiError;
+use crate::{
+    adapters::http::access::{NamespaceAccess, SuperAdmin},
+    core::{
+        domain::{audit::{AuditFilter, GLOBAL_CHAIN}, namespace::Permission},
+        services::audit::AuditService,
+    },
+};
+
+pub(super) async fn get_namespace_audit(
+    access: NamespaceAccess,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+    Query(mut filter): Query<AuditFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    filter.namespace_id = Some(access.namespace_id);
+
+    let page = audit_service.query(&filter).await?;
+
+    Ok(JsonResponse(page))
+}
+
+pub(super) async fn verify_namespace_audit(
+    access: NamespaceAccess,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    let verification = audit_service.verify(access.namespace_id).await?;
+
+    Ok(JsonResponse(verification))
+}
+
+pub(super) async fn get_namespace_checkpoints(
+    access: NamespaceAccess,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    let export = audit_service.export_checkpoints(Some(access.namespace_id)).await?;
+
+    Ok(JsonResponse(export))
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ChainParams {
+    /// Namespace id the chain was written for, the global chain when absent.
+    chain: Option<Uuid>,
+}
+
+/// Entries of all namespaces and the ones that belong to none, e.g. signups.
+pub(super) async fn get_global_audit(
+    _admin: SuperAdmin,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+    Query(filter): Query<AuditFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    let page = audit_service.query(&filter).await?;
+
+    Ok(JsonResponse(page))
+}
+
+/// Any chain, including the ones of deleted namespaces.
+pub(super) async fn verify_global_audit(
+    _admin: SuperAdmin,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+    Query(params): Query<ChainParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let verification = audit_service.verify(params.chain.unwrap_or(GLOBAL_CHAIN)).await?;
+
+    Ok(JsonResponse(verification))
+}
+
+/// Checkpoints of every chain unless one is asked for.
+pub(super) async fn get_global_checkpoints(
+    _admin: SuperAdmin,
+    Extension(audit_service): Extension<Arc<AuditService>>,
+    Query(params): Query<ChainParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let export = audit_service.export_checkpoints(params.chain).await?;
+
+    Ok(JsonResponse(export))
+}
@@ -23,18 +23,21 @@ use axum_extra::{
 };
 use anyhow::Result;
 use chrono::{Utc, Duration};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::fmt::Display;
-use std::sync::LazyLock;
+use std::sync::Arc;
+use tracing::error;
 
+use crate::core::domain::api_key::API_KEY_PREFIX;
+use crate::core::domain::scope::TokenScopes;
+use crate::core::domain::session::IssuedSession;
 use crate::core::domain::user::User;
+use crate::core::services::api_key::ApiKeyService;
+use crate::core::services::session::SessionService;
 
-static KEYS: LazyLock<Keys> = LazyLock::new(|| {
-    let secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-    Keys::new(secret.as_bytes())
-});
+use super::token_keys::TokenKeys;
+use super::ApiError;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -51,32 +54,66 @@ pub struct Claims {
     email: String,
     super_admin: bool,
     exp: i64,
+    /// Session the access token was issued for.
+    #[serde(default)]
+    sid: Option<uuid::Uuid>,
+    /// Id of the access token itself, what the denylist holds.
+    #[serde(default)]
+    jti: Option<uuid::Uuid>,
+    /// Scopes and namespaces the token is limited to, absent for unrestricted tokens.
+    #[serde(flatten)]
+    limits: TokenScopes,
+    /// Set when the request was made with an API key, never part of a JWT.
+    #[serde(skip)]
+    api_key: Option<uuid::Uuid>,
 }
 
 impl Claims {
     pub fn get_user_id(&self) -> uuid::Uuid {
         self.uid
     } 
+
+    pub fn is_super_admin(&self) -> bool {
+        self.super_admin
+    }
+
+    /// Whether the request was made with an API key; its service account is [`Claims::get_user_id`].
+    pub fn is_service_account(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    pub fn scopes(&self) -> &TokenScopes {
+        &self.limits
+    }
+
+    /// Restricted tokens only reach what their scopes name; account and admin endpoints refuse them.
+    pub fn require_unrestricted(&self) -> Result<(), ApiError> {
+        if self.limits.is_restricted() {
+            return Err(ApiError::forbidden("This endpoint can not be used with a restricted token"));
+        }
+
+        Ok(())
+    }
+
+    pub fn session_id(&self) -> Option<uuid::Uuid> {
+        self.sid
+    }
+
+    pub fn token_id(&self) -> Option<uuid::Uuid> {
+        self.jti
+    }
+
+    pub fn expires_at(&self) -> Option<chrono::DateTime<Utc>> {
+        chrono::DateTime::from_timestamp(self.exp, 0)
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct AuthBody {
     access_token: String,
     token_type: String,
-}
-
-struct Keys {
-    encoding: EncodingKey,
-    decoding: DecodingKey,
-}
-
-impl Keys {
-    fn new(secret: &[u8]) -> Self {
-        Self {
-            encoding: EncodingKey::from_secret(secret),
-            decoding: DecodingKey::from_secret(secret),
-        }
-    }
+    expires_in: i64,
+    refresh_token: String,
 }
 
 impl Display for Claims {
@@ -86,10 +123,12 @@ impl Display for Claims {
 }
 
 impl AuthBody {
-    fn new(access_token: String) -> Self {
+    fn new(access_token: String, expires_in: i64, refresh_token: String) -> Self {
         Self {
             access_token,
             token_type: "Bearer".to_string(),
+            expires_in,
+            refresh_token,
         }
     }
 }
@@ -106,14 +145,71 @@ where
             .extract::<TypedHeader<Authorization<Bearer>>>()
             .await
             .map_err(|_| AuthError::InvalidToken)?;
+
+        if bearer.token().starts_with(API_KEY_PREFIX) {
+            return api_key_claims(parts, bearer.token()).await;
+        }
+
+        let keys = parts.extensions.get::<Arc<TokenKeys>>()
+            .ok_or(AuthError::InvalidToken)?;
+
         // Decode the user data
-        let token_data = decode::<Claims>(bearer.token(), &KEYS.decoding, &Validation::default())
+        let claims = keys.decode::<Claims>(bearer.token(), true)
             .map_err(|_| AuthError::InvalidToken)?;
 
-        Ok(token_data.claims)
+        // tokens issued before sessions existed can not be revoked, so they are not accepted
+        let (Some(sid), Some(jti)) = (claims.sid, claims.jti) else {
+            return Err(AuthError::InvalidToken);
+        };
+
+        let sessions = parts.extensions.get::<Arc<SessionService>>()
+            .ok_or(AuthError::InvalidToken)?;
+
+        let revoked = sessions.is_revoked(sid, jti)
+            .await
+            .map_err(|e| {
+                error!("❌ Failed to check token revocation: {}", e);
+                AuthError::InvalidToken
+            })?;
+
+        if revoked {
+            return Err(AuthError::InvalidToken);
+        }
+
+        Ok(claims)
     }
 }
 
+/// Decodes one of our access tokens without checking whether it expired or was revoked.
+pub fn decode_ignoring_expiry(keys: &TokenKeys, token: &str) -> Option<Claims> {
+    keys.decode::<Claims>(token, false).ok()
+}
+
+async fn api_key_claims(parts: &Parts, token: &str) -> Result<Claims, AuthError> {
+    let api_keys = parts.extensions.get::<Arc<ApiKeyService>>()
+        .ok_or(AuthError::InvalidToken)?;
+
+    let credentials = api_keys.authenticate(token)
+        .await
+        .map_err(|e| {
+            error!("❌ Failed to authenticate API key: {}", e);
+            AuthError::InvalidToken
+        })?
+        .ok_or(AuthError::InvalidToken)?;
+    let limits = credentials.token_scopes();
+
+    Ok(Claims {
+        uid: credentials.user_id,
+        email: credentials.email,
+        super_admin: false,
+        exp: credentials.expires_at.map(|exp| exp.timestamp()).unwrap_or(i64::MAX),
+        sid: None,
+        jti: None,
+        limits,
+        api_key: Some(credentials.id),
+    })
+}
+
 impl IntoResponse for AuthError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
@@ -129,20 +225,25 @@ impl IntoResponse for AuthError {
     }
 }
 
-pub async fn generate_auth_header(u: User) -> Result<Json<AuthBody>, AuthError> {
-    let expiration = Utc::now() + Duration::days(7);
+/// Issues an access token for a session, handed out together with the session's refresh token.
+pub async fn generate_auth_header(keys: &TokenKeys, u: User, issued: &IssuedSession, ttl: Duration) -> Result<Json<AuthBody>, AuthError> {
+    let expiration = Utc::now() + ttl;
 
     let claims = Claims {
         uid: u.id,
         email: u.email,
         super_admin: u.super_admin,
         exp: expiration.timestamp(),
+        sid: Some(issued.session.id),
+        jti: Some(uuid::Uuid::now_v7()),
+        limits: issued.session.token_scopes(),
+        api_key: None,
     };
 
     // Create the authorization token
-    let token = encode(&Header::default(), &claims, &KEYS.encoding)
+    let token = keys.encode(&claims)
         .map_err(|_| AuthError::TokenCreation)?;
 
     // Send the authorized token
-    Ok(Json(AuthBody::new(token)))
-}
\ No newline at end of file
+    Ok(Json(AuthBody::new(token, ttl.num_seconds(), issued.refresh_token.clone())))
+}
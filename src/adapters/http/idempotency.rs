@@ -0,0 +1,201 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::{auth::Claims, ApiError, BODY_LIMIT_BYTES};
+use crate::core::{domain::idempotency::StoredResponse, services::idempotency::IdempotencyService};
+
+static IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+/// Set on responses that were replayed rather than handled again.
+static IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Responses larger than this aren't kept; the key is freed so a retry does the work again.
+const MAX_STORED_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Makes mutating requests that carry an `Idempotency-Key` safe to retry: the first one
+/// is handled and its response kept, later ones with the same key and request get that
+/// response back. Keys are per caller; requests that don't authenticate pass through,
+/// the handler turns them away. Responses marked with [`carrying_credentials`] aren't
+/// kept, retries of their requests are refused instead.
+pub(super) async fn idempotent(req: Request, next: Next) -> Response {
+    if !matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return next.run(req).await;
+    }
+    let Some(key) = req.headers().get(&IDEMPOTENCY_KEY) else {
+        return next.run(req).await;
+    };
+    let Ok(key) = key.to_str().map(str::to_string) else {
+        return ApiError::bad_request("Invalid idempotency key: it can only hold visible ASCII characters").into_response();
+    };
+
+    let (mut parts, body) = req.into_parts();
+
+    let Ok(claims) = Claims::from_request_parts(&mut parts, &()).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    let Some(service) = parts.extensions.get::<Arc<IdempotencyService>>().cloned() else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    let user_id = claims.get_user_id();
+
+    let Ok(body) = to_bytes(body, BODY_LIMIT_BYTES).await else {
+        return ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+    };
+
+    match service.begin(user_id, &key, &request_hash(&parts.method, parts.uri.to_string(), &body)).await {
+        Ok(None) => {}
+        Ok(Some(stored)) => return replay(stored),
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    // dropped along with this future when the client goes away mid-request
+    let in_flight = InFlight {
+        service: Some(service),
+        user_id,
+        key,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+
+    // server errors are worth another try, so they aren't kept
+    let body = match to_bytes(body, MAX_STORED_RESPONSE_BYTES).await {
+        Ok(body) if !parts.status.is_server_error() => body,
+        Ok(body) => {
+            in_flight.release().await;
+            return Response::from_parts(parts, Body::from(body));
+        }
+        Err(e) => {
+            warn!("⚠️  Response to idempotency key '{}' could not be kept: {}", in_flight.key, e);
+            in_flight.release().await;
+            return ApiError::internal_error("Response too large to be kept for the idempotency key").into_response();
+        }
+    };
+
+    let header_value = |name: &HeaderName| parts.headers.get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // credentials are shown once, a replay would hand them out again to whoever holds the key
+    let withheld = header_value(&header::CACHE_CONTROL).is_some_and(|v| v.contains("no-store"));
+    let stored = if withheld {
+        StoredResponse {
+            status: parts.status.as_u16(),
+            content_type: None,
+            location: None,
+            body: Vec::new(),
+            withheld,
+        }
+    } else {
+        StoredResponse {
+            status: parts.status.as_u16(),
+            content_type: header_value(&header::CONTENT_TYPE),
+            location: header_value(&header::LOCATION),
+            body: body.to_vec(),
+            withheld,
+        }
+    };
+    in_flight.complete(&stored).await;
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// A key claimed for a request that is being handled. Unless its response is kept or the key
+/// released, dropping it frees the key, so a retry needn't wait for the in-flight lock to run out.
+struct InFlight {
+    /// Taken once the key is settled.
+    service: Option<Arc<IdempotencyService>>,
+    user_id: Uuid,
+    key: String,
+}
+
+impl InFlight {
+    async fn complete(mut self, stored: &StoredResponse) {
+        let Some(service) = self.service.take() else {
+            return;
+        };
+
+        if let Err(e) = service.complete(self.user_id, &self.key, stored).await {
+            error!("❌ Failed to keep the response to idempotency key '{}': {}", self.key, e);
+            release(&service, self.user_id, &self.key).await;
+        }
+    }
+
+    async fn release(mut self) {
+        if let Some(service) = self.service.take() {
+            release(&service, self.user_id, &self.key).await;
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        let Some(service) = self.service.take() else {
+            return;
+        };
+        let (user_id, key) = (self.user_id, std::mem::take(&mut self.key));
+
+        tokio::spawn(async move {
+            release(&service, user_id, &key).await;
+        });
+    }
+}
+
+async fn release(service: &IdempotencyService, user_id: Uuid, key: &str) {
+    if let Err(e) = service.release(user_id, key).await {
+        error!("❌ Failed to free idempotency key '{}': {}", key, e);
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+
+    let headers = response.headers_mut();
+    for (name, value) in [(header::CONTENT_TYPE, stored.content_type), (header::LOCATION, stored.location)] {
+        if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED.clone(), HeaderValue::from_static("true"));
+
+    response
+}
+
+/// Marks a response that hands out credentials: nothing on the way may store it,
+/// the idempotency keys included.
+pub(super) fn carrying_credentials(response: impl IntoResponse) -> Response {
+    ([(header::CACHE_CONTROL, "no-store")], response).into_response()
+}
+
+/// Tells requests apart, so a key sent again with another request is caught.
+fn request_hash(method: &Method, uri: String, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b" ");
+    hasher.update(uri);
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
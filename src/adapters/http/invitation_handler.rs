@@ -0,0 +1,79 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use axum::{
+    Json as JsonResponse,
+    Extension,
+    http::StatusCode,
+    response::IntoResponse,
+    extract::{Json, Path},
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{idempotency::carrying_credentials, ApiError};
+use crate::{
+    adapters::http::access::NamespaceAccess,
+    core::{
+        domain::{invitation::{AcceptInvitation, NewInvitation}, namespace::Permission},
+        services::invitation::InvitationService,
+    },
+};
+
+pub(super) async fn get_invitations(
+    access: NamespaceAccess,
+    Extension(invitation_service): Extension<Arc<InvitationService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    let invitations = invitation_service.find_pending(access.namespace_id).await?;
+
+    Ok(JsonResponse(invitations))
+}
+
+pub(super) async fn create_invitation(
+    access: NamespaceAccess,
+    Extension(invitation_service): Extension<Arc<InvitationService>>,
+    Json(req): Json<NewInvitation>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+    req.validate()?;
+
+    let created = invitation_service.create(access.user_id(), access.role, access.namespace_id, &req).await?;
+
+    Ok(carrying_credentials((StatusCode::CREATED, JsonResponse(created))))
+}
+
+pub(super) async fn revoke_invitation(
+    access: NamespaceAccess,
+    Path((_, invitation_id)): Path<(String, Uuid)>,
+    Extension(invitation_service): Extension<Arc<InvitationService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    invitation_service.revoke(access.user_id(), access.namespace_id, invitation_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Needs no sign-in: the token is the proof, and the account may not exist yet.
+pub(super) async fn accept_invitation(
+    Extension(invitation_service): Extension<Arc<InvitationService>>,
+    Json(req): Json<AcceptInvitation>,
+) -> Result<impl IntoResponse, ApiError> {
+    let accepted = invitation_service.accept(&req.token).await?;
+
+    Ok(carrying_credentials(JsonResponse(accepted)))
+}
@@ -13,60 +13,118 @@
 
 mod workflow_handler;
 mod user_handler;
+mod sso_handler;
 mod namespace_handler;
+mod audit_handler;
+mod api_key_handler;
+mod admin_handler;
+mod invitation_handler;
+mod run_handler;
+mod idempotency;
 mod auth;
+mod access;
+pub mod token_keys;
 
 use axum::{
     Extension,
-    routing::{get, post, delete},
+    routing::{get, post, patch, delete},
     http::StatusCode,
     response::Json,
     response::{IntoResponse, Response},
     Router,
     extract::{DefaultBodyLimit},
+    middleware,
 };
 use tower_http::trace::TraceLayer;
 use aws_sdk_s3::client::Client as s3c;
 use serde_json::json;
 use tokio::sync::oneshot;
 use tracing::info;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use anyhow::{Result as AnyhowResult};
 
 use crate::{
-    adapters::wasmtime::wit_runtime::WitPluginRuntime, 
-    core::services::{namespace::NamespaceService, user::UserService, workflow::WorkflowService}, 
+    adapters::wasmtime::wit_runtime::{InvalidComponent, WitPluginRuntime}, 
+    core::domain::api_key::ApiKeyError,
+    core::domain::session::SessionError,
+    core::domain::scope::ScopeError,
+    core::domain::user::{RegistrationError, SignInError},
+    core::domain::oidc::OidcError,
+    core::domain::admin::AdminError,
+    core::domain::invitation::InvitationError,
+    core::domain::run::RunError,
+    core::domain::idempotency::IdempotencyError,
+    core::domain::search::SearchAttributeError,
+    core::domain::namespace::{MembershipError, NamespaceError},
+    core::domain::schema::{SchemaViolations, Violation},
+    core::services::{
+        admin::AdminService, api_key::ApiKeyService, audit::AuditService, idempotency::IdempotencyService, invitation::InvitationService, namespace::NamespaceService,
+        run::RunService, session::SessionService, sso::SsoService, user::UserService, workflow::WorkflowService,
+    }, 
     infra::config::AppConfig,
 };
 
+use token_keys::TokenKeys;
+use access::TrustedProxies;
+
 use super::http::{
-    workflow_handler::{run_workflow, remove_plugin_endpoint, create_workflow, get_workflows},
-    user_handler::{signup, signin},
-    namespace_handler::{create_namespace, get_namespaces},
+    workflow_handler::{remove_plugin_endpoint, create_workflow, get_workflows, get_version_schemas, get_version_manifest},
+    user_handler::{
+        signup, signin, refresh, signout, rotate_token, start_scoped_session,
+        get_sessions, revoke_session, revoke_all_sessions, revoke_token,
+    },
+    sso_handler::{oidc_login, oidc_callback},
+    run_handler::{start_run, get_run, get_runs, cancel_run, terminate_run, get_search_attributes, create_search_attribute, remove_search_attribute},
+    invitation_handler::{get_invitations, create_invitation, revoke_invitation, accept_invitation},
+    admin_handler::{get_users, update_user_status, update_super_admin, get_all_namespaces, force_cancel_run, unload_plugins},
+    api_key_handler::{get_api_keys, create_api_key, revoke_api_key},
+    audit_handler::{
+        get_namespace_audit, verify_namespace_audit, get_namespace_checkpoints,
+        get_global_audit, verify_global_audit, get_global_checkpoints,
+    },
+    namespace_handler::{
+        create_namespace, get_namespaces, get_namespace,
+        rename_namespace, archive_namespace, unarchive_namespace, delete_namespace,
+        get_members, add_member, update_member, remove_member, transfer_ownership, leave_namespace,
+    },
 };
 
+/// Largest request body accepted, ~30mb.
+const BODY_LIMIT_BYTES: usize = 30485760;
+
 #[derive(Debug)]
 pub struct ApiError {
     pub status: StatusCode,
     pub message: String,
     pub details: Option<String>,
+    pub violations: Option<Vec<Violation>>,
 }
 
 impl ApiError {
-    pub fn bad_request(message: impl Into<String>) -> Self {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
         Self {
-            status: StatusCode::BAD_REQUEST,
+            status,
             message: message.into(),
             details: None,
+            violations: None,
         }
     }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
     
     pub fn internal_error(message: impl Into<String>) -> Self {
-        Self {
-            status: StatusCode::INTERNAL_SERVER_ERROR,
-            message: message.into(),
-            details: None,
-        }
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
     }
 
         pub fn with_details(mut self, details: impl Into<String>) -> Self {
@@ -75,8 +133,136 @@ impl ApiError {
     }
 }
 
+impl From<SchemaViolations> for ApiError {
+    fn from(err: SchemaViolations) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            message: err.to_string(),
+            details: None,
+            violations: Some(err.violations),
+        }
+    }
+}
+
 impl From<anyhow::Error> for ApiError {
     fn from(err: anyhow::Error) -> Self {
+        if let Some(violations) = err.downcast_ref::<SchemaViolations>() {
+            return violations.clone().into();
+        }
+
+        if let Some(invalid) = err.downcast_ref::<InvalidComponent>() {
+            return Self::new(StatusCode::UNPROCESSABLE_ENTITY, invalid.to_string());
+        }
+
+        if let Some(membership) = err.downcast_ref::<MembershipError>() {
+            let status = match membership {
+                MembershipError::UserNotFound(_) | MembershipError::NotMember => StatusCode::NOT_FOUND,
+                MembershipError::AlreadyMember | MembershipError::LastOwner => StatusCode::CONFLICT,
+                MembershipError::NotAllowed(_) => StatusCode::FORBIDDEN,
+            };
+            return Self::new(status, membership.to_string());
+        }
+
+        if let Some(namespace) = err.downcast_ref::<NamespaceError>() {
+            let status = match namespace {
+                NamespaceError::NotFound => StatusCode::NOT_FOUND,
+                NamespaceError::SlugTaken(_) | NamespaceError::Archived => StatusCode::CONFLICT,
+            };
+            return Self::new(status, namespace.to_string());
+        }
+
+        if let Some(sign_in) = err.downcast_ref::<SignInError>() {
+            let status = match sign_in {
+                SignInError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+                SignInError::Disabled => StatusCode::FORBIDDEN,
+                SignInError::TooManyAttempts { .. } => StatusCode::TOO_MANY_REQUESTS,
+            };
+            return Self::new(status, sign_in.to_string());
+        }
+
+        if let Some(registration) = err.downcast_ref::<RegistrationError>() {
+            return Self::forbidden(registration.to_string());
+        }
+
+        if let Some(invitation) = err.downcast_ref::<InvitationError>() {
+            let status = match invitation {
+                InvitationError::Invalid => StatusCode::BAD_REQUEST,
+                InvitationError::NotFound => StatusCode::NOT_FOUND,
+                InvitationError::NotAllowed(_) => StatusCode::FORBIDDEN,
+            };
+            return Self::new(status, invitation.to_string());
+        }
+
+        if let Some(run) = err.downcast_ref::<RunError>() {
+            let status = match run {
+                RunError::NotFound => StatusCode::NOT_FOUND,
+                RunError::UnknownWorkflowType(_) | RunError::InvalidBusinessId(_) => StatusCode::BAD_REQUEST,
+                RunError::BusinessIdInUse(_) | RunError::NotActive(_) => StatusCode::CONFLICT,
+            };
+            return Self::new(status, run.to_string());
+        }
+
+        if let Some(search) = err.downcast_ref::<SearchAttributeError>() {
+            let status = match search {
+                SearchAttributeError::NotFound(_) => StatusCode::NOT_FOUND,
+                SearchAttributeError::AlreadyDefined(_) | SearchAttributeError::TooMany => StatusCode::CONFLICT,
+                SearchAttributeError::InvalidName(_)
+                | SearchAttributeError::Unknown(_)
+                | SearchAttributeError::NotAnObject
+                | SearchAttributeError::InvalidValue { .. }
+                | SearchAttributeError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            };
+            return Self::new(status, search.to_string());
+        }
+
+        if let Some(idempotency) = err.downcast_ref::<IdempotencyError>() {
+            let status = match idempotency {
+                IdempotencyError::InvalidKey(_) => StatusCode::BAD_REQUEST,
+                IdempotencyError::InFlight | IdempotencyError::Withheld => StatusCode::CONFLICT,
+                IdempotencyError::Mismatch => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            return Self::new(status, idempotency.to_string());
+        }
+
+        if let Some(admin) = err.downcast_ref::<AdminError>() {
+            let status = match admin {
+                AdminError::UserNotFound | AdminError::RunNotFound => StatusCode::NOT_FOUND,
+                AdminError::LastSuperAdmin => StatusCode::CONFLICT,
+                AdminError::NotAllowed(_) => StatusCode::FORBIDDEN,
+            };
+            return Self::new(status, admin.to_string());
+        }
+
+        if let Some(oidc) = err.downcast_ref::<OidcError>() {
+            let status = match oidc {
+                OidcError::InvalidState => StatusCode::BAD_REQUEST,
+                OidcError::Denied(_) | OidcError::InvalidIdToken(_) => StatusCode::UNAUTHORIZED,
+                OidcError::EmailMissing => StatusCode::FORBIDDEN,
+            };
+            return Self::new(status, oidc.to_string());
+        }
+
+        if let Some(session) = err.downcast_ref::<SessionError>() {
+            let status = match session {
+                SessionError::InvalidRefreshToken => StatusCode::UNAUTHORIZED,
+                SessionError::NotFound => StatusCode::NOT_FOUND,
+            };
+            return Self::new(status, session.to_string());
+        }
+
+        if let Some(scope) = err.downcast_ref::<ScopeError>() {
+            return Self::forbidden(scope.to_string());
+        }
+
+        if let Some(api_key) = err.downcast_ref::<ApiKeyError>() {
+            let status = match api_key {
+                ApiKeyError::NotFound => StatusCode::NOT_FOUND,
+                ApiKeyError::AlreadyRevoked => StatusCode::CONFLICT,
+                ApiKeyError::NotAllowed(_) => StatusCode::FORBIDDEN,
+            };
+            return Self::new(status, api_key.to_string());
+        }
+
         let error_chain: Vec<String> = err.chain()
             .map(|e| e.to_string())
             .collect();
@@ -89,6 +275,7 @@ impl From<anyhow::Error> for ApiError {
             } else {
                 None
             },
+            violations: None,
         }
     }
 }
@@ -99,6 +286,7 @@ impl From<validator::ValidationErrors> for ApiError {
             status: StatusCode::BAD_REQUEST,
             message: err.to_string(),
             details: None,
+            violations: None,
         }
     }
 }
@@ -113,54 +301,136 @@ impl IntoResponse for ApiError {
         if let Some(details) = self.details {
             body["details"] = json!(details);
         }
+
+        if let Some(violations) = self.violations {
+            body["violations"] = json!(violations);
+        }
         
         (self.status, Json(body)).into_response()
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server(
     s3_client: Arc<s3c>,
     workflows_service: Arc<WorkflowService>,
     user_service: Arc<UserService>,
     namespace_service: Arc<NamespaceService>,
+    audit_service: Arc<AuditService>,
+    api_key_service: Arc<ApiKeyService>,
+    session_service: Arc<SessionService>,
+    sso_service: Option<Arc<SsoService>>,
+    admin_service: Arc<AdminService>,
+    invitation_service: Arc<InvitationService>,
+    run_service: Arc<RunService>,
+    idempotency_service: Arc<IdempotencyService>,
+    token_keys: Arc<TokenKeys>,
     shutdown_rx: oneshot::Receiver<()>,
     wit_runtime: Arc<WitPluginRuntime>,
     cfg: Arc<AppConfig>,
 ) -> AnyhowResult<()> {
     info!("Starting HTTP server on port {:?}", cfg.port);
 
+    let trusted_proxies = Arc::new(TrustedProxies::parse(cfg.trusted_proxies.as_deref())?);
+
     let app = Router::new()
-        // .route("/workflows", get(workflows_list))
-        // .route("/workflows/{workflow_key}/functions", get(list_functions))
         .route("/auth/signup", post(signup))
         .route("/auth/signin", post(signin))
+        .route("/auth/refresh", post(refresh))
+        .route("/auth/signout", post(signout))
+        .route("/auth/token/rotate", post(rotate_token))
+        .route("/auth/tokens/revoke", post(revoke_token))
+        .route("/auth/sessions/scoped", post(start_scoped_session))
+        .route("/auth/sessions", get(get_sessions))
+        .route("/auth/sessions", delete(revoke_all_sessions))
+        .route("/auth/sessions/{session_id}", delete(revoke_session))
+        .route("/auth/oidc/login", get(oidc_login))
+        .route("/auth/oidc/callback", get(oidc_callback))
+        .route("/auth/invitations/accept", post(accept_invitation))
+
+        .route("/ns", post(create_namespace))
+        .route("/ns", get(get_namespaces))
+        .route("/ns/{slug}", get(get_namespace))
+        .route("/ns/{slug}", patch(rename_namespace))
+        .route("/ns/{slug}", delete(delete_namespace))
+        .route("/ns/{slug}/archive", post(archive_namespace))
+        .route("/ns/{slug}/unarchive", post(unarchive_namespace))
+
+        .route("/ns/{slug}/members", get(get_members))
+        .route("/ns/{slug}/members", post(add_member))
+        .route("/ns/{slug}/members/{user_id}", patch(update_member))
+        .route("/ns/{slug}/members/{user_id}", delete(remove_member))
+        .route("/ns/{slug}/transfer-ownership", post(transfer_ownership))
+        .route("/ns/{slug}/leave", post(leave_namespace))
+        .route("/ns/{slug}/invitations", get(get_invitations))
+        .route("/ns/{slug}/invitations", post(create_invitation))
+        .route("/ns/{slug}/invitations/{invitation_id}", delete(revoke_invitation))
+        .route("/ns/{slug}/api-keys", get(get_api_keys))
+        .route("/ns/{slug}/api-keys", post(create_api_key))
+        .route("/ns/{slug}/api-keys/{key_id}", delete(revoke_api_key))
 
-        .route("/namespaces", post(create_namespace))
-        .route("/namespaces", get(get_namespaces))
+        .route("/ns/{slug}/audit", get(get_namespace_audit))
+        .route("/ns/{slug}/audit/verify", get(verify_namespace_audit))
+        .route("/ns/{slug}/audit/checkpoints", get(get_namespace_checkpoints))
 
-        .route("/namespaces/{id}/workflows", post(create_workflow))
-        .route("/namespaces/{id}/workflows", get(get_workflows))
+        .route("/ns/{slug}/workflows", post(create_workflow))
+        .route("/ns/{slug}/workflows", get(get_workflows))
+        .route("/ns/{slug}/workflows/{key}", delete(remove_plugin_endpoint))
+        .route("/ns/{slug}/workflows/{key}/versions/{version}/schemas", get(get_version_schemas))
+        .route("/ns/{slug}/workflows/{key}/versions/{version}/manifest", get(get_version_manifest))
+        .route("/ns/{slug}/workflows/{key}/runs", post(start_run))
+        .route("/ns/{slug}/runs", get(get_runs))
+        .route("/ns/{slug}/runs/{run_id}", get(get_run))
+        .route("/ns/{slug}/runs/{run_id}/cancel", post(cancel_run))
+        .route("/ns/{slug}/runs/{run_id}/terminate", post(terminate_run))
+        .route("/ns/{slug}/search-attributes", get(get_search_attributes))
+        .route("/ns/{slug}/search-attributes", post(create_search_attribute))
+        .route("/ns/{slug}/search-attributes/{name}", delete(remove_search_attribute))
 
-        .route("/workflows/{workflow_key}/{function}", post(run_workflow))
-        .route("/workflows/{workflow_key}", delete(remove_plugin_endpoint))
+        .route("/admin/users", get(get_users))
+        .route("/admin/users/{user_id}/status", patch(update_user_status))
+        .route("/admin/users/{user_id}/super-admin", patch(update_super_admin))
+        .route("/admin/namespaces", get(get_all_namespaces))
+        .route("/admin/runs/{run_id}/cancel", post(force_cancel_run))
+        .route("/admin/plugins/unload", post(unload_plugins))
+        .route("/admin/audit", get(get_global_audit))
+        .route("/admin/audit/verify", get(verify_global_audit))
+        .route("/admin/audit/checkpoints", get(get_global_checkpoints))
 
         .route("/health", get(health_check))
+        // inside the extension layers, it needs the services they add
+        .layer(middleware::from_fn(idempotency::idempotent))
         .layer(Extension(wit_runtime))
         .layer(Extension(s3_client))
         .layer(Extension(workflows_service))
         .layer(Extension(user_service))
         .layer(Extension(namespace_service))
-        .layer(DefaultBodyLimit::max(30485760)) // ~30mb
+        .layer(Extension(audit_service))
+        .layer(Extension(api_key_service))
+        .layer(Extension(session_service))
+        .layer(Extension(sso_service))
+        .layer(Extension(admin_service))
+        .layer(Extension(invitation_service))
+        .layer(Extension(run_service))
+        .layer(Extension(idempotency_service))
+        .layer(Extension(token_keys.clone()))
+        .layer(Extension(trusted_proxies))
+        .layer(DefaultBodyLimit::max(BODY_LIMIT_BYTES))
         .layer(TraceLayer::new_for_http());
 
-    let app = Router::new().nest("/api", app);
+    // outside /api, where verifiers look for it
+    let app = Router::new()
+        .nest("/api", app)
+        .route("/.well-known/jwks.json", get(jwks))
+        .layer(Extension(token_keys));
 
     let addr = format!("0.0.0.0:{}", cfg.port);
 
     let listener = tokio::net::TcpListener::bind(addr.clone()).await?;
     info!("HTTP server listening on http://{addr}");
 
-    axum::serve(listener, app)
+    // the peer address is what sign-in limits and sessions go by
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(async {
             let _ = shutdown_rx.await;
             
@@ -172,6 +442,11 @@ pub async fn start_server(
     Ok(())
 }
 
+/// Public keys access tokens can be verified with, looked up by the `kid` in their header.
+async fn jwks(Extension(token_keys): Extension<Arc<TokenKeys>>) -> Json<token_keys::Jwks> {
+    Json(token_keys.jwks().clone())
+}
+
 async fn health_check() -> (StatusCode, Json<serde_json::Value>) {
     (
         StatusCode::OK,
@@ -16,16 +16,18 @@ use anyhow::Result;
 use axum::{
     Json as JsonResponse,
     Extension,
+    http::StatusCode,
     response::IntoResponse,
-    extract::Json,
+    extract::{Json, Path},
 };
+use uuid::Uuid;
 use validator::Validate;
 
 use super::ApiError;
 use crate::{
-    adapters::http::auth::Claims, 
+    adapters::http::{access::NamespaceAccess, auth::Claims}, 
     core::{
-        domain::namespace::{NewNamespace}, 
+        domain::namespace::{MemberRoleUpdate, NamespaceUpdate, NewMember, NewNamespace, OwnershipTransfer, Permission}, 
         services::namespace::NamespaceService,
     },
 };
@@ -35,6 +37,12 @@ pub(super) async fn create_namespace(
     Extension(namespace_service): Extension<Arc<NamespaceService>>,
     Json(req): Json<NewNamespace>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if claims.is_service_account() {
+        return Err(ApiError::forbidden("API keys can not create namespaces"));
+    }
+
+    claims.require_unrestricted()?;
+
     req.validate()?;
     
     let u = namespace_service.create(claims.get_user_id(), &req).await?;
@@ -47,7 +55,141 @@ pub(super) async fn get_namespaces(
     Extension(namespace_service): Extension<Arc<NamespaceService>>,
 ) -> Result<impl IntoResponse, ApiError> {
     
-    let u = namespace_service.find_all(claims.get_user_id()).await?;
+    let mut u = namespace_service.find_all(claims.get_user_id()).await?;
+    u.retain(|ns| claims.scopes().allows_namespace(ns.id));
 
     Ok(JsonResponse(u))
+}
+
+pub(super) async fn get_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Read)?;
+
+    let ns = namespace_service.get(access.namespace_id).await?;
+
+    Ok(JsonResponse(ns))
+}
+
+pub(super) async fn rename_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+    Json(req): Json<NamespaceUpdate>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+    req.validate()?;
+
+    let ns = namespace_service.rename(access.user_id(), access.namespace_id, &req.slug).await?;
+
+    Ok(JsonResponse(ns))
+}
+
+pub(super) async fn archive_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    let ns = namespace_service.archive(access.user_id(), access.namespace_id).await?;
+
+    Ok(JsonResponse(ns))
+}
+
+pub(super) async fn unarchive_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    let ns = namespace_service.unarchive(access.user_id(), access.namespace_id).await?;
+
+    Ok(JsonResponse(ns))
+}
+
+pub(super) async fn delete_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Delete)?;
+
+    let deleted = namespace_service.delete(access.user_id(), access.namespace_id).await?;
+
+    Ok(JsonResponse(serde_json::json!({
+        "slug": deleted.slug,
+        "workflows": deleted.workflow_keys,
+        "deleted_live_runs": deleted.deleted_live_runs,
+    })))
+}
+
+pub(super) async fn get_members(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Read)?;
+
+    let m = namespace_service.members(access.namespace_id).await?;
+
+    Ok(JsonResponse(m))
+}
+
+pub(super) async fn add_member(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+    Json(req): Json<NewMember>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+    req.validate()?;
+
+    namespace_service.add_member(access.user_id(), access.role, access.namespace_id, &req).await?;
+
+    Ok(StatusCode::CREATED)
+}
+
+pub(super) async fn update_member(
+    access: NamespaceAccess,
+    Path((_, user_id)): Path<(String, Uuid)>,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+    Json(req): Json<MemberRoleUpdate>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    namespace_service.change_role(access.user_id(), access.role, access.namespace_id, user_id, req.role).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn remove_member(
+    access: NamespaceAccess,
+    Path((_, user_id)): Path<(String, Uuid)>,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::ManageMembers)?;
+
+    namespace_service.remove_member(access.user_id(), access.role, access.namespace_id, user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn transfer_ownership(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+    Json(req): Json<OwnershipTransfer>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Delete)?;
+
+    namespace_service.transfer_ownership(access.user_id(), access.namespace_id, req.user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn leave_namespace(
+    access: NamespaceAccess,
+    Extension(namespace_service): Extension<Arc<NamespaceService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.claims.require_unrestricted()?;
+
+    namespace_service.leave(access.user_id(), access.namespace_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
\ No newline at end of file
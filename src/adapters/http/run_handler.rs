@@ -0,0 +1,150 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use axum::{
+    Json as JsonResponse,
+    Extension,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    extract::{Json, Path, Query},
+};
+use uuid::Uuid;
+
+use super::{workflow_handler::ensure_workflow_in_namespace, ApiError};
+use crate::{
+    adapters::http::access::NamespaceAccess,
+    core::{
+        domain::{namespace::Permission, run::{RunFilter, RunResource, StartRun, StopRun}, search::NewSearchAttribute, scope::Scope},
+        services::{run::RunService, workflow::WorkflowService},
+    },
+};
+
+/// Largest run input accepted, serialized.
+const MAX_INPUT_BYTES: usize = 1024 * 1024;
+
+pub(super) async fn start_run(
+    access: NamespaceAccess,
+    Extension(workflow_service): Extension<Arc<WorkflowService>>,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Path((_, key)): Path<(String, String)>,
+    Json(req): Json<StartRun>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Run)?;
+
+    let input_size = req.input.to_string().len();
+    if input_size > MAX_INPUT_BYTES {
+        return Err(ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Run input too large (max {} bytes): {} bytes", MAX_INPUT_BYTES, input_size),
+        ));
+    }
+
+    let workflow = ensure_workflow_in_namespace(&workflow_service, access.namespace_id, &key).await?;
+    let run = run_service.start(access.user_id(), &workflow, req).await?;
+    let resource = RunResource::new(run, &access.slug);
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, resource.links.self_.clone())],
+        JsonResponse(resource),
+    ))
+}
+
+pub(super) async fn get_run(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Path((_, run_id)): Path<(String, Uuid)>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require_scoped(Permission::Read, Scope::RunsRead)?;
+
+    let run = run_service.detail(access.namespace_id, &access.slug, run_id).await?;
+
+    Ok(JsonResponse(run))
+}
+
+pub(super) async fn get_runs(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Query(filter): Query<RunFilter>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require_scoped(Permission::Read, Scope::RunsRead)?;
+
+    let page = run_service.list(access.namespace_id, &filter).await?;
+
+    Ok(JsonResponse(page.with_links(&access.slug)))
+}
+
+pub(super) async fn cancel_run(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Path((_, run_id)): Path<(String, Uuid)>,
+    req: Option<Json<StopRun>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Stop)?;
+
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let stopped = run_service.cancel(access.user_id(), access.namespace_id, run_id, &req).await?;
+
+    Ok((StatusCode::ACCEPTED, JsonResponse(stopped.with_links(&access.slug))))
+}
+
+pub(super) async fn terminate_run(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Path((_, run_id)): Path<(String, Uuid)>,
+    req: Option<Json<StopRun>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Stop)?;
+
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let stopped = run_service.terminate(access.user_id(), access.namespace_id, run_id, &req).await?;
+
+    Ok(JsonResponse(stopped.with_links(&access.slug)))
+}
+
+pub(super) async fn get_search_attributes(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require_scoped(Permission::Read, Scope::RunsRead)?;
+
+    let definitions = run_service.search_attributes(access.namespace_id).await?;
+
+    Ok(JsonResponse(definitions))
+}
+
+pub(super) async fn create_search_attribute(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Json(req): Json<NewSearchAttribute>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    let definition = run_service.define_search_attribute(access.user_id(), access.namespace_id, &req).await?;
+
+    Ok((StatusCode::CREATED, JsonResponse(definition)))
+}
+
+pub(super) async fn remove_search_attribute(
+    access: NamespaceAccess,
+    Extension(run_service): Extension<Arc<RunService>>,
+    Path((_, name)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    access.require(Permission::Administer)?;
+
+    run_service.remove_search_attribute(access.user_id(), access.namespace_id, &name).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
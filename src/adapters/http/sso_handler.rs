@@ -0,0 +1,60 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::Result;
+use axum::{
+    Extension,
+    response::{IntoResponse, Redirect},
+    extract::Query,
+};
+
+use super::ApiError;
+use super::auth::generate_auth_header;
+use super::token_keys::TokenKeys;
+use super::access::ClientOrigin;
+use crate::core::domain::oidc::OidcCallback;
+use crate::core::domain::scope::TokenScopes;
+use crate::core::services::{session::SessionService, sso::SsoService};
+
+fn configured(sso: Option<Arc<SsoService>>) -> Result<Arc<SsoService>, ApiError> {
+    sso.ok_or_else(|| ApiError::not_found("Single sign-on is not configured"))
+}
+
+pub(super) async fn oidc_login(
+    Extension(sso): Extension<Option<Arc<SsoService>>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sso = configured(sso)?;
+
+    let url = sso.begin().await?;
+
+    Ok(Redirect::to(&url))
+}
+
+pub(super) async fn oidc_callback(
+    Extension(sso): Extension<Option<Arc<SsoService>>>,
+    Extension(session_service): Extension<Arc<SessionService>>,
+    Extension(token_keys): Extension<Arc<TokenKeys>>,
+    ClientOrigin(origin): ClientOrigin,
+    Query(callback): Query<OidcCallback>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sso = configured(sso)?;
+
+    let u = sso.complete(&callback).await?;
+
+    let issued = session_service.start(&u, origin, TokenScopes::default()).await?;
+
+    let auth_jwt = generate_auth_header(&token_keys, u, &issued, session_service.access_ttl()).await;
+
+    Ok(auth_jwt)
+}
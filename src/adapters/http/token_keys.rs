@@ -0,0 +1,262 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+//! Keys access tokens are signed and verified with.
+//!
+//! Tokens are signed with one RS256 or EdDSA key and carry its `kid`; any number of
+//! older keys stay valid for verification so a rotation does not sign everyone out.
+//! All of them are published at `/.well-known/jwks.json`. The algorithm follows from
+//! the key type. Without a key file the HS256 `JWT_SECRET` is used as before; once a
+//! key file is configured the secret, if still set, only verifies tokens without `kid`.
+
+use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::{pkcs1::{DecodeRsaPrivateKey as _, DecodeRsaPublicKey as _}, traits::PublicKeyParts, RsaPrivateKey, RsaPublicKey};
+use rsa::pkcs8::{DecodePrivateKey as _, DecodePublicKey as _};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::infra::config::AppConfig;
+
+/// A public key as published in the JWKS document.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublicJwk {
+    pub kty: &'static str,
+    #[serde(rename = "use")]
+    pub use_: &'static str,
+    pub alg: &'static str,
+    pub kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Jwks {
+    pub keys: Vec<PublicJwk>,
+}
+
+struct SigningKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+}
+
+struct VerificationKey {
+    algorithm: Algorithm,
+    decoding: DecodingKey,
+}
+
+/// A key read from a PEM file; `private_pem` is kept only when the file held a private key.
+struct LoadedKey {
+    kid: String,
+    algorithm: Algorithm,
+    decoding: DecodingKey,
+    jwk: PublicJwk,
+    private_pem: Option<Vec<u8>>,
+}
+
+pub struct TokenKeys {
+    signing: SigningKey,
+    verification: HashMap<String, VerificationKey>,
+    /// Verifies HS256 tokens that name no key.
+    legacy_secret: Option<DecodingKey>,
+    jwks: Jwks,
+}
+
+impl TokenKeys {
+    /// Loads and checks every configured key, so a bad setup fails at start and not on the first request.
+    pub fn from_config(cfg: &AppConfig) -> Result<Self> {
+        let legacy_secret = cfg.jwt_secret.as_deref().filter(|s| !s.is_empty());
+
+        let Some(signing_file) = &cfg.jwt_signing_key_file else {
+            let secret = legacy_secret
+                .context("Either JWT_SIGNING_KEY_FILE or JWT_SECRET must be set")?;
+
+            info!("Signing access tokens with HS256, no JWKS keys are published");
+            return Ok(TokenKeys {
+                signing: SigningKey {
+                    kid: None,
+                    algorithm: Algorithm::HS256,
+                    encoding: EncodingKey::from_secret(secret.as_bytes()),
+                },
+                verification: HashMap::new(),
+                legacy_secret: Some(DecodingKey::from_secret(secret.as_bytes())),
+                jwks: Jwks { keys: Vec::new() },
+            });
+        };
+
+        let signing = load_key(signing_file)?;
+        let private_pem = signing.private_pem.as_deref()
+            .with_context(|| format!("JWT signing key '{}' is not a private key", signing_file))?;
+
+        let encoding = match signing.algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(private_pem)?,
+            _ => EncodingKey::from_ed_pem(private_pem)?,
+        };
+
+        let mut keys = TokenKeys {
+            signing: SigningKey {
+                kid: Some(signing.kid.clone()),
+                algorithm: signing.algorithm,
+                encoding,
+            },
+            verification: HashMap::new(),
+            legacy_secret: legacy_secret.map(|s| DecodingKey::from_secret(s.as_bytes())),
+            jwks: Jwks { keys: Vec::new() },
+        };
+        keys.add_verification_key(signing);
+
+        let previous = cfg.jwt_verification_key_files.as_deref().unwrap_or_default();
+        for file in previous.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            keys.add_verification_key(load_key(file)?);
+        }
+
+        info!(
+            "Signing access tokens with {:?} key '{}', {} key(s) accepted for verification",
+            keys.signing.algorithm, keys.signing.kid.as_deref().unwrap_or_default(), keys.verification.len(),
+        );
+
+        Ok(keys)
+    }
+
+    fn add_verification_key(&mut self, key: LoadedKey) {
+        if self.verification.contains_key(&key.kid) {
+            return;
+        }
+
+        self.jwks.keys.push(key.jwk);
+        self.verification.insert(key.kid, VerificationKey {
+            algorithm: key.algorithm,
+            decoding: key.decoding,
+        });
+    }
+
+    pub fn jwks(&self) -> &Jwks {
+        &self.jwks
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let mut header = Header::new(self.signing.algorithm);
+        header.kid = self.signing.kid.clone();
+
+        Ok(encode(&header, claims, &self.signing.encoding)?)
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, token: &str, validate_exp: bool) -> Result<T> {
+        let header = decode_header(token)?;
+
+        let (algorithm, key) = match &header.kid {
+            Some(kid) => {
+                let key = self.verification.get(kid)
+                    .with_context(|| format!("Unknown signing key '{}'", kid))?;
+                (key.algorithm, &key.decoding)
+            }
+            None => {
+                let key = self.legacy_secret.as_ref()
+                    .context("Token names no signing key")?;
+                (Algorithm::HS256, key)
+            }
+        };
+
+        // the key decides the algorithm, never the token
+        if header.alg != algorithm {
+            bail!("Token is signed with {:?}, its key uses {:?}", header.alg, algorithm);
+        }
+
+        let mut validation = Validation::new(algorithm);
+        validation.validate_exp = validate_exp;
+
+        Ok(decode::<T>(token, key, &validation)?.claims)
+    }
+}
+
+fn load_key(path: &str) -> Result<LoadedKey> {
+    let pem = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read JWT key file '{}'", path))?;
+
+    if let Ok(private) = RsaPrivateKey::from_pkcs8_pem(&pem).or_else(|_| RsaPrivateKey::from_pkcs1_pem(&pem)) {
+        return Ok(rsa_key(&private.to_public_key(), Some(pem.into_bytes())));
+    }
+
+    if let Ok(public) = RsaPublicKey::from_public_key_pem(&pem).or_else(|_| RsaPublicKey::from_pkcs1_pem(&pem)) {
+        return Ok(rsa_key(&public, None));
+    }
+
+    if let Ok(private) = ed25519_dalek::SigningKey::from_pkcs8_pem(&pem) {
+        return ed25519_key(&private.verifying_key(), Some(pem.into_bytes()));
+    }
+
+    if let Ok(public) = ed25519_dalek::VerifyingKey::from_public_key_pem(&pem) {
+        return ed25519_key(&public, None);
+    }
+
+    bail!("JWT key file '{}' holds no RSA or Ed25519 key in PEM format", path)
+}
+
+fn rsa_key(public: &RsaPublicKey, private_pem: Option<Vec<u8>>) -> LoadedKey {
+    let (n, e) = (public.n().to_bytes_be(), public.e().to_bytes_be());
+    let kid = key_id(&[n.as_slice(), e.as_slice()].concat());
+
+    LoadedKey {
+        kid: kid.clone(),
+        algorithm: Algorithm::RS256,
+        decoding: DecodingKey::from_rsa_raw_components(&n, &e),
+        jwk: PublicJwk {
+            kty: "RSA",
+            use_: "sig",
+            alg: "RS256",
+            kid,
+            n: Some(URL_SAFE_NO_PAD.encode(&n)),
+            e: Some(URL_SAFE_NO_PAD.encode(&e)),
+            crv: None,
+            x: None,
+        },
+        private_pem,
+    }
+}
+
+fn ed25519_key(public: &ed25519_dalek::VerifyingKey, private_pem: Option<Vec<u8>>) -> Result<LoadedKey> {
+    let x = URL_SAFE_NO_PAD.encode(public.as_bytes());
+    let kid = key_id(public.as_bytes());
+
+    Ok(LoadedKey {
+        kid: kid.clone(),
+        algorithm: Algorithm::EdDSA,
+        decoding: DecodingKey::from_ed_components(&x)?,
+        jwk: PublicJwk {
+            kty: "OKP",
+            use_: "sig",
+            alg: "EdDSA",
+            kid,
+            n: None,
+            e: None,
+            crv: Some("Ed25519"),
+            x: Some(x),
+        },
+        private_pem,
+    })
+}
+
+/// Derived from the public key, so the same key always gets the same id on every node.
+fn key_id(public: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public)[..8])
+}
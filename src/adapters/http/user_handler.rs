@@ -16,16 +16,23 @@ use anyhow::Result;
 use axum::{
     Json as JsonResponse,
     Extension,
+    http::StatusCode,
     response::IntoResponse,
-    extract::Json,
+    extract::{Json, Path},
 };
+use uuid::Uuid;
 use validator::Validate;
 
 
 use super::ApiError;
-use crate::core::services::user::UserService;
+use crate::core::services::{session::SessionService, user::UserService};
+use crate::core::domain::scope::TokenScopes;
+use crate::core::domain::session::{RefreshRequest, RevokeTokenRequest};
 use crate::core::domain::user::{NewUser, UserAuth, NewUserResponse};
-use super::auth::generate_auth_header;
+use super::auth::{decode_ignoring_expiry, generate_auth_header, Claims};
+use super::token_keys::TokenKeys;
+use super::idempotency::carrying_credentials;
+use super::access::ClientOrigin;
 
 pub(super) async fn signup(
     Extension(user_service): Extension<Arc<UserService>>,
@@ -33,24 +40,160 @@ pub(super) async fn signup(
 ) -> Result<impl IntoResponse, ApiError> {
     req.validate()?;
     
-    let u = user_service.create(&req).await?;
+    let u = user_service.signup(&req).await?;
 
-    Ok(JsonResponse(
+    Ok(carrying_credentials(JsonResponse(
         NewUserResponse{
             password: u.token,
         }
-    ))
+    )))
 }
 
 pub(super) async fn signin(
     Extension(user_service): Extension<Arc<UserService>>,
+    Extension(session_service): Extension<Arc<SessionService>>,
+    Extension(token_keys): Extension<Arc<TokenKeys>>,
+    ClientOrigin(origin): ClientOrigin,
     Json(req): Json<UserAuth>,
 ) -> Result<impl IntoResponse, ApiError> {
     req.validate()?;
 
-    let u = user_service.auth(req.email, req.password).await?;
+    let ip = origin.ip.as_deref().and_then(|ip| ip.parse().ok());
 
-    let auth_jwt = generate_auth_header(u.clone()).await;
+    let u = user_service.auth(req.email, req.password, ip).await?;
 
-    Ok(auth_jwt)
+    let issued = session_service.start(&u, origin, req.limits).await?;
+
+    let auth_jwt = generate_auth_header(&token_keys, u, &issued, session_service.access_ttl()).await;
+
+    Ok(carrying_credentials(auth_jwt))
+}
+
+pub(super) async fn refresh(
+    Extension(session_service): Extension<Arc<SessionService>>,
+    Extension(token_keys): Extension<Arc<TokenKeys>>,
+    Json(req): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (issued, u) = session_service.refresh(&req.refresh_token).await?;
+
+    let auth_jwt = generate_auth_header(&token_keys, u, &issued, session_service.access_ttl()).await;
+
+    Ok(carrying_credentials(auth_jwt))
+}
+
+/// Ends the session the request was made with.
+pub(super) async fn signout(
+    claims: Claims,
+    Extension(session_service): Extension<Arc<SessionService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sid = claims.session_id()
+        .ok_or_else(|| ApiError::bad_request("API keys have no session, revoke the key instead"))?;
+
+    session_service.revoke(claims.get_user_id(), sid).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replaces the caller's token; every session, this one included, is ended.
+pub(super) async fn rotate_token(
+    claims: Claims,
+    Extension(user_service): Extension<Arc<UserService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    if claims.is_service_account() {
+        return Err(ApiError::forbidden("Service accounts have no token to rotate"));
+    }
+
+    claims.require_unrestricted()?;
+
+    let token = user_service.rotate_token(claims.get_user_id()).await?;
+
+    Ok(carrying_credentials(JsonResponse(
+        NewUserResponse{
+            password: token,
+        }
+    )))
+}
+
+/// Starts a session restricted to some scopes or namespaces, e.g. for CI or a dashboard.
+pub(super) async fn start_scoped_session(
+    claims: Claims,
+    Extension(session_service): Extension<Arc<SessionService>>,
+    Extension(token_keys): Extension<Arc<TokenKeys>>,
+    ClientOrigin(origin): ClientOrigin,
+    Json(req): Json<TokenScopes>,
+) -> Result<impl IntoResponse, ApiError> {
+    if claims.is_service_account() {
+        return Err(ApiError::forbidden("API keys can not start sessions"));
+    }
+
+    let (issued, u) = session_service.start_scoped(claims.get_user_id(), claims.scopes(), origin, req).await?;
+
+    let auth_jwt = generate_auth_header(&token_keys, u, &issued, session_service.access_ttl()).await;
+
+    Ok(carrying_credentials(auth_jwt))
+}
+
+pub(super) async fn get_sessions(
+    claims: Claims,
+    Extension(session_service): Extension<Arc<SessionService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let sessions = session_service.find_all(claims.get_user_id()).await?;
+    let current = claims.session_id();
+
+    let sessions: Vec<_> = sessions.into_iter()
+        .map(|s| {
+            let is_current = Some(s.id) == current;
+            let mut v = serde_json::to_value(s).unwrap_or_default();
+            v["current"] = serde_json::json!(is_current);
+            v
+        })
+        .collect();
+
+    Ok(JsonResponse(sessions))
+}
+
+pub(super) async fn revoke_session(
+    claims: Claims,
+    Path(session_id): Path<Uuid>,
+    Extension(session_service): Extension<Arc<SessionService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.require_unrestricted()?;
+
+    session_service.revoke(claims.get_user_id(), session_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn revoke_all_sessions(
+    claims: Claims,
+    Extension(session_service): Extension<Arc<SessionService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    claims.require_unrestricted()?;
+
+    let revoked = session_service.revoke_all(claims.get_user_id()).await?;
+
+    Ok(JsonResponse(serde_json::json!({ "revoked": revoked })))
+}
+
+/// Puts a single access token on the denylist, e.g. one that leaked.
+pub(super) async fn revoke_token(
+    claims: Claims,
+    Extension(session_service): Extension<Arc<SessionService>>,
+    Extension(token_keys): Extension<Arc<TokenKeys>>,
+    Json(req): Json<RevokeTokenRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = decode_ignoring_expiry(&token_keys, &req.access_token)
+        .ok_or_else(|| ApiError::bad_request("Not an access token issued by this server"))?;
+
+    if token.get_user_id() != claims.get_user_id() && !claims.is_super_admin() {
+        return Err(ApiError::forbidden("You can only revoke your own tokens"));
+    }
+
+    let (Some(jti), Some(expires_at)) = (token.token_id(), token.expires_at()) else {
+        return Err(ApiError::bad_request("Token can not be revoked individually"));
+    };
+
+    session_service.deny_token(claims.get_user_id(), jti, expires_at).await?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
\ No newline at end of file
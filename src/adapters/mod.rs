@@ -1,4 +1,6 @@
 pub mod filesystem;
 pub mod http;
+pub mod oidc;
 pub mod postgres;
+pub mod s3;
 pub mod wasmtime;
\ No newline at end of file
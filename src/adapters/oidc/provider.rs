@@ -0,0 +1,365 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::JwkSet,
+    Algorithm, DecodingKey, Validation,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::core::domain::oidc::{Identity, OidcError};
+use crate::core::ports::identity::IdentityProvider;
+
+/// Unknown key ids make us refetch the JWKS, but not more often than this.
+const JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct OidcSettings {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_url: String,
+    pub scopes: String,
+    pub groups_claim: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+struct Jwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// An OpenID Connect provider found through its discovery document.
+///
+/// Only the authorization code flow with S256 PKCE is used, ID tokens must be signed
+/// with one of the provider's published asymmetric keys.
+pub struct OidcProvider {
+    settings: OidcSettings,
+    discovery: Discovery,
+    http: reqwest::Client,
+    jwks: RwLock<Jwks>,
+}
+
+impl OidcProvider {
+    pub async fn discover(settings: OidcSettings) -> Result<impl IdentityProvider, anyhow::Error> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        let url = format!("{}/.well-known/openid-configuration", settings.issuer_url.trim_end_matches('/'));
+        let discovery: Discovery = http.get(&url).send().await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("Failed to fetch OIDC discovery document '{}'", url))?
+            .json().await
+            .context("Invalid OIDC discovery document")?;
+
+        if discovery.issuer.trim_end_matches('/') != settings.issuer_url.trim_end_matches('/') {
+            bail!("OIDC discovery document is for issuer '{}', expected '{}'", discovery.issuer, settings.issuer_url);
+        }
+
+        reqwest::Url::parse(&discovery.authorization_endpoint)
+            .with_context(|| format!("Invalid OIDC authorization endpoint '{}'", discovery.authorization_endpoint))?;
+
+        let keys = fetch_jwks(&http, &discovery.jwks_uri).await?;
+        info!("OIDC provider '{}' discovered with {} signing key(s)", discovery.issuer, keys.keys.len());
+
+        Ok(OidcProvider {
+            settings,
+            discovery,
+            http,
+            jwks: RwLock::new(Jwks { keys, fetched_at: Instant::now() }),
+        })
+    }
+
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey, anyhow::Error> {
+        {
+            let jwks = self.jwks.read().await;
+            if let Some(jwk) = jwks.keys.find(kid) {
+                return Ok(DecodingKey::from_jwk(jwk)?);
+            }
+            if jwks.fetched_at.elapsed() < JWKS_REFETCH_INTERVAL {
+                bail!(OidcError::InvalidIdToken(format!("unknown signing key '{}'", kid)));
+            }
+        }
+
+        // the provider may have rotated its keys since we last looked
+        let mut jwks = self.jwks.write().await;
+        if jwks.fetched_at.elapsed() >= JWKS_REFETCH_INTERVAL {
+            jwks.keys = fetch_jwks(&self.http, &self.discovery.jwks_uri).await?;
+            jwks.fetched_at = Instant::now();
+        }
+
+        let jwk = jwks.keys.find(kid)
+            .ok_or_else(|| OidcError::InvalidIdToken(format!("unknown signing key '{}'", kid)))?;
+
+        Ok(DecodingKey::from_jwk(jwk)?)
+    }
+
+    async fn verify_id_token(&self, id_token: &str, nonce: &str) -> Result<Identity, anyhow::Error> {
+        let header = decode_header(id_token)
+            .map_err(|e| OidcError::InvalidIdToken(e.to_string()))?;
+
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            bail!(OidcError::InvalidIdToken(format!("{:?} signed tokens are not accepted", header.alg)));
+        }
+
+        let kid = header.kid
+            .ok_or_else(|| OidcError::InvalidIdToken("token names no signing key".to_string()))?;
+        let key = self.decoding_key(&kid).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.discovery.issuer]);
+        validation.set_audience(&[&self.settings.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<Map<String, JsonValue>>(id_token, &key, &validation)
+            .map_err(|e| OidcError::InvalidIdToken(e.to_string()))?
+            .claims;
+
+        if claims.get("nonce").and_then(JsonValue::as_str) != Some(nonce) {
+            bail!(OidcError::InvalidIdToken("nonce does not match".to_string()));
+        }
+
+        let subject = claims.get("sub").and_then(JsonValue::as_str)
+            .ok_or_else(|| OidcError::InvalidIdToken("missing subject".to_string()))?;
+
+        // an unverified email could belong to anyone, it must not link to an existing account
+        let email = claims.get("email").and_then(JsonValue::as_str)
+            .filter(|_| claims.get("email_verified").and_then(JsonValue::as_bool).unwrap_or(false))
+            .ok_or(OidcError::EmailMissing)?;
+
+        let groups = match claims.get(&self.settings.groups_claim) {
+            Some(JsonValue::Array(groups)) => groups.iter()
+                .filter_map(JsonValue::as_str)
+                .map(str::to_string)
+                .collect(),
+            Some(JsonValue::String(group)) => vec![group.clone()],
+            _ => Vec::new(),
+        };
+
+        Ok(Identity {
+            issuer: self.discovery.issuer.clone(),
+            subject: subject.to_string(),
+            email: email.to_lowercase(),
+            groups,
+        })
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> String {
+        let mut url = reqwest::Url::parse(&self.discovery.authorization_endpoint)
+            .expect("authorization endpoint is checked at discovery");
+
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.settings.client_id)
+            .append_pair("redirect_uri", &self.settings.redirect_url)
+            .append_pair("scope", &self.settings.scopes)
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", code_challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        url.to_string()
+    }
+
+    async fn exchange(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<Identity, anyhow::Error> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.settings.redirect_url),
+            ("client_id", &self.settings.client_id),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.settings.client_secret {
+            form.push(("client_secret", secret));
+        }
+
+        let response = self.http.post(&self.discovery.token_endpoint)
+            .form(&form)
+            .send().await
+            .context("Failed to reach the OIDC token endpoint")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!(OidcError::Denied(format!("token endpoint answered {}: {}", status, body)));
+        }
+
+        let tokens: TokenResponse = response.json().await
+            .context("Invalid response from the OIDC token endpoint")?;
+
+        self.verify_id_token(&tokens.id_token, nonce).await
+    }
+}
+
+async fn fetch_jwks(http: &reqwest::Client, jwks_uri: &str) -> Result<JwkSet, anyhow::Error> {
+    let keys = http.get(jwks_uri).send().await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Failed to fetch OIDC signing keys '{}'", jwks_uri))?
+        .json().await
+        .context("Invalid OIDC JWKS document")?;
+
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use ed25519_dalek::{pkcs8::EncodePrivateKey, SigningKey};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    const ISSUER: &str = "https://idp.example.test";
+    const CLIENT_ID: &str = "xarxa";
+    const NONCE: &str = "n0nce";
+    const KID: &str = "test-key";
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    /// A provider as discovery would leave it, with the JWKS already fetched.
+    fn provider() -> OidcProvider {
+        let x = URL_SAFE_NO_PAD.encode(signing_key().verifying_key().as_bytes());
+        let keys: JwkSet = serde_json::from_value(json!({
+            "keys": [{ "kty": "OKP", "crv": "Ed25519", "alg": "EdDSA", "use": "sig", "kid": KID, "x": x }],
+        })).unwrap();
+
+        OidcProvider {
+            settings: OidcSettings {
+                issuer_url: ISSUER.to_string(),
+                client_id: CLIENT_ID.to_string(),
+                client_secret: None,
+                redirect_url: "https://xarxa.example.test/api/auth/oidc/callback".to_string(),
+                scopes: "openid email".to_string(),
+                groups_claim: "groups".to_string(),
+            },
+            discovery: Discovery {
+                issuer: ISSUER.to_string(),
+                authorization_endpoint: format!("{}/authorize", ISSUER),
+                token_endpoint: format!("{}/token", ISSUER),
+                jwks_uri: format!("{}/jwks", ISSUER),
+            },
+            http: reqwest::Client::new(),
+            jwks: RwLock::new(Jwks { keys, fetched_at: Instant::now() }),
+        }
+    }
+
+    fn claims() -> Map<String, JsonValue> {
+        let claims = json!({
+            "iss": ISSUER,
+            "aud": CLIENT_ID,
+            "sub": "user-1",
+            "exp": chrono::Utc::now().timestamp() + 300,
+            "nonce": NONCE,
+            "email": "Ada@Example.test",
+            "email_verified": true,
+            "groups": ["ops"],
+        });
+
+        match claims {
+            JsonValue::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    fn sign(claims: &Map<String, JsonValue>) -> String {
+        let der = signing_key().to_pkcs8_der().unwrap();
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(KID.to_string());
+
+        encode(&header, claims, &EncodingKey::from_ed_der(der.as_bytes())).unwrap()
+    }
+
+    fn with(name: &str, value: JsonValue) -> String {
+        let mut claims = claims();
+        claims.insert(name.to_string(), value);
+        sign(&claims)
+    }
+
+    async fn rejection(id_token: &str) -> OidcError {
+        provider().verify_id_token(id_token, NONCE).await
+            .unwrap_err()
+            .downcast::<OidcError>()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_id_token() {
+        let identity = provider().verify_id_token(&sign(&claims()), NONCE).await.unwrap();
+
+        assert_eq!(identity.issuer, ISSUER);
+        assert_eq!(identity.subject, "user-1");
+        assert_eq!(identity.email, "ada@example.test");
+        assert_eq!(identity.groups, ["ops"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_audience() {
+        let error = rejection(&with("aud", json!("someone-else"))).await;
+        assert!(matches!(error, OidcError::InvalidIdToken(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_issuer() {
+        let error = rejection(&with("iss", json!("https://evil.example.test"))).await;
+        assert!(matches!(error, OidcError::InvalidIdToken(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_nonce() {
+        let error = rejection(&with("nonce", json!("replayed"))).await;
+        assert!(matches!(error, OidcError::InvalidIdToken(reason) if reason.contains("nonce")));
+    }
+
+    #[tokio::test]
+    async fn rejects_unverified_email() {
+        let error = rejection(&with("email_verified", json!(false))).await;
+        assert!(matches!(error, OidcError::EmailMissing));
+    }
+
+    #[tokio::test]
+    async fn rejects_hs256_signed_token() {
+        // signed with the public key as HMAC secret, the classic algorithm confusion
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(KID.to_string());
+        let secret = signing_key().verifying_key().to_bytes();
+        let id_token = encode(&header, &claims(), &EncodingKey::from_secret(&secret)).unwrap();
+
+        let error = rejection(&id_token).await;
+        assert!(matches!(error, OidcError::InvalidIdToken(reason) if reason.contains("HS256")));
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::bail;
+use async_trait::async_trait;
+use std::sync::Arc;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::core::domain::admin::{AdminError, NamespaceStats, NamespaceStatsFilter, UserFilter, UserSummary};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::ports::storage::AdminRepository;
+use super::audit;
+
+pub struct PostgresAdminRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresAdminRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl AdminRepository {
+        PostgresAdminRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl AdminRepository for PostgresAdminRepository {
+    async fn find_users(&self, filter: &UserFilter, limit: i64) -> Result<Vec<UserSummary>, anyhow::Error> {
+        let users = sqlx::query_as!(
+            UserSummary,
+            r#"
+            SELECT u.id, u.email as "email: String", u.super_admin, u.service_account, u.created_at, u.disabled_at,
+                (SELECT count(*) FROM namespace_members m WHERE m.user_id = u.id) as "namespaces!"
+            FROM users u
+            WHERE ($1::text IS NULL OR strpos(lower(u.email::text), lower($1)) > 0)
+              AND ($2::bool IS NULL OR u.super_admin = $2)
+              AND ($3::bool IS NULL OR (u.disabled_at IS NOT NULL) = $3)
+              AND ($4::bool IS NULL OR u.service_account = $4)
+              AND ($5::uuid IS NULL OR u.id > $5)
+            ORDER BY u.id
+            LIMIT $6
+            "#,
+            filter.q,
+            filter.super_admin,
+            filter.disabled,
+            filter.service_account,
+            filter.after,
+            limit,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn set_disabled(&self, actor: Uuid, user_id: Uuid, disabled: bool) -> Result<UserSummary, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let admins = lock_super_admins(&mut tx).await?;
+        if disabled && admins == [user_id] {
+            bail!(AdminError::LastSuperAdmin);
+        }
+
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE users SET disabled_at = CASE WHEN $2 THEN COALESCE(disabled_at, now()) END
+            WHERE id = $1
+            RETURNING id
+            "#,
+            user_id,
+            disabled,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if updated.is_none() {
+            bail!(AdminError::UserNotFound);
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new(if disabled { "user.disable" } else { "user.enable" })
+            .actor(actor)
+            .object("user", user_id)
+        ).await?;
+
+        let user = user_summary(&mut tx, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    async fn set_super_admin(&self, actor: Uuid, user_id: Uuid, super_admin: bool) -> Result<UserSummary, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let admins = lock_super_admins(&mut tx).await?;
+        if !super_admin && admins == [user_id] {
+            bail!(AdminError::LastSuperAdmin);
+        }
+
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE users SET super_admin = $2
+            WHERE id = $1 AND NOT service_account
+            RETURNING id
+            "#,
+            user_id,
+            super_admin,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if updated.is_none() {
+            bail!(AdminError::UserNotFound);
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new(if super_admin { "user.promote" } else { "user.demote" })
+            .actor(actor)
+            .object("user", user_id)
+        ).await?;
+
+        let user = user_summary(&mut tx, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    async fn namespace_stats(&self, filter: &NamespaceStatsFilter) -> Result<Vec<NamespaceStats>, anyhow::Error> {
+        let stats = sqlx::query_as!(
+            NamespaceStats,
+            r#"
+            SELECT n.id, n.slug, n.created_by, n.created_at, n.archived_at,
+                (SELECT count(*) FROM namespace_members m WHERE m.namespace_id = n.id) as "members!",
+                (SELECT count(*) FROM workflows w WHERE w.namespace_id = n.id) as "workflows!",
+                v.versions as "versions!",
+                v.storage_bytes as "storage_bytes!",
+                r.runs as "runs!",
+                r.running_runs as "running_runs!",
+                r.last_run_at
+            FROM namespaces n
+            CROSS JOIN LATERAL (
+                SELECT count(*) as versions, COALESCE(sum(wv.wasm_size_bytes), 0)::bigint as storage_bytes
+                FROM workflow_versions wv
+                    JOIN workflows w
+                    ON w.id = wv.workflow_id
+                WHERE w.namespace_id = n.id
+            ) v
+            CROSS JOIN LATERAL (
+                SELECT count(*) as runs,
+                    count(*) FILTER (WHERE wr.state = 'running') as running_runs,
+                    max(wr.started_at) as last_run_at
+                FROM workflow_runs wr
+                    JOIN workflow_versions wv
+                    ON wv.id = wr.workflow_version_id
+                    JOIN workflows w
+                    ON w.id = wv.workflow_id
+                WHERE w.namespace_id = n.id
+            ) r
+            WHERE ($1::text IS NULL OR strpos(lower(n.slug), lower($1)) > 0)
+              AND ($2::bool IS NULL OR (n.archived_at IS NOT NULL) = $2)
+            ORDER BY n.slug
+            "#,
+            filter.q,
+            filter.archived,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
+
+/// Locks the rows of the active super admins for the rest of the transaction,
+/// so concurrent demotions can not leave nobody in charge.
+async fn lock_super_admins(conn: &mut PgConnection) -> Result<Vec<Uuid>, anyhow::Error> {
+    let ids = sqlx::query_scalar!(
+        r#"
+        SELECT id FROM users
+        WHERE super_admin AND disabled_at IS NULL
+        ORDER BY id
+        FOR UPDATE
+        "#
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(ids)
+}
+
+async fn user_summary(conn: &mut PgConnection, user_id: Uuid) -> Result<UserSummary, anyhow::Error> {
+    let user = sqlx::query_as!(
+        UserSummary,
+        r#"
+        SELECT u.id, u.email as "email: String", u.super_admin, u.service_account, u.created_at, u.disabled_at,
+            (SELECT count(*) FROM namespace_members m WHERE m.user_id = u.id) as "namespaces!"
+        FROM users u
+        WHERE u.id = $1
+        "#,
+        user_id
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(user)
+}
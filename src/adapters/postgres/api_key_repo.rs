@@ -0,0 +1,215 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::api_key::{ApiKey, ApiKeyCredentials, ApiKeyError, NewApiKeyRecord};
+use crate::core::domain::namespace::NamespaceRole;
+use crate::core::ports::storage::ApiKeyRepository;
+use super::audit;
+
+/// Service accounts can not sign in, no token hashes to this.
+const NO_TOKEN: &str = "!";
+
+pub struct PostgresApiKeyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl ApiKeyRepository {
+        PostgresApiKeyRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    async fn create(&self, actor: Uuid, ns_id: Uuid, k: &NewApiKeyRecord) -> Result<ApiKey, anyhow::Error> {
+        let (key_id, user_id) = (Uuid::now_v7(), Uuid::now_v7());
+        let email = format!("{}@service-accounts.xarxa.internal", k.prefix);
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO users (id, email, token_hash, service_account)
+            VALUES ($1, $2, $3, true)
+            "#,
+            user_id,
+            email,
+            NO_TOKEN,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO namespace_members (namespace_id, user_id, role)
+            VALUES ($1, $2, $3)
+            "#,
+            ns_id,
+            user_id,
+            k.role as NamespaceRole,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            INSERT INTO api_keys (id, namespace_id, user_id, name, role, prefix, secret_hash, created_by, expires_at, scopes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, namespace_id, user_id, name, role as "role: NamespaceRole", prefix, created_by, created_at, expires_at, last_used_at, revoked_at, scopes
+            "#,
+            key_id,
+            ns_id,
+            user_id,
+            k.name,
+            k.role as NamespaceRole,
+            k.prefix,
+            k.secret_hash,
+            actor,
+            k.expires_at,
+            k.scopes.as_deref(),
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("api_key.create")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("api_key", key_id)
+            .meta(json!({ "name": k.name, "role": k.role, "prefix": k.prefix, "expires_at": k.expires_at, "scopes": k.scopes }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(key)
+    }
+
+    async fn find_all(&self, ns_id: Uuid) -> Result<Vec<ApiKey>, anyhow::Error> {
+        let keys = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT k.id, k.namespace_id, k.user_id, k.name, COALESCE(m.role, k.role) as "role!: NamespaceRole", k.prefix,
+                   k.created_by, k.created_at, k.expires_at, k.last_used_at, k.revoked_at, k.scopes
+            FROM api_keys k
+                LEFT JOIN namespace_members m
+                ON m.namespace_id = k.namespace_id AND m.user_id = k.user_id
+            WHERE k.namespace_id = $1
+            ORDER BY k.created_at DESC
+            "#,
+            ns_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    async fn find_credentials(&self, prefix: &str) -> Result<Option<ApiKeyCredentials>, anyhow::Error> {
+        let credentials = sqlx::query_as!(
+            ApiKeyCredentials,
+            r#"
+            SELECT k.id, k.namespace_id, k.user_id, u.email as "email: String", k.secret_hash, k.expires_at, k.revoked_at, k.scopes
+            FROM api_keys k
+                JOIN users u
+                ON u.id = k.user_id
+            WHERE k.prefix = $1 AND u.disabled_at IS NULL
+            "#,
+            prefix
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(credentials)
+    }
+
+    async fn touch(&self, key_id: Uuid) -> Result<(), anyhow::Error> {
+        // a busy key would otherwise write on every request
+        sqlx::query!(
+            r#"
+            UPDATE api_keys SET last_used_at = now()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < now() - interval '1 minute')
+            "#,
+            key_id
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn revoke(&self, actor: Uuid, ns_id: Uuid, key_id: Uuid) -> Result<ApiKey, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            SELECT id, namespace_id, user_id, name, role as "role: NamespaceRole", prefix, created_by, created_at, expires_at, last_used_at, revoked_at, scopes
+            FROM api_keys
+            WHERE id = $1 AND namespace_id = $2
+            FOR UPDATE
+            "#,
+            key_id,
+            ns_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(ApiKeyError::NotFound)?;
+
+        if key.revoked_at.is_some() {
+            bail!(ApiKeyError::AlreadyRevoked);
+        }
+
+        let key = sqlx::query_as!(
+            ApiKey,
+            r#"
+            UPDATE api_keys SET revoked_at = now()
+            WHERE id = $1
+            RETURNING id, namespace_id, user_id, name, role as "role: NamespaceRole", prefix, created_by, created_at, expires_at, last_used_at, revoked_at, scopes
+            "#,
+            key.id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // the service account stays for the audit trail but holds nothing anymore
+        sqlx::query!(
+            r#"
+            DELETE FROM namespace_members WHERE user_id = $1
+            "#,
+            key.user_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("api_key.revoke")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("api_key", key.id)
+            .meta(json!({ "name": key.name, "prefix": key.prefix }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(key)
+    }
+}
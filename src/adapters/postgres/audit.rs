@@ -0,0 +1,96 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+use crate::core::domain::audit::{ChainedEntry, NewAuditEntry, GENESIS_HASH, GLOBAL_CHAIN};
+
+/// Appends an audit entry on the given connection, so it commits or rolls back
+/// together with the change it describes.
+///
+/// The entry is linked to the head of its namespace's chain. Appends to one chain are
+/// serialized by a transaction-level advisory lock, so the connection must be in a transaction.
+pub(super) async fn append(conn: &mut PgConnection, entry: &NewAuditEntry) -> Result<(), anyhow::Error> {
+    let chain_id = entry.namespace_id.unwrap_or(GLOBAL_CHAIN);
+
+    sqlx::query!(
+        r#"
+        SELECT pg_advisory_xact_lock(hashtextextended('audit_logs:' || $1::uuid::text, 0))
+        "#,
+        chain_id,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let head = sqlx::query!(
+        r#"
+        SELECT seq as "seq!", hash as "hash!"
+        FROM audit_logs
+        WHERE chain_id = $1 AND seq IS NOT NULL
+        ORDER BY seq DESC
+        LIMIT 1
+        "#,
+        chain_id,
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let (seq, prev_hash) = match head {
+        Some(h) => (h.seq + 1, h.hash),
+        None => (1, GENESIS_HASH.to_vec()),
+    };
+
+    // hashed at the precision the column keeps
+    let now = Utc::now();
+    let created_at = DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now);
+
+    let mut chained = ChainedEntry {
+        id: 0,
+        chain_id,
+        seq,
+        actor_id: entry.user_id,
+        action: entry.action.clone(),
+        object_type: entry.object_type.clone(),
+        object_id: entry.object_id,
+        meta: entry.meta.clone(),
+        created_at,
+        prev_hash: Vec::new(),
+        hash: Vec::new(),
+    };
+    chained.hash = chained.compute_hash(&prev_hash);
+    chained.prev_hash = prev_hash;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO audit_logs (namespace_id, user_id, action, object_type, object_id, meta, created_at, chain_id, seq, actor_id, prev_hash, hash)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+        entry.namespace_id,
+        entry.user_id,
+        chained.action,
+        chained.object_type,
+        chained.object_id,
+        chained.meta,
+        chained.created_at,
+        chained.chain_id,
+        chained.seq,
+        chained.actor_id,
+        chained.prev_hash,
+        chained.hash,
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::audit::{AuditCheckpoint, AuditEntry, AuditFilter, ChainHead, ChainedEntry, NewAuditEntry};
+use crate::core::ports::audit::AuditLog;
+use super::audit;
+
+pub struct PostgresAuditLog {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresAuditLog {
+    pub fn new(pool: Arc<PgPool>) -> impl AuditLog {
+        PostgresAuditLog {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLog for PostgresAuditLog {
+    async fn record(&self, entry: &NewAuditEntry) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        audit::append(&mut tx, entry).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn query(&self, filter: &AuditFilter, limit: i64) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT id, namespace_id, user_id, action, object_type, object_id, meta, created_at
+            FROM audit_logs
+            WHERE ($1::uuid IS NULL OR namespace_id = $1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+              AND ($3::text IS NULL OR action = $3 OR starts_with(action, $3 || '.'))
+              AND ($4::text IS NULL OR object_type = $4)
+              AND ($5::uuid IS NULL OR object_id = $5)
+              AND ($6::timestamptz IS NULL OR created_at >= $6)
+              AND ($7::timestamptz IS NULL OR created_at < $7)
+              AND ($8::bigint IS NULL OR id < $8)
+            ORDER BY id DESC
+            LIMIT $9
+            "#,
+            filter.namespace_id,
+            filter.actor,
+            filter.action,
+            filter.object_type,
+            filter.object_id,
+            filter.since,
+            filter.until,
+            filter.before,
+            limit,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    async fn chain_entries(&self, chain_id: Uuid, after_seq: i64, limit: i64) -> Result<Vec<ChainedEntry>, anyhow::Error> {
+        let entries = sqlx::query_as!(
+            ChainedEntry,
+            r#"
+            SELECT id, chain_id as "chain_id!", seq as "seq!", actor_id, action, object_type, object_id, meta, created_at,
+                   prev_hash as "prev_hash!", hash as "hash!"
+            FROM audit_logs
+            WHERE chain_id = $1 AND seq > $2
+            ORDER BY seq
+            LIMIT $3
+            "#,
+            chain_id,
+            after_seq,
+            limit,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    async fn chain_heads(&self) -> Result<Vec<ChainHead>, anyhow::Error> {
+        let heads = sqlx::query_as!(
+            ChainHead,
+            r#"
+            SELECT DISTINCT ON (chain_id) chain_id as "chain_id!", seq as "seq!", hash as "hash!"
+            FROM audit_logs
+            WHERE chain_id IS NOT NULL
+            ORDER BY chain_id, seq DESC
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(heads)
+    }
+
+    async fn save_checkpoint(&self, head: &ChainHead, key_id: &str, signature: &[u8]) -> Result<AuditCheckpoint, anyhow::Error> {
+        let checkpoint = sqlx::query_as!(
+            AuditCheckpoint,
+            r#"
+            INSERT INTO audit_checkpoints (chain_id, seq, hash, key_id, signature)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, chain_id, seq, hash, key_id, signature, created_at
+            "#,
+            head.chain_id,
+            head.seq,
+            head.hash,
+            key_id,
+            signature,
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(checkpoint)
+    }
+
+    async fn latest_checkpoint(&self, chain_id: Uuid) -> Result<Option<AuditCheckpoint>, anyhow::Error> {
+        let checkpoint = sqlx::query_as!(
+            AuditCheckpoint,
+            r#"
+            SELECT id, chain_id, seq, hash, key_id, signature, created_at
+            FROM audit_checkpoints
+            WHERE chain_id = $1
+            ORDER BY seq DESC
+            LIMIT 1
+            "#,
+            chain_id,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(checkpoint)
+    }
+
+    async fn checkpoints(&self, chain_id: Option<Uuid>) -> Result<Vec<AuditCheckpoint>, anyhow::Error> {
+        let checkpoints = sqlx::query_as!(
+            AuditCheckpoint,
+            r#"
+            SELECT id, chain_id, seq, hash, key_id, signature, created_at
+            FROM audit_checkpoints
+            WHERE ($1::uuid IS NULL OR chain_id = $1)
+            ORDER BY id DESC
+            "#,
+            chain_id,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(checkpoints)
+    }
+}
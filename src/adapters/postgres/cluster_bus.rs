@@ -0,0 +1,77 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::Mutex;
+
+use crate::core::domain::cluster::ClusterEvent;
+use crate::core::ports::cluster::ClusterBus;
+
+/// Channel every node LISTENs on.
+const CHANNEL: &str = "xarxa_cluster";
+
+/// Cluster events over Postgres NOTIFY; delivery is at most once, nodes that are
+/// down while an event is published never see it.
+pub struct PostgresClusterBus {
+    pool: Arc<PgPool>,
+    listener: Mutex<Option<PgListener>>,
+}
+
+impl PostgresClusterBus {
+    pub fn new(pool: Arc<PgPool>) -> impl ClusterBus {
+        PostgresClusterBus {
+            pool,
+            listener: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl ClusterBus for PostgresClusterBus {
+    async fn publish(&self, event: &ClusterEvent) -> Result<(), anyhow::Error> {
+        let payload = serde_json::to_string(event)?;
+
+        sqlx::query!(
+            r#"
+            SELECT pg_notify($1, $2)
+            "#,
+            CHANNEL,
+            payload,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<ClusterEvent, anyhow::Error> {
+        let mut guard = self.listener.lock().await;
+
+        let mut listener = match guard.take() {
+            Some(listener) => listener,
+            None => {
+                let mut listener = PgListener::connect_with(&self.pool).await?;
+                listener.listen(CHANNEL).await?;
+                listener
+            }
+        };
+
+        // on error the listener is dropped, the next call starts over with a fresh connection
+        let notification = listener.recv().await?;
+        *guard = Some(listener);
+
+        Ok(serde_json::from_str(notification.payload())?)
+    }
+}
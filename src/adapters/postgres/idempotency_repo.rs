@@ -0,0 +1,130 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::idempotency::{IdempotencyClaim, StoredResponse};
+use crate::core::ports::storage::IdempotencyRepository;
+
+pub struct PostgresIdempotencyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresIdempotencyRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl IdempotencyRepository {
+        PostgresIdempotencyRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for PostgresIdempotencyRepository {
+    async fn claim(&self, user_id: Uuid, key: &str, request_hash: &str, locked_until: DateTime<Utc>, expires_at: DateTime<Utc>) -> Result<IdempotencyClaim, anyhow::Error> {
+        loop {
+            // the primary key lets only one request claim a key; an expired one is taken over,
+            // as is one whose request went away before its response was kept
+            let claimed = sqlx::query_scalar!(
+                r#"
+                INSERT INTO idempotency_keys (user_id, key, request_hash, locked_until, expires_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (user_id, key) DO UPDATE
+                SET request_hash = EXCLUDED.request_hash, status = NULL, content_type = NULL, location = NULL, body = NULL, withheld = false,
+                    created_at = now(), locked_until = EXCLUDED.locked_until, expires_at = EXCLUDED.expires_at
+                WHERE idempotency_keys.expires_at < now()
+                   OR (idempotency_keys.status IS NULL AND idempotency_keys.locked_until < now())
+                RETURNING user_id
+                "#,
+                user_id,
+                key,
+                request_hash,
+                locked_until,
+                expires_at,
+            )
+            .fetch_optional(&*self.pool)
+            .await?;
+
+            if claimed.is_some() {
+                return Ok(IdempotencyClaim::New);
+            }
+
+            let existing = sqlx::query!(
+                r#"
+                SELECT request_hash, status, content_type, location, body, withheld
+                FROM idempotency_keys
+                WHERE user_id = $1 AND key = $2
+                "#,
+                user_id,
+                key,
+            )
+            .fetch_optional(&*self.pool)
+            .await?;
+
+            // released in between, try to claim it again
+            let Some(existing) = existing else {
+                continue;
+            };
+
+            let response = existing.status.map(|status| StoredResponse {
+                status: status as u16,
+                content_type: existing.content_type,
+                location: existing.location,
+                body: existing.body.unwrap_or_default(),
+                withheld: existing.withheld,
+            });
+
+            return Ok(IdempotencyClaim::Existing {
+                request_hash: existing.request_hash,
+                response,
+            });
+        }
+    }
+
+    async fn complete(&self, user_id: Uuid, key: &str, response: &StoredResponse) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys SET status = $3, content_type = $4, location = $5, body = $6, withheld = $7
+            WHERE user_id = $1 AND key = $2
+            "#,
+            user_id,
+            key,
+            response.status as i16,
+            response.content_type,
+            response.location,
+            response.body,
+            response.withheld,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release(&self, user_id: Uuid, key: &str) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2 AND status IS NULL
+            "#,
+            user_id,
+            key,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::oidc::OidcLogin;
+use crate::core::domain::user::User;
+use crate::core::ports::storage::IdentityRepository;
+
+pub struct PostgresIdentityRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresIdentityRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl IdentityRepository {
+        PostgresIdentityRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityRepository for PostgresIdentityRepository {
+    async fn save_login(&self, login: &OidcLogin) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO oidc_logins (state, nonce, code_verifier, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            login.state,
+            login.nonce,
+            login.code_verifier,
+            login.created_at,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn take_login(&self, state: &str, not_before: DateTime<Utc>) -> Result<Option<OidcLogin>, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let login = sqlx::query_as!(
+            OidcLogin,
+            r#"
+            DELETE FROM oidc_logins
+            WHERE state = $1
+            RETURNING state, nonce, code_verifier, created_at
+            "#,
+            state,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // abandoned logins are never called back for, sweep them while we're here
+        sqlx::query!(
+            r#"
+            DELETE FROM oidc_logins
+            WHERE created_at < $1
+            "#,
+            not_before,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(login.filter(|l| l.created_at >= not_before))
+    }
+
+    async fn find_user(&self, issuer: &str, subject: &str) -> Result<Option<User>, anyhow::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT u.id, u.email, u.token_hash as token, u.super_admin, u.created_at, u.disabled_at
+            FROM user_identities i
+            JOIN users u ON u.id = i.user_id
+            WHERE i.issuer = $1 AND i.subject = $2
+            "#,
+            issuer,
+            subject,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn link(&self, issuer: &str, subject: &str, user_id: Uuid) -> Result<bool, anyhow::Error> {
+        let inserted = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_identities (issuer, subject, user_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (issuer, subject) DO UPDATE SET last_login = now()
+            RETURNING (xmax = 0) as "inserted!"
+            "#,
+            issuer,
+            subject,
+            user_id,
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(inserted)
+    }
+}
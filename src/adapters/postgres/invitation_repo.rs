@@ -0,0 +1,247 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::invitation::{Invitation, InvitationError, Invitee, NewInvitationRecord};
+use crate::core::domain::namespace::NamespaceRole;
+use crate::core::ports::storage::InvitationRepository;
+use super::audit;
+
+pub struct PostgresInvitationRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresInvitationRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl InvitationRepository {
+        PostgresInvitationRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for PostgresInvitationRepository {
+    async fn create(&self, actor: Uuid, ns_id: Uuid, i: &NewInvitationRecord) -> Result<Invitation, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE namespace_invitations SET revoked_at = now()
+            WHERE namespace_id = $1 AND email = $2 AND accepted_at IS NULL AND revoked_at IS NULL
+            "#,
+            ns_id,
+            i.email,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let invitation = sqlx::query_as!(
+            Invitation,
+            r#"
+            INSERT INTO namespace_invitations (id, namespace_id, email, role, token_hash, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, namespace_id, email as "email: String", role as "role: NamespaceRole",
+                invited_by, created_at, expires_at, accepted_at, revoked_at
+            "#,
+            i.id,
+            ns_id,
+            i.email,
+            i.role as NamespaceRole,
+            i.token_hash,
+            actor,
+            i.expires_at,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("invitation.create")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("invitation", invitation.id)
+            .meta(json!({ "email": invitation.email, "role": invitation.role }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(invitation)
+    }
+
+    async fn find_pending(&self, ns_id: Uuid) -> Result<Vec<Invitation>, anyhow::Error> {
+        let invitations = sqlx::query_as!(
+            Invitation,
+            r#"
+            SELECT id, namespace_id, email as "email: String", role as "role: NamespaceRole",
+                invited_by, created_at, expires_at, accepted_at, revoked_at
+            FROM namespace_invitations
+            WHERE namespace_id = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > now()
+            ORDER BY created_at DESC
+            "#,
+            ns_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    async fn find_with_hash(&self, id: Uuid) -> Result<Option<(Invitation, String)>, anyhow::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, namespace_id, email as "email: String", role as "role: NamespaceRole", token_hash,
+                invited_by, created_at, expires_at, accepted_at, revoked_at
+            FROM namespace_invitations
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(row.map(|r| (
+            Invitation {
+                id: r.id,
+                namespace_id: r.namespace_id,
+                email: r.email,
+                role: r.role,
+                invited_by: r.invited_by,
+                created_at: r.created_at,
+                expires_at: r.expires_at,
+                accepted_at: r.accepted_at,
+                revoked_at: r.revoked_at,
+            },
+            r.token_hash,
+        )))
+    }
+
+    async fn revoke(&self, actor: Uuid, ns_id: Uuid, id: Uuid) -> Result<Invitation, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let invitation = sqlx::query_as!(
+            Invitation,
+            r#"
+            UPDATE namespace_invitations SET revoked_at = now()
+            WHERE id = $1 AND namespace_id = $2 AND accepted_at IS NULL AND revoked_at IS NULL
+            RETURNING id, namespace_id, email as "email: String", role as "role: NamespaceRole",
+                invited_by, created_at, expires_at, accepted_at, revoked_at
+            "#,
+            id,
+            ns_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(InvitationError::NotFound)?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("invitation.revoke")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("invitation", id)
+            .meta(json!({ "email": invitation.email }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(invitation)
+    }
+
+    async fn accept(&self, id: Uuid, invitee: &Invitee) -> Result<Invitation, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // a new account only exists once the accept commits, a failed one rolls it back
+        let user_id = match invitee {
+            Invitee::Existing(user_id) => *user_id,
+            Invitee::New { email, token_hash } => {
+                let user_id = Uuid::now_v7();
+
+                sqlx::query!(
+                    r#"
+                    INSERT INTO users (id, email, token_hash)
+                    VALUES ($1, $2, $3)
+                    "#,
+                    user_id,
+                    email,
+                    token_hash,
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                audit::append(&mut tx, &NewAuditEntry::new("user.signup")
+                    .actor(user_id)
+                    .object("user", user_id)
+                    .meta(json!({ "email": email }))
+                ).await?;
+
+                user_id
+            }
+        };
+
+        // the row lock makes a second, concurrent accept see it as accepted
+        let invitation = sqlx::query_as!(
+            Invitation,
+            r#"
+            UPDATE namespace_invitations SET accepted_at = now(), accepted_by = $2
+            WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > now()
+            RETURNING id, namespace_id, email as "email: String", role as "role: NamespaceRole",
+                invited_by, created_at, expires_at, accepted_at, revoked_at
+            "#,
+            id,
+            user_id,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(invitation) = invitation else {
+            bail!(InvitationError::Invalid);
+        };
+
+        let added = sqlx::query!(
+            r#"
+            INSERT INTO namespace_members (namespace_id, user_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (namespace_id, user_id) DO NOTHING
+            "#,
+            invitation.namespace_id,
+            user_id,
+            invitation.role as NamespaceRole,
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() > 0;
+
+        audit::append(&mut tx, &NewAuditEntry::new("invitation.accept")
+            .namespace(invitation.namespace_id)
+            .actor(user_id)
+            .object("invitation", invitation.id)
+            .meta(json!({ "email": invitation.email, "role": invitation.role, "already_member": !added }))
+        ).await?;
+
+        if added {
+            audit::append(&mut tx, &NewAuditEntry::new("member.add")
+                .namespace(invitation.namespace_id)
+                .actor(user_id)
+                .object("user", user_id)
+                .meta(json!({ "role": invitation.role, "invitation": invitation.id }))
+            ).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(invitation)
+    }
+}
@@ -1,4 +1,15 @@
 pub mod workflow_repo;
 pub mod user_repo;
 pub mod namespace_repo;
-pub mod workflow_dto;
\ No newline at end of file
+pub mod workflow_dto;
+pub mod audit_log;
+pub mod api_key_repo;
+pub mod session_repo;
+pub mod identity_repo;
+pub mod admin_repo;
+pub mod cluster_bus;
+pub mod invitation_repo;
+pub mod run_repo;
+pub mod idempotency_repo;
+pub mod search_attribute_repo;
+mod audit;
\ No newline at end of file
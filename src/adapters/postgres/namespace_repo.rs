@@ -11,13 +11,19 @@
  * SPDX-License-Identifier: AGPL-3.0-or-later
  */
 
+use anyhow::bail;
 use async_trait::async_trait;
+use serde_json::json;
 use std::sync::Arc;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
-use crate::core::domain::namespace::{NewNamespace, Namespace, NamespaceRole};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::namespace::{
+    DeletedNamespace, MemberView, MembershipError, NamespaceError, NewNamespace, Namespace, NamespaceRole, ResolvedSlug,
+};
 use crate::core::ports::storage::NamespaceRepository;
+use super::audit;
 
 pub struct PostgresNamespaceRepository {
     pool: Arc<PgPool>,
@@ -37,13 +43,15 @@ impl NamespaceRepository for PostgresNamespaceRepository {
         let namespace_id = Uuid::now_v7();
         
         let mut tx = self.pool.begin().await?;
+
+        ensure_slug_free(&mut tx, &ns.slug, None).await?;
         
         let namespace = sqlx::query_as!(
             Namespace,
             r#"
             INSERT INTO namespaces (id, slug, created_by)
             VALUES ($1, $2, $3)
-            RETURNING id, slug, created_by, created_at
+            RETURNING id, slug, created_by, created_at, archived_at
             "#,
             namespace_id,
             ns.slug,
@@ -73,7 +81,7 @@ impl NamespaceRepository for PostgresNamespaceRepository {
         let namespaces = sqlx::query_as!(
             Namespace,
             r#"
-            SELECT n.id, n.slug, n.created_by, n.created_at
+            SELECT n.id, n.slug, n.created_by, n.created_at, n.archived_at
             FROM namespaces n
             INNER JOIN namespace_members nm ON n.id = nm.namespace_id
             WHERE nm.user_id = $1
@@ -102,4 +110,429 @@ impl NamespaceRepository for PostgresNamespaceRepository {
 
         Ok(role.map(|r| r.role))
     }
+
+    async fn find_by_id(&self, ns_id: Uuid) -> Result<Option<Namespace>, anyhow::Error> {
+        let namespace = sqlx::query_as!(
+            Namespace,
+            r#"
+            SELECT id, slug, created_by, created_at, archived_at
+            FROM namespaces
+            WHERE id = $1
+            "#,
+            ns_id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(namespace)
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<ResolvedSlug>, anyhow::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT n.id, n.slug, n.created_by, n.created_at, n.archived_at, (n.slug <> $1) as "alias!"
+            FROM namespaces n
+            WHERE n.slug = $1
+               OR n.id = (SELECT a.namespace_id FROM namespace_slug_aliases a WHERE a.slug = $1)
+            "#,
+            slug
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(row.map(|r| ResolvedSlug {
+            namespace: Namespace {
+                id: r.id,
+                slug: r.slug,
+                created_by: r.created_by,
+                created_at: r.created_at,
+                archived_at: r.archived_at,
+            },
+            alias: r.alias,
+        }))
+    }
+
+    async fn rename(&self, actor: Uuid, ns_id: Uuid, slug: &str) -> Result<Namespace, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query_scalar!(
+            r#"
+            SELECT slug FROM namespaces WHERE id = $1 FOR UPDATE
+            "#,
+            ns_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(NamespaceError::NotFound)?;
+
+        ensure_slug_free(&mut tx, slug, Some(ns_id)).await?;
+
+        // taking back one of our own old slugs
+        sqlx::query!(
+            r#"
+            DELETE FROM namespace_slug_aliases WHERE slug = $1 AND namespace_id = $2
+            "#,
+            slug,
+            ns_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO namespace_slug_aliases (slug, namespace_id)
+            VALUES ($1, $2)
+            ON CONFLICT (slug) DO NOTHING
+            "#,
+            previous,
+            ns_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let namespace = sqlx::query_as!(
+            Namespace,
+            r#"
+            UPDATE namespaces SET slug = $2
+            WHERE id = $1
+            RETURNING id, slug, created_by, created_at, archived_at
+            "#,
+            ns_id,
+            slug,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("namespace.rename")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("namespace", ns_id)
+            .meta(json!({ "slug": slug, "previous_slug": previous }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(namespace)
+    }
+
+    async fn set_archived(&self, actor: Uuid, ns_id: Uuid, archived: bool) -> Result<Namespace, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let namespace = sqlx::query_as!(
+            Namespace,
+            r#"
+            UPDATE namespaces
+            SET archived_at = CASE WHEN $2 THEN COALESCE(archived_at, now()) ELSE NULL END
+            WHERE id = $1
+            RETURNING id, slug, created_by, created_at, archived_at
+            "#,
+            ns_id,
+            archived,
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(NamespaceError::NotFound)?;
+
+        let action = if archived { "namespace.archive" } else { "namespace.unarchive" };
+
+        audit::append(&mut tx, &NewAuditEntry::new(action)
+            .namespace(ns_id)
+            .actor(actor)
+            .object("namespace", ns_id)
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(namespace)
+    }
+
+    async fn delete(&self, actor: Uuid, ns_id: Uuid) -> Result<DeletedNamespace, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let slug = sqlx::query_scalar!(
+            r#"
+            SELECT slug FROM namespaces WHERE id = $1 FOR UPDATE
+            "#,
+            ns_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(NamespaceError::NotFound)?;
+
+        let workflow_keys = sqlx::query_scalar!(
+            r#"
+            SELECT key as "key: String" FROM workflows WHERE namespace_id = $1
+            "#,
+            ns_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let storage_urls = sqlx::query_scalar!(
+            r#"
+            SELECT wv.storage_url as "storage_url!"
+            FROM workflow_versions wv
+                JOIN workflows w
+                ON w.id = wv.workflow_id
+            WHERE w.namespace_id = $1 AND wv.storage_url IS NOT NULL AND wv.storage_url <> ''
+            "#,
+            ns_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // the runs go with the namespace (ON DELETE CASCADE); an executor still holding one
+        // finds it gone at its next write or lease renewal and stops the running activity
+        let deleted_live_runs = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) as "count!" FROM (
+                SELECT id FROM workflow_runs
+                WHERE namespace_id = $1 AND state IN ('pending', 'running')
+                FOR UPDATE
+            ) active
+            "#,
+            ns_id
+        )
+        .fetch_one(&mut *tx)
+        .await? as u64;
+
+        // the entry outlives the namespace, audit_logs.namespace_id is nulled by the FK
+        audit::append(&mut tx, &NewAuditEntry::new("namespace.delete")
+            .actor(actor)
+            .object("namespace", ns_id)
+            .meta(json!({ "slug": slug, "deleted_live_runs": deleted_live_runs }))
+        ).await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM namespaces WHERE id = $1
+            "#,
+            ns_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(DeletedNamespace { slug, workflow_keys, storage_urls, deleted_live_runs })
+    }
+
+    async fn members(&self, ns_id: Uuid) -> Result<Vec<MemberView>, anyhow::Error> {
+        let members = sqlx::query_as!(
+            MemberView,
+            r#"
+            SELECT nm.user_id, u.email as "email: String", nm.role as "role: NamespaceRole", nm.joined_at
+            FROM namespace_members nm
+            INNER JOIN users u ON u.id = nm.user_id
+            WHERE nm.namespace_id = $1
+            ORDER BY nm.joined_at
+            "#,
+            ns_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    async fn add_member(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid, role: NamespaceRole) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let members = lock_members(&mut tx, ns_id).await?;
+        if members.iter().any(|(uid, _)| *uid == user_id) {
+            bail!(MembershipError::AlreadyMember);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO namespace_members (namespace_id, user_id, role)
+            VALUES ($1, $2, $3)
+            "#,
+            ns_id,
+            user_id,
+            role as NamespaceRole,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("member.add")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("user", user_id)
+            .meta(json!({ "role": role }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_member_role(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid, role: NamespaceRole) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let members = lock_members(&mut tx, ns_id).await?;
+        let previous = member_role(&members, user_id)?;
+
+        if previous == NamespaceRole::Owner && role != NamespaceRole::Owner && owner_count(&members) == 1 {
+            bail!(MembershipError::LastOwner);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE namespace_members SET role = $3
+            WHERE namespace_id = $1 AND user_id = $2
+            "#,
+            ns_id,
+            user_id,
+            role as NamespaceRole,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("member.role_change")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("user", user_id)
+            .meta(json!({ "role": role, "previous_role": previous }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn remove_member(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let members = lock_members(&mut tx, ns_id).await?;
+        let previous = member_role(&members, user_id)?;
+
+        if previous == NamespaceRole::Owner && owner_count(&members) == 1 {
+            bail!(MembershipError::LastOwner);
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM namespace_members
+            WHERE namespace_id = $1 AND user_id = $2
+            "#,
+            ns_id,
+            user_id,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let action = if actor == user_id { "member.leave" } else { "member.remove" };
+
+        audit::append(&mut tx, &NewAuditEntry::new(action)
+            .namespace(ns_id)
+            .actor(actor)
+            .object("user", user_id)
+            .meta(json!({ "previous_role": previous }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn transfer_ownership(&self, actor: Uuid, ns_id: Uuid, new_owner: Uuid) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let members = lock_members(&mut tx, ns_id).await?;
+        if member_role(&members, actor)? != NamespaceRole::Owner {
+            bail!(MembershipError::NotAllowed("Only an owner can transfer ownership".to_string()));
+        }
+        let previous = member_role(&members, new_owner)?;
+
+        // the new owner is promoted first, so the namespace is never left without an owner
+        sqlx::query!(
+            r#"
+            UPDATE namespace_members SET role = $3
+            WHERE namespace_id = $1 AND user_id = $2
+            "#,
+            ns_id,
+            new_owner,
+            NamespaceRole::Owner as NamespaceRole,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if new_owner != actor {
+            sqlx::query!(
+                r#"
+                UPDATE namespace_members SET role = $3
+                WHERE namespace_id = $1 AND user_id = $2
+                "#,
+                ns_id,
+                actor,
+                NamespaceRole::Admin as NamespaceRole,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new("namespace.transfer_ownership")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("user", new_owner)
+            .meta(json!({ "previous_role": previous }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// A slug is free when no other namespace uses it, now or as an alias.
+async fn ensure_slug_free(conn: &mut PgConnection, slug: &str, own: Option<Uuid>) -> Result<(), anyhow::Error> {
+    let taken = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM namespaces WHERE slug = $1 AND id IS DISTINCT FROM $2
+            UNION ALL
+            SELECT 1 FROM namespace_slug_aliases WHERE slug = $1 AND namespace_id IS DISTINCT FROM $2
+        ) as "taken!"
+        "#,
+        slug,
+        own,
+    )
+    .fetch_one(conn)
+    .await?;
+
+    if taken {
+        bail!(NamespaceError::SlugTaken(slug.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Locks the member rows of a namespace for the rest of the transaction,
+/// so concurrent changes can not both remove the last owner.
+async fn lock_members(conn: &mut PgConnection, ns_id: Uuid) -> Result<Vec<(Uuid, NamespaceRole)>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT user_id, role as "role: NamespaceRole"
+        FROM namespace_members
+        WHERE namespace_id = $1
+        FOR UPDATE
+        "#,
+        ns_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| (r.user_id, r.role)).collect())
+}
+
+fn member_role(members: &[(Uuid, NamespaceRole)], user_id: Uuid) -> Result<NamespaceRole, anyhow::Error> {
+    members.iter()
+        .find(|(uid, _)| *uid == user_id)
+        .map(|(_, role)| *role)
+        .ok_or_else(|| MembershipError::NotMember.into())
+}
+
+fn owner_count(members: &[(Uuid, NamespaceRole)]) -> usize {
+    members.iter().filter(|(_, role)| *role == NamespaceRole::Owner).count()
 }
\ No newline at end of file
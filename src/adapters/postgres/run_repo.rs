@@ -0,0 +1,732 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde_json::{json, Map, Value as JsonValue};
+use std::sync::Arc;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::search::SearchQuery;
+use crate::core::domain::run::{event, ActivityAttempt, ClaimedRun, RunClaim, NewRun, NewRunEvent, ReusePolicy, Run, RunError, RunEvent, RunFilter, RunState, RunStatus, RunSummary, StoppedRun};
+use crate::core::ports::storage::RunRepository;
+use super::audit;
+use super::workflow_dto::WorkflowVersion as WorkflowVersionDTO;
+
+pub struct PostgresRunRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresRunRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl RunRepository {
+        PostgresRunRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl RunRepository for PostgresRunRepository {
+    async fn create(&self, r: &NewRun) -> Result<Run, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(business_id) = &r.business_id {
+            ensure_business_id_free(&mut tx, r.namespace_id, business_id, r.reuse_policy).await?;
+        }
+
+        // keeps the parent from being stopped before its new child can be reached
+        if let Some(parent_run_id) = r.parent_run_id {
+            let parent = sqlx::query_scalar!(
+                r#"
+                SELECT state as "state: RunState" FROM workflow_runs WHERE id = $1 AND namespace_id = $2 FOR SHARE
+                "#,
+                parent_run_id,
+                r.namespace_id,
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(RunError::NotFound)?;
+
+            if parent.is_finished() {
+                bail!(RunError::NotActive(parent));
+            }
+        }
+
+        // starts are serialized by the business id lock, the unique index stays as a guard for open runs
+        let run = sqlx::query_as!(
+            Run,
+            r#"
+            INSERT INTO workflow_runs (id, namespace_id, workflow_id, workflow_version_id, workflow_key, workflow_type, version, business_id, reuse_policy,
+                                       search_attributes, parent_run_id, input, started_by, state)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 'pending')
+            RETURNING id, namespace_id as "namespace_id!", workflow_id, workflow_key as "workflow_key!", workflow_type as "workflow_type!",
+                      version as "version!", business_id, search_attributes, parent_run_id, state as "state: RunState", cancel_requested_at,
+                   input, result, failure, started_by, started_at, updated_at, completed_at
+            "#,
+            r.id,
+            r.namespace_id,
+            r.workflow_id,
+            r.workflow_version_id,
+            r.workflow_key,
+            r.workflow_type,
+            r.version,
+            r.business_id,
+            r.reuse_policy as ReusePolicy,
+            r.search_attributes,
+            r.parent_run_id,
+            r.input,
+            r.started_by,
+        )
+        .fetch_one(&mut *tx)
+        .await;
+
+        let run = match run {
+            Ok(run) => run,
+            Err(sqlx::Error::Database(db)) if db.constraint() == Some("workflow_runs_business_id_open_idx") => {
+                // the transaction is done for, the run that won is looked up outside of it
+                drop(tx);
+                let winner = sqlx::query_scalar!(
+                    r#"
+                    SELECT id FROM workflow_runs
+                    WHERE namespace_id = $1 AND business_id = $2 AND state IN ('pending', 'running') AND reuse_policy <> 'allow_always'
+                    "#,
+                    r.namespace_id,
+                    r.business_id,
+                )
+                .fetch_optional(&*self.pool)
+                .await?;
+
+                match winner {
+                    Some(winner) => bail!(RunError::BusinessIdInUse(winner)),
+                    None => bail!("The run holding business id '{}' finished while another was started, try again", r.business_id.as_deref().unwrap_or_default()),
+                }
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        insert_event(&mut tx, r.id, &NewRunEvent::new(event::RUN_STARTED, json!({
+            "workflow_type": r.workflow_type,
+            "version": r.version,
+            "parent_run_id": r.parent_run_id,
+        }))).await?;
+
+        audit::append(&mut tx, &NewAuditEntry::new("run.start")
+            .namespace(r.namespace_id)
+            .actor(r.started_by)
+            .object("run", r.id)
+            .meta(json!({ "key": r.workflow_key, "version": r.version, "workflow_type": r.workflow_type, "business_id": r.business_id }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(run)
+    }
+
+    async fn find(&self, ns_id: Uuid, run_id: Uuid) -> Result<Option<Run>, anyhow::Error> {
+        let run = sqlx::query_as!(
+            Run,
+            r#"
+            SELECT id, namespace_id as "namespace_id!", workflow_id, workflow_key as "workflow_key!", workflow_type as "workflow_type!",
+                   version as "version!", business_id, search_attributes, parent_run_id, state as "state: RunState", cancel_requested_at,
+                   input, result, failure, started_by, started_at, updated_at, completed_at
+            FROM workflow_runs
+            WHERE id = $1 AND namespace_id = $2
+            "#,
+            run_id,
+            ns_id,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(run)
+    }
+
+    async fn namespace_of(&self, run_id: Uuid) -> Result<Option<Uuid>, anyhow::Error> {
+        let ns_id = sqlx::query_scalar!(
+            r#"
+            SELECT namespace_id as "namespace_id!" FROM workflow_runs WHERE id = $1
+            "#,
+            run_id,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(ns_id)
+    }
+
+    async fn list(&self, ns_id: Uuid, filter: &RunFilter, search: &SearchQuery, limit: i64) -> Result<Vec<RunSummary>, anyhow::Error> {
+        let mut names = Vec::with_capacity(search.ranges.len());
+        let mut kinds = Vec::with_capacity(search.ranges.len());
+        let mut ops = Vec::with_capacity(search.ranges.len());
+        let mut values = Vec::with_capacity(search.ranges.len());
+        for c in &search.ranges {
+            names.push(c.name.as_str());
+            kinds.push(c.kind.as_str());
+            ops.push(c.op.as_str());
+            values.push(c.value.as_str());
+        }
+
+        // equality goes through the GIN index, ranges are checked on what it leaves
+        let runs = sqlx::query_as!(
+            RunSummary,
+            r#"
+            SELECT id, workflow_id, workflow_key as "workflow_key!", workflow_type as "workflow_type!", version as "version!",
+                   business_id, search_attributes, parent_run_id, state as "state: RunState", cancel_requested_at, started_by, started_at, updated_at, completed_at
+            FROM workflow_runs
+            WHERE namespace_id = $1
+              AND ($2::text IS NULL OR workflow_key = $2)
+              AND ($3::text IS NULL OR version = $3)
+              AND ($4::text IS NULL OR state = $4)
+              AND ($5::text IS NULL OR business_id = $5)
+              AND ($6::timestamptz IS NULL OR started_at >= $6)
+              AND ($7::timestamptz IS NULL OR started_at < $7)
+              AND ($8::timestamptz IS NULL OR completed_at >= $8)
+              AND ($9::timestamptz IS NULL OR completed_at < $9)
+              AND ($10::uuid IS NULL OR id < $10)
+              AND search_attributes @> $11
+              AND NOT EXISTS (
+                  SELECT 1 FROM unnest($12::text[], $13::text[], $14::text[], $15::text[]) AS c(name, kind, op, value)
+                  WHERE NOT COALESCE(CASE c.kind
+                      WHEN 'int' THEN CASE c.op
+                          WHEN '>' THEN (search_attributes ->> c.name)::bigint > c.value::bigint
+                          WHEN '>=' THEN (search_attributes ->> c.name)::bigint >= c.value::bigint
+                          WHEN '<' THEN (search_attributes ->> c.name)::bigint < c.value::bigint
+                          ELSE (search_attributes ->> c.name)::bigint <= c.value::bigint
+                      END
+                      ELSE CASE c.op
+                          WHEN '>' THEN (search_attributes ->> c.name)::timestamptz > c.value::timestamptz
+                          WHEN '>=' THEN (search_attributes ->> c.name)::timestamptz >= c.value::timestamptz
+                          WHEN '<' THEN (search_attributes ->> c.name)::timestamptz < c.value::timestamptz
+                          ELSE (search_attributes ->> c.name)::timestamptz <= c.value::timestamptz
+                      END
+                  END, false)
+              )
+            ORDER BY id DESC
+            LIMIT $16
+            "#,
+            ns_id,
+            filter.workflow_key,
+            filter.version,
+            filter.state as Option<RunState>,
+            filter.business_id,
+            filter.started_since,
+            filter.started_until,
+            filter.completed_since,
+            filter.completed_until,
+            filter.before,
+            JsonValue::Object(search.equals.clone()),
+            &names as &[&str],
+            &kinds as &[&str],
+            &ops as &[&str],
+            &values as &[&str],
+            limit,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    async fn events(&self, run_id: Uuid) -> Result<Vec<RunEvent>, anyhow::Error> {
+        let mut conn = self.pool.acquire().await?;
+
+        load_events(&mut conn, run_id).await
+    }
+
+    async fn claim(&self, lease_secs: f64) -> Result<Option<ClaimedRun>, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // a lease that ran out means the executor holding it is gone, someone else takes over
+        let token = Uuid::now_v7();
+        let Some(row) = sqlx::query!(
+            r#"
+            WITH next AS (
+                SELECT id FROM workflow_runs
+                WHERE state IN ('pending', 'running') AND (claimed_until IS NULL OR claimed_until < now())
+                ORDER BY started_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            UPDATE workflow_runs wr
+            SET state = 'running', claimed_until = now() + make_interval(secs => $1), claim_token = $2, updated_at = now()
+            FROM next
+            WHERE wr.id = next.id
+            RETURNING wr.id, wr.namespace_id as "namespace_id!", wr.workflow_id, wr.workflow_version_id, wr.workflow_key as "workflow_key!",
+                      wr.workflow_type as "workflow_type!", wr.version as "version!", wr.business_id, wr.search_attributes, wr.parent_run_id,
+                      wr.state as "state: RunState", wr.cancel_requested_at, wr.input, wr.result, wr.failure, wr.started_by, wr.started_at, wr.updated_at, wr.completed_at, wr.seq
+            "#,
+            lease_secs,
+            token,
+        )
+        .fetch_optional(&mut *tx)
+        .await? else {
+            return Ok(None);
+        };
+
+        let version = match row.workflow_version_id {
+            Some(version_id) => sqlx::query_as!(
+                WorkflowVersionDTO,
+                r#"
+                SELECT id, workflow_id, version, wasm_md5, wasm_size_bytes, storage_url, created_by, changelog, created_at, input_schema, output_schema, activity_schemas, manifest
+                FROM workflow_versions
+                WHERE id = $1
+                "#,
+                version_id,
+            )
+            .fetch_optional(&mut *tx)
+            .await?,
+            None => None,
+        };
+
+        let events = load_events(&mut tx, row.id).await?;
+
+        tx.commit().await?;
+
+        let storage_url = version.as_ref().and_then(|v| v.storage_url.clone());
+
+        Ok(Some(ClaimedRun {
+            run: Run {
+                id: row.id,
+                namespace_id: row.namespace_id,
+                workflow_id: row.workflow_id,
+                workflow_key: row.workflow_key,
+                workflow_type: row.workflow_type,
+                version: row.version,
+                business_id: row.business_id,
+                search_attributes: row.search_attributes,
+                parent_run_id: row.parent_run_id,
+                state: row.state,
+                cancel_requested_at: row.cancel_requested_at,
+                input: row.input,
+                result: row.result,
+                failure: row.failure,
+                started_by: row.started_by,
+                started_at: row.started_at,
+                updated_at: row.updated_at,
+                completed_at: row.completed_at,
+            },
+            claim: RunClaim {
+                run_id: row.id,
+                token,
+            },
+            seq: row.seq,
+            version: version.map(|v| v.to_domain()),
+            storage_url,
+            events,
+        }))
+    }
+
+    async fn append_events(&self, claim: RunClaim, events: &[NewRunEvent], lease_secs: f64) -> Result<Option<Vec<i32>>, anyhow::Error> {
+        let run_id = claim.run_id;
+        let mut tx = self.pool.begin().await?;
+
+        if !lock_claimed(&mut tx, claim).await? {
+            return Ok(None);
+        }
+
+        let mut seqs = Vec::with_capacity(events.len());
+        for e in events {
+            seqs.push(insert_event(&mut tx, run_id, e).await?);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE workflow_runs SET claimed_until = now() + make_interval(secs => $2), updated_at = now()
+            WHERE id = $1
+            "#,
+            run_id,
+            lease_secs,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(seqs))
+    }
+
+    async fn upsert_search_attributes(&self, claim: RunClaim, attributes: &Map<String, JsonValue>, lease_secs: f64) -> Result<bool, anyhow::Error> {
+        let run_id = claim.run_id;
+        let mut tx = self.pool.begin().await?;
+
+        if !lock_claimed(&mut tx, claim).await? {
+            return Ok(false);
+        }
+
+        let attributes = JsonValue::Object(attributes.clone());
+        insert_event(&mut tx, run_id, &NewRunEvent::new(event::SEARCH_ATTRIBUTES_UPSERTED, attributes.clone())).await?;
+
+        // removed attributes are sent as null, stripping them leaves no nulls behind
+        sqlx::query!(
+            r#"
+            UPDATE workflow_runs
+            SET search_attributes = jsonb_strip_nulls(search_attributes || $2), claimed_until = now() + make_interval(secs => $3), updated_at = now()
+            WHERE id = $1
+            "#,
+            run_id,
+            attributes,
+            lease_secs,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn request_cancel(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, reason: Option<&str>) -> Result<StoppedRun, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let children = lock_active_tree(&mut tx, ns_id, run_id).await?;
+        let ids: Vec<Uuid> = std::iter::once(run_id).chain(children.iter().copied()).collect();
+
+        // asking again changes nothing, the first request stands
+        let requested = sqlx::query_scalar!(
+            r#"
+            UPDATE workflow_runs SET cancel_requested_at = now(), updated_at = now()
+            WHERE id = ANY($1) AND cancel_requested_at IS NULL
+            RETURNING id
+            "#,
+            &ids,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for id in &requested {
+            insert_event(&mut tx, *id, &NewRunEvent::new(event::RUN_CANCEL_REQUESTED, json!({
+                "reason": reason,
+                "actor": actor,
+                "propagated_from": (*id != run_id).then_some(run_id),
+            }))).await?;
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new("run.cancel")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("run", run_id)
+            .meta(json!({ "reason": reason, "children": children }))
+        ).await?;
+
+        let run = find_run(&mut tx, run_id).await?;
+
+        tx.commit().await?;
+
+        Ok(StoppedRun { run, children })
+    }
+
+    async fn terminate(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, reason: Option<&str>) -> Result<StoppedRun, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let children = lock_active_tree(&mut tx, ns_id, run_id).await?;
+        let ids: Vec<Uuid> = std::iter::once(run_id).chain(children.iter().copied()).collect();
+
+        for id in ids {
+            let propagated_from = (id != run_id).then_some(run_id);
+
+            insert_event(&mut tx, id, &NewRunEvent::new(event::RUN_TERMINATED, json!({
+                "reason": reason,
+                "actor": actor,
+                "propagated_from": propagated_from,
+            }))).await?;
+
+            // the executor holding the lease finds the run no longer running and lets go of it
+            sqlx::query!(
+                r#"
+                UPDATE workflow_runs
+                SET state = 'terminated', failure = $2, completed_at = now(), updated_at = now(), claimed_until = NULL, claim_token = NULL
+                WHERE id = $1
+                "#,
+                id,
+                json!({ "kind": "terminated", "message": reason, "actor": actor, "propagated_from": propagated_from }),
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new("run.terminate")
+            .namespace(ns_id)
+            .actor(actor)
+            .object("run", run_id)
+            .meta(json!({ "reason": reason, "children": children }))
+        ).await?;
+
+        let run = find_run(&mut tx, run_id).await?;
+
+        tx.commit().await?;
+
+        Ok(StoppedRun { run, children })
+    }
+
+    async fn renew(&self, claim: RunClaim, lease_secs: f64) -> Result<Option<RunStatus>, anyhow::Error> {
+        let status = sqlx::query!(
+            r#"
+            UPDATE workflow_runs SET claimed_until = now() + make_interval(secs => $3), updated_at = now()
+            WHERE id = $1 AND claim_token = $2 AND state = 'running'
+            RETURNING cancel_requested_at
+            "#,
+            claim.run_id,
+            claim.token,
+            lease_secs,
+        )
+        .fetch_optional(&*self.pool)
+        .await?
+        .map(|row| RunStatus {
+            cancel_requested: row.cancel_requested_at.is_some(),
+        });
+
+        Ok(status)
+    }
+
+    async fn start_attempt(&self, claim: RunClaim, scheduled: i64) -> Result<Option<ActivityAttempt>, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        if !lock_claimed(&mut tx, claim).await? {
+            return Ok(None);
+        }
+
+        // the heartbeat details stay, they are how the new attempt resumes
+        let attempt = sqlx::query_as!(
+            ActivityAttempt,
+            r#"
+            INSERT INTO activity_attempts (run_id, scheduled)
+            VALUES ($1, $2)
+            ON CONFLICT (run_id, scheduled) DO UPDATE
+            SET attempt = activity_attempts.attempt + 1, started_at = now()
+            RETURNING attempt, heartbeat_details
+            "#,
+            claim.run_id,
+            scheduled,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(attempt))
+    }
+
+    async fn heartbeat(&self, claim: RunClaim, scheduled: i64, details: &str, lease_secs: f64) -> Result<bool, anyhow::Error> {
+        let run_id = claim.run_id;
+        let mut tx = self.pool.begin().await?;
+
+        if !lock_claimed(&mut tx, claim).await? {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE activity_attempts
+            SET last_heartbeat_at = now(), heartbeat_details = $3
+            WHERE run_id = $1 AND scheduled = $2
+            "#,
+            run_id,
+            scheduled,
+            details,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE workflow_runs SET claimed_until = now() + make_interval(secs => $2), updated_at = now()
+            WHERE id = $1
+            "#,
+            run_id,
+            lease_secs,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn finish(&self, claim: RunClaim, state: RunState, result: Option<&JsonValue>, failure: Option<&JsonValue>, event: &NewRunEvent) -> Result<bool, anyhow::Error> {
+        let run_id = claim.run_id;
+        let mut tx = self.pool.begin().await?;
+
+        if !lock_claimed(&mut tx, claim).await? {
+            return Ok(false);
+        }
+
+        insert_event(&mut tx, run_id, event).await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE workflow_runs
+            SET state = $2, result = $3, failure = $4, completed_at = now(), updated_at = now(), claimed_until = NULL, claim_token = NULL
+            WHERE id = $1
+            "#,
+            run_id,
+            state as RunState,
+            result,
+            failure,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
+/// Refuses the business id when any earlier run with it blocks the new start's policy,
+/// locking those runs so they can't finish or start over meanwhile.
+async fn ensure_business_id_free(conn: &mut PgConnection, ns_id: Uuid, business_id: &str, policy: ReusePolicy) -> Result<(), anyhow::Error> {
+    // serializes every start with the id, `allow_always` ones included, until the transaction ends
+    sqlx::query!(
+        r#"
+        SELECT pg_advisory_xact_lock(hashtextextended('workflow_runs:' || $1::uuid::text || ':' || $2::text, 0))
+        "#,
+        ns_id,
+        business_id,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // the new start's policy decides, whatever the earlier runs were started with
+    let earlier = sqlx::query!(
+        r#"
+        SELECT id, state as "state: RunState" FROM workflow_runs
+        WHERE namespace_id = $1 AND business_id = $2
+        ORDER BY id DESC
+        FOR UPDATE
+        "#,
+        ns_id,
+        business_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if let Some(blocking) = earlier.iter().find(|run| policy.blocks(run.state)) {
+        bail!(RunError::BusinessIdInUse(blocking.id));
+    }
+
+    Ok(())
+}
+
+/// Locks a pending or running run and its descendants that still are,
+/// returning the ids of those descendants.
+async fn lock_active_tree(conn: &mut PgConnection, ns_id: Uuid, run_id: Uuid) -> Result<Vec<Uuid>, anyhow::Error> {
+    let state = sqlx::query_scalar!(
+        r#"
+        SELECT state as "state: RunState" FROM workflow_runs WHERE id = $1 AND namespace_id = $2 FOR UPDATE
+        "#,
+        run_id,
+        ns_id,
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(RunError::NotFound)?;
+
+    if state.is_finished() {
+        bail!(RunError::NotActive(state));
+    }
+
+    // finished runs end the walk, what they started was stopped along with them
+    let children = sqlx::query_scalar!(
+        r#"
+        WITH RECURSIVE tree AS (
+            SELECT id FROM workflow_runs WHERE parent_run_id = $1 AND state IN ('pending', 'running')
+            UNION
+            SELECT wr.id FROM workflow_runs wr
+                JOIN tree
+                ON wr.parent_run_id = tree.id
+            WHERE wr.state IN ('pending', 'running')
+        )
+        SELECT wr.id as "id!" FROM workflow_runs wr
+            JOIN tree
+            ON tree.id = wr.id
+        ORDER BY wr.id
+        FOR UPDATE OF wr
+        "#,
+        run_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(children)
+}
+
+async fn find_run(conn: &mut PgConnection, run_id: Uuid) -> Result<Run, anyhow::Error> {
+    let run = sqlx::query_as!(
+        Run,
+        r#"
+        SELECT id, namespace_id as "namespace_id!", workflow_id, workflow_key as "workflow_key!", workflow_type as "workflow_type!",
+               version as "version!", business_id, search_attributes, parent_run_id, state as "state: RunState", cancel_requested_at,
+               input, result, failure, started_by, started_at, updated_at, completed_at
+        FROM workflow_runs
+        WHERE id = $1
+        "#,
+        run_id,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(run)
+}
+
+/// Locks the run for the rest of the transaction, telling whether it is still running
+/// under the claim; an executor whose lease was taken over holds a stale one.
+async fn lock_claimed(conn: &mut PgConnection, claim: RunClaim) -> Result<bool, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT state as "state: RunState", claim_token FROM workflow_runs WHERE id = $1 FOR UPDATE
+        "#,
+        claim.run_id,
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(row.is_some_and(|row| row.state == RunState::Running && row.claim_token == Some(claim.token)))
+}
+
+async fn load_events(conn: &mut PgConnection, run_id: Uuid) -> Result<Vec<RunEvent>, anyhow::Error> {
+    let events = sqlx::query_as!(
+        RunEvent,
+        r#"
+        SELECT seq, kind, payload, created_at
+        FROM workflow_run_events
+        WHERE run_id = $1
+        ORDER BY seq
+        "#,
+        run_id,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(events)
+}
+
+/// Callers hold the run's row lock, so the next seq can't be taken twice.
+async fn insert_event(conn: &mut PgConnection, run_id: Uuid, e: &NewRunEvent) -> Result<i32, anyhow::Error> {
+    let seq = sqlx::query_scalar!(
+        r#"
+        INSERT INTO workflow_run_events (run_id, seq, kind, payload)
+        VALUES ($1, (SELECT COALESCE(max(seq), 0) + 1 FROM workflow_run_events WHERE run_id = $1), $2, $3)
+        RETURNING seq
+        "#,
+        run_id,
+        e.kind,
+        e.payload,
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(seq)
+}
@@ -0,0 +1,139 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::search::{NewSearchAttribute, SearchAttributeDefinition, SearchAttributeError, SearchAttributeKind, MAX_DEFINITIONS};
+use crate::core::ports::storage::SearchAttributeRepository;
+use super::audit;
+
+pub struct PostgresSearchAttributeRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresSearchAttributeRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl SearchAttributeRepository {
+        PostgresSearchAttributeRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchAttributeRepository for PostgresSearchAttributeRepository {
+    async fn find_all(&self, ns_id: Uuid) -> Result<Vec<SearchAttributeDefinition>, anyhow::Error> {
+        let definitions = sqlx::query_as!(
+            SearchAttributeDefinition,
+            r#"
+            SELECT name, kind as "kind: SearchAttributeKind", created_by, created_at
+            FROM namespace_search_attributes
+            WHERE namespace_id = $1
+            ORDER BY name
+            "#,
+            ns_id,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(definitions)
+    }
+
+    async fn define(&self, actor: Uuid, ns_id: Uuid, a: &NewSearchAttribute) -> Result<SearchAttributeDefinition, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // the namespace row serializes definitions, so the limit holds
+        sqlx::query!(
+            r#"
+            SELECT id FROM namespaces WHERE id = $1 FOR UPDATE
+            "#,
+            ns_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let defined = sqlx::query_scalar!(
+            r#"
+            SELECT count(*) as "count!" FROM namespace_search_attributes WHERE namespace_id = $1
+            "#,
+            ns_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if defined >= MAX_DEFINITIONS as i64 {
+            bail!(SearchAttributeError::TooMany);
+        }
+
+        let Some(definition) = sqlx::query_as!(
+            SearchAttributeDefinition,
+            r#"
+            INSERT INTO namespace_search_attributes (namespace_id, name, kind, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (namespace_id, name) DO NOTHING
+            RETURNING name, kind as "kind: SearchAttributeKind", created_by, created_at
+            "#,
+            ns_id,
+            a.name,
+            a.kind as SearchAttributeKind,
+            actor,
+        )
+        .fetch_optional(&mut *tx)
+        .await? else {
+            bail!(SearchAttributeError::AlreadyDefined(a.name.clone()));
+        };
+
+        audit::append(&mut tx, &NewAuditEntry::new("search_attribute.define")
+            .namespace(ns_id)
+            .actor(actor)
+            .meta(json!({ "name": a.name, "kind": a.kind }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(definition)
+    }
+
+    async fn remove(&self, actor: Uuid, ns_id: Uuid, name: &str) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let removed = sqlx::query!(
+            r#"
+            DELETE FROM namespace_search_attributes WHERE namespace_id = $1 AND name = $2
+            "#,
+            ns_id,
+            name,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if removed.rows_affected() == 0 {
+            bail!(SearchAttributeError::NotFound(name.to_string()));
+        }
+
+        audit::append(&mut tx, &NewAuditEntry::new("search_attribute.remove")
+            .namespace(ns_id)
+            .actor(actor)
+            .meta(json!({ "name": name }))
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::domain::session::{NewSession, Session, SessionRecord};
+use crate::core::ports::storage::SessionRepository;
+
+pub struct PostgresSessionRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresSessionRepository {
+    pub fn new(pool: Arc<PgPool>) -> impl SessionRepository {
+        PostgresSessionRepository {
+            pool,
+        }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for PostgresSessionRepository {
+    async fn create(&self, s: &NewSession) -> Result<Session, anyhow::Error> {
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO user_sessions (id, user_id, refresh_hash, user_agent, ip, expires_at, scopes, namespace_ids)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, user_agent, ip, created_at, last_refreshed_at, expires_at, revoked_at, scopes, namespace_ids
+            "#,
+            s.id,
+            s.user_id,
+            s.refresh_hash,
+            s.user_agent,
+            s.ip,
+            s.expires_at,
+            s.scopes.as_deref(),
+            s.namespace_ids.as_deref(),
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<SessionRecord>, anyhow::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, user_id, refresh_hash, user_agent, ip, created_at, last_refreshed_at, expires_at, revoked_at, scopes, namespace_ids
+            FROM user_sessions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(row.map(|r| SessionRecord {
+            session: Session {
+                id: r.id,
+                user_id: r.user_id,
+                user_agent: r.user_agent,
+                ip: r.ip,
+                created_at: r.created_at,
+                last_refreshed_at: r.last_refreshed_at,
+                expires_at: r.expires_at,
+                revoked_at: r.revoked_at,
+                scopes: r.scopes,
+                namespace_ids: r.namespace_ids,
+            },
+            refresh_hash: r.refresh_hash,
+        }))
+    }
+
+    async fn rotate_refresh(&self, id: Uuid, old_hash: &str, new_hash: &str, expires_at: DateTime<Utc>) -> Result<Option<Session>, anyhow::Error> {
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            UPDATE user_sessions
+            SET refresh_hash = $3, expires_at = $4, last_refreshed_at = now()
+            WHERE id = $1 AND refresh_hash = $2 AND revoked_at IS NULL AND expires_at > now()
+            RETURNING id, user_id, user_agent, ip, created_at, last_refreshed_at, expires_at, revoked_at, scopes, namespace_ids
+            "#,
+            id,
+            old_hash,
+            new_hash,
+            expires_at,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<Session>, anyhow::Error> {
+        let sessions = sqlx::query_as!(
+            Session,
+            r#"
+            SELECT id, user_id, user_agent, ip, created_at, last_refreshed_at, expires_at, revoked_at, scopes, namespace_ids
+            FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > now()
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<bool, anyhow::Error> {
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE user_sessions SET revoked_at = now()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+            id,
+            user_id,
+        )
+        .execute(&*self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(revoked > 0)
+    }
+
+    async fn revoke_all(&self, user_id: Uuid) -> Result<u64, anyhow::Error> {
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE user_sessions SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id,
+        )
+        .execute(&*self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(revoked)
+    }
+
+    async fn deny_token(&self, jti: Uuid, expires_at: DateTime<Utc>) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO revoked_tokens (jti, expires_at)
+            VALUES ($1, $2)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+            jti,
+            expires_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // expired tokens are rejected anyway, their entries only take space
+        sqlx::query!(
+            r#"
+            DELETE FROM revoked_tokens WHERE expires_at < now()
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn is_revoked(&self, session_id: Uuid, jti: Uuid) -> Result<bool, anyhow::Error> {
+        let revoked = sqlx::query_scalar!(
+            r#"
+            SELECT
+                NOT EXISTS (SELECT 1 FROM user_sessions WHERE id = $1 AND revoked_at IS NULL)
+                OR EXISTS (SELECT 1 FROM revoked_tokens WHERE jti = $2)
+                as "revoked!"
+            "#,
+            session_id,
+            jti,
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+
+        Ok(revoked)
+    }
+}
@@ -41,7 +41,7 @@ impl UserRepository for PostgresUserRepository {
             r#"
             INSERT INTO users (id, email, token_hash)
             VALUES ($1, $2, $3)
-            RETURNING id, email, token_hash as token, super_admin, created_at
+            RETURNING id, email, token_hash as token, super_admin, created_at, disabled_at
             "#,
             id,
             u.email,
@@ -60,7 +60,7 @@ impl UserRepository for PostgresUserRepository {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, email, token_hash as token, super_admin, created_at
+            SELECT id, email, token_hash as token, super_admin, created_at, disabled_at
             FROM users
             WHERE email = $1
             "#,
@@ -71,4 +71,35 @@ impl UserRepository for PostgresUserRepository {
 
         Ok(user)
     }
-}
\ No newline at end of file
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, anyhow::Error> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, email, token_hash as token, super_admin, created_at, disabled_at
+            FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn update_token_hash(&self, id: Uuid, token_hash: String) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE users SET token_hash = $2
+            WHERE id = $1
+            "#,
+            id,
+            token_hash,
+        )
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -11,10 +11,16 @@
  * SPDX-License-Identifier: AGPL-3.0-or-later
  */
 
+use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
 
-use crate::core::domain::workflow::Workflow as WorkflowDomain;
+use crate::core::domain::schema::WorkflowSchemas;
+use crate::core::domain::workflow::{
+    Workflow as WorkflowDomain,
+    WorkflowVersion as WorkflowVersionDomain,
+};
 
 pub(super) struct Workflow {
     pub id: Uuid,
@@ -39,6 +45,11 @@ pub(super) struct WorkflowVersion {
     pub storage_url: Option<String>, 
     pub created_by: Uuid, 
     pub changelog: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub input_schema: Option<JsonValue>,
+    pub output_schema: Option<JsonValue>,
+    pub activity_schemas: JsonValue,
+    pub manifest: JsonValue,
 }
 
 impl Workflow {
@@ -56,4 +67,30 @@ impl Workflow {
             updated_at: self.updated_at,
         }
     }
+}
+
+impl WorkflowVersion {
+    pub(super) fn to_domain(self) -> WorkflowVersionDomain {
+        // activity_schemas is always written from a map, anything else means the row was edited by hand.
+        let activities = match self.activity_schemas {
+            JsonValue::Object(obj) => obj.into_iter().collect(),
+            _ => HashMap::new(),
+        };
+
+        WorkflowVersionDomain {
+            id: self.id,
+            workflow_id: self.workflow_id,
+            version: self.version,
+            wasm_size_bytes: self.wasm_size_bytes,
+            created_by: self.created_by,
+            changelog: self.changelog,
+            created_at: self.created_at,
+            schemas: WorkflowSchemas {
+                input: self.input_schema,
+                output: self.output_schema,
+                activities,
+            },
+            manifest: serde_json::from_value(self.manifest).unwrap_or_default(),
+        }
+    }
 }
\ No newline at end of file
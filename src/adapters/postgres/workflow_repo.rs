@@ -17,7 +17,7 @@ use std::sync::Arc;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::core::domain::workflow::{NewWorkflow, Workflow};
+use crate::core::domain::workflow::{NewWorkflow, Workflow, WorkflowVersion, VersionBlobRef};
 use super::workflow_dto::{
     Workflow as WorkflowDTO,
     WorkflowVersion as WorkflowVersionDTO,
@@ -44,6 +44,8 @@ const PLACEHOLDER: &str = "0.0.0";
 impl WorkflowRepository for PostgresWorkflowRepository {
     async fn insert(&self, user_id: Uuid, ns_id: Uuid, w: &NewWorkflow) -> Result<Workflow, anyhow::Error> {
         let (workflow_id, workflow_version_id) = (Uuid::now_v7(), Uuid::now_v7());
+        let activity_schemas = serde_json::to_value(&w.schemas.activities)?;
+        let manifest = serde_json::to_value(&w.manifest)?;
 
         let mut tx = self.pool.begin().await?;
 
@@ -67,9 +69,9 @@ impl WorkflowRepository for PostgresWorkflowRepository {
         let wfv = sqlx::query_as!(
             WorkflowVersionDTO,
             r#"
-            INSERT INTO workflow_versions (id, workflow_id, version, wasm_md5, wasm_size_bytes, storage_url, created_by, changelog)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, workflow_id, version, wasm_md5, wasm_size_bytes, storage_url, created_by, changelog
+            INSERT INTO workflow_versions (id, workflow_id, version, wasm_md5, wasm_size_bytes, storage_url, created_by, changelog, input_schema, output_schema, activity_schemas, manifest)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, workflow_id, version, wasm_md5, wasm_size_bytes, storage_url, created_by, changelog, created_at, input_schema, output_schema, activity_schemas, manifest
             "#,
             workflow_version_id,
             workflow_id,
@@ -78,7 +80,11 @@ impl WorkflowRepository for PostgresWorkflowRepository {
             w.wasm_size_bytes as i64,
             w.storage_url,
             user_id,
-            DEFAULT_CHANGELOG
+            DEFAULT_CHANGELOG,
+            w.schemas.input,
+            w.schemas.output,
+            activity_schemas,
+            manifest,
         )
         .fetch_one(&mut *tx)
         .await?;
@@ -114,21 +120,24 @@ impl WorkflowRepository for PostgresWorkflowRepository {
     //     Ok(wf.to_domain(wfv.version))
     // }
 
-    //  async fn find_by_key(&self, key: &str) -> Result<Option<WorkflowDTO>, anyhow::Error> {
-    //     let wf = sqlx::query_as!(
-    //         WorkflowDTO,
-    //         r#"
-    //         SELECT id, namespace_id, key, display_name, description, active_version_id, is_archived, created_by, created_at, updated_at
-    //         FROM workflows
-    //         WHERE key = $1
-    //         "#,
-    //         key
-    //     )
-    //     .fetch_optional(&*self.pool)
-    //     .await?;
+    async fn find_by_key(&self, ns_id: Uuid, key: &str) -> Result<Option<Workflow>, anyhow::Error> {
+        let wf = sqlx::query_as!(
+            Workflow,
+            r#"
+            SELECT w.id, w.namespace_id, w.key, w.display_name, w.description, COALESCE(wv.version, '') as "active_version!", w.is_archived, w.created_by, w.created_at, w.updated_at
+            FROM workflows w
+                LEFT JOIN workflow_versions wv
+                ON wv.id = w.active_version_id
+            WHERE w.namespace_id = $1 AND w.key = $2
+            "#,
+            ns_id,
+            key,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
 
-    //     Ok(wf)
-    // }
+        Ok(wf)
+    }
 
     async fn find_all(&self, ns_id: Uuid) -> Result<Vec<Workflow>, anyhow::Error> {
         let items = sqlx::query_as!(
@@ -148,4 +157,38 @@ impl WorkflowRepository for PostgresWorkflowRepository {
 
         Ok(items)
     }
+
+    async fn find_version(&self, ns_id: Uuid, key: &str, version: &str) -> Result<Option<WorkflowVersion>, anyhow::Error> {
+        let wfv = sqlx::query_as!(
+            WorkflowVersionDTO,
+            r#"
+            SELECT wv.id, wv.workflow_id, wv.version, wv.wasm_md5, wv.wasm_size_bytes, wv.storage_url, wv.created_by, wv.changelog, wv.created_at, wv.input_schema, wv.output_schema, wv.activity_schemas, wv.manifest
+            FROM workflow_versions wv
+                JOIN workflows w
+                ON w.id = wv.workflow_id
+            WHERE w.namespace_id = $1 AND w.key = $2 AND wv.version = $3
+            "#,
+            ns_id,
+            key,
+            version,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(wfv.map(|v| v.to_domain()))
+    }
+
+    async fn blob_refs(&self) -> Result<Vec<VersionBlobRef>, anyhow::Error> {
+        let refs = sqlx::query_as!(
+            VersionBlobRef,
+            r#"
+            SELECT id as version_id, storage_url, created_at
+            FROM workflow_versions
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+
+        Ok(refs)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use anyhow::Context;
+use async_trait::async_trait;
+use aws_sdk_s3::client::Client as s3c;
+use chrono::DateTime;
+use std::sync::Arc;
+
+use crate::core::ports::blob::{BlobInfo, BlobStore};
+
+pub struct S3BlobStore {
+    client: Arc<s3c>,
+    bucket: String,
+}
+
+impl S3BlobStore {
+    pub fn new(client: Arc<s3c>, bucket: String) -> impl BlobStore {
+        S3BlobStore {
+            client,
+            bucket,
+        }
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), anyhow::Error> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(bytes.into())
+            .acl(aws_sdk_s3::types::ObjectCannedAcl::BucketOwnerFullControl)
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload blob '{}'", key))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, anyhow::Error> {
+        let object = self.client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download blob '{}'", key))?;
+
+        let bytes = object.body
+            .collect()
+            .await
+            .with_context(|| format!("Failed to read blob '{}'", key))?;
+
+        Ok(bytes.into_bytes().to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), anyhow::Error> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .with_context(|| format!("Failed to delete blob '{}'", key))?;
+
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<BlobInfo>, anyhow::Error> {
+        let mut blobs = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let page = self.client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .with_context(|| format!("Failed to list blobs under '{}'", prefix))?;
+
+            for object in page.contents() {
+                if let Some(key) = object.key() {
+                    blobs.push(BlobInfo {
+                        key: key.to_string(),
+                        last_modified: object.last_modified()
+                            .and_then(|t| DateTime::from_timestamp(t.secs(), t.subsec_nanos())),
+                    });
+                }
+            }
+
+            match page.next_continuation_token() {
+                Some(token) => continuation_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+
+        Ok(blobs)
+    }
+}
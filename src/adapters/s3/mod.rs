@@ -0,0 +1 @@
+pub mod blob_store;
\ No newline at end of file
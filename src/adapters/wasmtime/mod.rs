@@ -1 +1,2 @@
-pub mod wit_runtime;
\ No newline at end of file
+pub mod wit_runtime;
+pub mod value_codec;
\ No newline at end of file
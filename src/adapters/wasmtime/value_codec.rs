@@ -0,0 +1,194 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+//! Conversion of `serde_json::Value` into the WIT `engine-types.value` variant, and of
+//! guest output back into JSON.
+//!
+//! WIT has no recursive types, so `engine-types.value` only carries scalars directly.
+//! Arrays and objects travel through the `json` case holding their serialized form,
+//! which keeps the conversion lossless.
+//!
+//! Only the way in needs the variant: guests hand their output back as JSON text, so
+//! there is no decoder from `engine-types.value` to JSON.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value as JsonValue;
+
+use crate::core::ports::wit::xarxa::api::engine_types::{Kvpair, Value};
+
+/// Converts workflow/activity input into the list of kvpairs expected by the guest.
+///
+/// Accepts a JSON object (`{"a": 1}`), the explicit kvpair form
+/// (`[{"key": "a", "value": 1}]`) or `null` for no input.
+pub fn json_to_kvpairs(params: &JsonValue) -> Result<Vec<Kvpair>> {
+    match params {
+        JsonValue::Null => Ok(Vec::new()),
+        JsonValue::Object(obj) => obj
+            .iter()
+            .map(|(key, value)| {
+                Ok(Kvpair {
+                    key: key.clone(),
+                    value: json_to_value(value).with_context(|| format!("Invalid value for key '{}'", key))?,
+                })
+            })
+            .collect(),
+        JsonValue::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let obj = item.as_object()
+                    .with_context(|| format!("Input item #{} must be an object with 'key' and 'value'", idx))?;
+
+                let key = match obj.get("key") {
+                    Some(JsonValue::String(k)) => k.clone(),
+                    Some(_) => bail!("Input item #{}: 'key' must be a string", idx),
+                    None => bail!("Input item #{}: missing 'key'", idx),
+                };
+
+                let value = obj.get("value")
+                    .with_context(|| format!("Input item #{}: missing 'value'", idx))?;
+
+                Ok(Kvpair {
+                    value: json_to_value(value).with_context(|| format!("Invalid value for key '{}'", key))?,
+                    key,
+                })
+            })
+            .collect(),
+        other => bail!("Input must be an object, a list of kvpairs or null, got: {}", json_type_name(other)),
+    }
+}
+
+pub fn json_to_value(value: &JsonValue) -> Result<Value> {
+    let v = match value {
+        JsonValue::Null => Value::Null,
+        JsonValue::Bool(b) => Value::Boolean(*b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Int(i)
+            } else if n.is_u64() {
+                bail!("Integer {} does not fit into a signed 64-bit value", n);
+            } else {
+                let f = n.as_f64()
+                    .with_context(|| format!("Number {} cannot be represented as f64", n))?;
+                Value::Float(f)
+            }
+        }
+        JsonValue::String(s) => Value::Str(s.clone()),
+        JsonValue::Array(_) | JsonValue::Object(_) => Value::Json(serde_json::to_string(value)?),
+    };
+
+    Ok(v)
+}
+
+/// Interprets a textual result returned by the guest, which must be JSON text.
+pub fn guest_output_to_json(output: &str) -> Result<JsonValue> {
+    serde_json::from_str(output).context("Guest returned output that is not valid JSON")
+}
+
+fn json_type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn object_becomes_kvpairs() {
+        let pairs = json_to_kvpairs(&json!({ "a": 1, "b": "x" })).unwrap();
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].key, "a");
+        assert!(matches!(pairs[0].value, Value::Int(1)));
+        assert_eq!(pairs[1].key, "b");
+        assert!(matches!(&pairs[1].value, Value::Str(s) if s == "x"));
+    }
+
+    #[test]
+    fn null_is_no_input() {
+        assert!(json_to_kvpairs(&JsonValue::Null).unwrap().is_empty());
+    }
+
+    #[test]
+    fn kvpair_list_is_accepted() {
+        let pairs = json_to_kvpairs(&json!([{ "key": "a", "value": true }])).unwrap();
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].key, "a");
+        assert!(matches!(pairs[0].value, Value::Boolean(true)));
+    }
+
+    #[test]
+    fn malformed_kvpair_list_is_refused() {
+        let not_object = json_to_kvpairs(&json!([1])).unwrap_err();
+        assert!(format!("{:#}", not_object).contains("#0 must be an object"));
+
+        let missing_key = json_to_kvpairs(&json!([{ "value": 1 }])).unwrap_err();
+        assert!(format!("{:#}", missing_key).contains("missing 'key'"));
+
+        let bad_key = json_to_kvpairs(&json!([{ "key": 1, "value": 1 }])).unwrap_err();
+        assert!(format!("{:#}", bad_key).contains("'key' must be a string"));
+
+        let missing_value = json_to_kvpairs(&json!([{ "key": "a" }])).unwrap_err();
+        assert!(format!("{:#}", missing_value).contains("missing 'value'"));
+    }
+
+    #[test]
+    fn scalar_input_is_refused() {
+        let err = json_to_kvpairs(&json!("a")).unwrap_err();
+        assert!(format!("{:#}", err).contains("got: string"));
+    }
+
+    #[test]
+    fn u64_beyond_i64_is_refused() {
+        let err = json_to_value(&json!(u64::MAX)).unwrap_err();
+        assert!(format!("{:#}", err).contains("does not fit"));
+
+        let err = json_to_kvpairs(&json!({ "n": u64::MAX })).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid value for key 'n'"));
+    }
+
+    #[test]
+    fn floats_stay_floats() {
+        assert!(matches!(json_to_value(&json!(1.5)).unwrap(), Value::Float(f) if f == 1.5));
+    }
+
+    #[test]
+    fn nested_values_travel_as_json() {
+        let nested = json!({ "a": [1, { "b": null }] });
+
+        let Value::Json(text) = json_to_value(&nested).unwrap() else {
+            panic!("expected the json case");
+        };
+        assert_eq!(serde_json::from_str::<JsonValue>(&text).unwrap(), nested);
+
+        let Value::Json(text) = json_to_value(&json!([1, 2])).unwrap() else {
+            panic!("expected the json case");
+        };
+        assert_eq!(text, "[1,2]");
+    }
+
+    #[test]
+    fn guest_output_is_parsed() {
+        assert_eq!(guest_output_to_json(r#"{"done":true}"#).unwrap(), json!({ "done": true }));
+        assert!(guest_output_to_json("not json").is_err());
+    }
+}
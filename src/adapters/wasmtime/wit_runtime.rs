@@ -13,29 +13,152 @@
 
 use wasmtime::*;
 use wasmtime::component::{Component, Linker, ResourceAny, Resource};
+use wasmtime::component::types::ComponentItem;
 
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 use wasmtime_wasi::ResourceTable;
 
 use std::collections::HashMap;
-use anyhow::{Result, Context, bail};
+use anyhow::{anyhow, Result, Context, bail};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::RwLock;
-use serde_json::{Value as JsonValue, json};
+use serde_json::{Map, Value as JsonValue};
 use tracing::info;
+use uuid::Uuid;
 
 // use crate::core::ports::wit::Controller;
 // use crate::core::ports::wit::exports::xarxa::engine::workflow_handler::History;
 // use crate::core::ports::wit::xarxa::engine::engine_types::{Kvpair, Value};
 
-use crate::core::ports::wit::Orchestrator;
-use crate::core::ports::wit::xarxa::api::engine_types::{Kvpair, Value};
+use crate::core::ports::wit::OrchestratorPre;
 use crate::core::ports::wit::exports::xarxa::api::workflow_ctrl::History;
+use crate::core::domain::run::{CompletedTask, MAX_HEARTBEAT_DETAILS_BYTES};
+use crate::core::domain::search::{self, SearchAttributeDefinition};
+use crate::core::domain::schema::{CompiledSchemas, SchemaViolations};
+use crate::core::domain::manifest::{ExportedInterface, WorkflowManifest};
+use crate::core::ports::wit::xarxa::api::engine_types::Kvpair;
+use super::value_codec::{json_to_kvpairs, json_to_value, guest_output_to_json};
 
 struct HostState {
     ctx: WasiCtx,
     table: ResourceTable,
     limits: StoreLimits,
+    /// Only set while a workflow decides, activities can't upsert.
+    search: Option<SearchAttributeUpserts>,
+    /// Only set while an activity runs.
+    cancellation: Option<Arc<CancellationToken>>,
+    /// Only set while an activity runs.
+    heartbeat: Option<Arc<Heartbeat>>,
+    /// Only set while a workflow decides, it is trapped once this has passed.
+    deadline: Option<std::time::Instant>,
+}
+
+/// Tells a running activity that its run is going away. Cancellation is up to the
+/// activity to notice through `xarxa:host/activity#cancelled`, termination traps it.
+#[derive(Debug, Default)]
+pub struct CancellationToken {
+    cancelled: AtomicBool,
+    terminated: AtomicBool,
+    timed_out: AtomicBool,
+}
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn terminate(&self) {
+        self.cancel();
+        self.terminated.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.terminated.load(Ordering::Relaxed)
+    }
+
+    /// Terminates an activity that missed its heartbeat timeout, only this attempt is lost.
+    pub fn time_out(&self) {
+        self.timed_out.store(true, Ordering::Relaxed);
+        self.terminate();
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::Relaxed)
+    }
+}
+
+/// Heartbeats of a running activity, for the executor to pick up. It starts out with the
+/// details the previous attempt sent last, so a retry can resume where that one stopped.
+#[derive(Debug, Default)]
+pub struct Heartbeat {
+    beats: AtomicU64,
+    details: std::sync::Mutex<Option<String>>,
+}
+
+impl Heartbeat {
+    pub fn resuming(details: Option<String>) -> Self {
+        Heartbeat {
+            beats: AtomicU64::new(0),
+            details: std::sync::Mutex::new(details),
+        }
+    }
+
+    fn beat(&self, details: String) {
+        *self.details.lock().unwrap_or_else(|e| e.into_inner()) = Some(details);
+        self.beats.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Heartbeats sent so far by this attempt.
+    pub fn beats(&self) -> u64 {
+        self.beats.load(Ordering::Relaxed)
+    }
+
+    pub fn details(&self) -> Option<String> {
+        self.details.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// What a workflow upserted during one decision task, checked against the namespace's definitions.
+struct SearchAttributeUpserts {
+    definitions: Vec<SearchAttributeDefinition>,
+    values: Map<String, JsonValue>,
+}
+
+/// Host interface a workflow upserts its search attributes through:
+/// `upsert: func(attributes: string) -> result<_, string>`, the attributes being a JSON object.
+const SEARCH_ATTRIBUTES_INTERFACE: &str = "xarxa:host/search-attributes";
+
+/// Host interface of running activities: `cancelled: func() -> bool`,
+/// `heartbeat: func(details: string) -> bool` answering whether the activity was cancelled,
+/// and `last-heartbeat: func() -> option<string>` with the details sent last, by this attempt or an earlier one.
+const ACTIVITY_INTERFACE: &str = "xarxa:host/activity";
+
+/// How often running guests check whether they were terminated.
+const EPOCH_TICK: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// One decision task of a run.
+pub struct DecisionTask<'a> {
+    pub workflow_type: &'a str,
+    pub run_seq: u64,
+    pub input: &'a JsonValue,
+    pub history: &'a [CompletedTask],
+    pub search_attributes: &'a [SearchAttributeDefinition],
+    /// Hands the workflow `cancel-workflow` before it is replayed.
+    pub cancel_requested: bool,
+    /// Wall-clock time the workflow gets before it is trapped.
+    pub timeout: std::time::Duration,
+}
+
+/// What a decision task came to.
+pub struct DecisionOutput {
+    pub output: JsonValue,
+    /// Search attributes upserted on the way, normalized; `null` removes one.
+    pub search_attributes: Map<String, JsonValue>,
 }
 
 // impl WasiView for HostState {
@@ -56,16 +179,77 @@ impl WasiView for HostState {
 //     }
 // }
 
+/// Custom section holding the JSON manifest written by the SDK.
+const MANIFEST_SECTION: &str = "xarxa:manifest";
+
+/// A component that compiled, implements the `orchestrator` world and whose imports
+/// are all satisfied by the host linker. Nothing is loaded or stored yet.
+pub struct PreparedComponent {
+    pre: OrchestratorPre<HostState>,
+    manifest: WorkflowManifest,
+}
+
+impl PreparedComponent {
+    pub fn manifest(&self) -> &WorkflowManifest {
+        &self.manifest
+    }
+}
+
+/// The uploaded binary is not a component the engine can run.
+#[derive(Debug)]
+pub struct InvalidComponent(pub String);
+
+impl std::fmt::Display for InvalidComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid workflow component: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidComponent {}
+
+/// Identifies a loaded component.
+///
+/// Workflow keys are only unique within a namespace and several versions of
+/// one workflow can be loaded side by side, so all three make up the key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PluginKey {
+    pub namespace_id: Uuid,
+    pub key: String,
+    pub version: String,
+}
+
+impl PluginKey {
+    pub fn new(namespace_id: Uuid, key: impl Into<String>, version: impl Into<String>) -> Self {
+        PluginKey {
+            namespace_id,
+            key: key.into(),
+            version: version.into(),
+        }
+    }
+
+    /// Components dropped into the plugins directory belong to no namespace.
+    pub fn local(key: impl Into<String>) -> Self {
+        Self::new(Uuid::nil(), key, "local")
+    }
+}
+
+impl std::fmt::Display for PluginKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}@{}", self.namespace_id, self.key, self.version)
+    }
+}
+
 pub struct LoadedWitPlugin {
-    component: Component,
-    // info: PluginInfo,
+    pre: OrchestratorPre<HostState>,
+    schemas: CompiledSchemas,
     loaded_at: std::time::SystemTime,
-    execution_count: u64,
+    execution_count: AtomicU64,
 }
 
 pub struct WitPluginRuntime {
     engine: Engine,
-    pub(crate) plugins: Arc<RwLock<HashMap<String, LoadedWitPlugin>>>,
+    linker: Arc<Linker<HostState>>,
+    pub(crate) plugins: Arc<RwLock<HashMap<PluginKey, Arc<LoadedWitPlugin>>>>,
 }
 
 impl WitPluginRuntime {
@@ -73,24 +257,110 @@ impl WitPluginRuntime {
         let mut config = Config::new();
         config.wasm_component_model(true);
         config.async_support(false); // Synchronous operation for simplicity
+        config.epoch_interruption(true);
         
         let engine = Engine::new(&config)
             .context("Failed to create WASM engine with component model support")?;
 
+        // guests check for termination at every tick, see `create_store`
+        let ticking = engine.weak();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || {
+                while let Some(engine) = ticking.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(EPOCH_TICK);
+                }
+            })
+            .context("Failed to start the epoch thread")?;
+
+        // Everything a component may import has to be registered here,
+        // uploads are checked against this linker before they are accepted.
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::p2::add_to_linker_sync(&mut linker)
+            .context("Failed to add WASI to the linker")?;
+        linker.instance(SEARCH_ATTRIBUTES_INTERFACE)?
+            .func_wrap("upsert", |mut store: StoreContextMut<'_, HostState>, (attributes,): (String,)| {
+                Ok((upsert_search_attributes(store.data_mut(), &attributes),))
+            })
+            .context("Failed to add search attributes to the linker")?;
+        let mut activity = linker.instance(ACTIVITY_INTERFACE)?;
+        activity
+            .func_wrap("cancelled", |store: StoreContextMut<'_, HostState>, (): ()| {
+                Ok((store.data().cancellation.as_ref().is_some_and(|token| token.is_cancelled()),))
+            })
+            .context("Failed to add the activity interface to the linker")?;
+        activity
+            .func_wrap("heartbeat", |store: StoreContextMut<'_, HostState>, (details,): (String,)| {
+                if details.len() > MAX_HEARTBEAT_DETAILS_BYTES {
+                    bail!("Heartbeat details too large (max {} bytes): {} bytes", MAX_HEARTBEAT_DETAILS_BYTES, details.len());
+                }
+                let state = store.data();
+                if let Some(heartbeat) = &state.heartbeat {
+                    heartbeat.beat(details);
+                }
+                Ok((state.cancellation.as_ref().is_some_and(|token| token.is_cancelled()),))
+            })
+            .context("Failed to add the activity interface to the linker")?;
+        activity
+            .func_wrap("last-heartbeat", |store: StoreContextMut<'_, HostState>, (): ()| {
+                Ok((store.data().heartbeat.as_ref().and_then(|heartbeat| heartbeat.details()),))
+            })
+            .context("Failed to add the activity interface to the linker")?;
+
         let plugins = Arc::new(RwLock::new(HashMap::new()));
         
         info!("🚀 Initialized WIT Plugin Manager");
 
         Ok(WitPluginRuntime {
             engine,
+            linker: Arc::new(linker),
             plugins,
         })
     }
 
-    pub async fn load_wit_plugin(&self, name: &str, wasm_bytes: &[u8]) -> Result<()> {
+    /// Fully validates a workflow component without loading it:
+    /// compiles it, checks its imports against the host linker and
+    /// type-checks its exports against the `orchestrator` world.
+    /// Compiling takes a while for large components, so it runs off the async workers.
+    pub async fn prepare(&self, wasm_bytes: &[u8]) -> Result<PreparedComponent> {
+        if !wasmparser::Parser::is_component(wasm_bytes) {
+            let reason = if wasmparser::Parser::is_core_wasm(wasm_bytes) {
+                "got a core WASM module, expected a component".to_string()
+            } else {
+                "not a WASM binary".to_string()
+            };
+            return Err(InvalidComponent(reason).into());
+        }
+
+        let engine = self.engine.clone();
+        let linker = self.linker.clone();
+        let wasm_bytes = wasm_bytes.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let component = Component::new(&engine, &wasm_bytes)
+                .map_err(|e| InvalidComponent(format!("compilation failed: {:#}", e)))?;
+
+            let instance_pre = linker.instantiate_pre(&component)
+                .map_err(|e| InvalidComponent(format!("unsatisfied imports: {:#}", e)))?;
+
+            let pre = OrchestratorPre::new(instance_pre)
+                .map_err(|e| InvalidComponent(format!("does not implement the orchestrator world: {:#}", e)))?;
+
+            let manifest = extract_wit_plugin_info(&engine, &component, &wasm_bytes)
+                .map_err(|e| InvalidComponent(format!("{:#}", e)))?;
+
+            Ok(PreparedComponent { pre, manifest })
+        })
+        .await
+        .context("Component preparation panicked")?
+    }
+
+    pub async fn load_wit_plugin(&self, name: &PluginKey, wasm_bytes: &[u8], schemas: CompiledSchemas) -> Result<()> {
         info!("📦 Loading WIT plugin: {}", name);
 
-        if name.is_empty() {
+        if name.key.is_empty() {
             bail!("Plugin name cannot be empty");
         }
         
@@ -98,19 +368,23 @@ impl WitPluginRuntime {
             bail!("WASM bytes cannot be empty for plugin '{}'", name);
         }
         
-        // Create WIT component
-        let component = Component::new(&self.engine, wasm_bytes)
-            .with_context(|| format!("Failed to create WIT component for plugin '{}'", name))?;
-        
-        // Get plugin information
-        // let info = self.extract_wit_plugin_info(&component, name).await?;
-        // TODO: It should me information about pipelines, activities, etc.
-        
+        let prepared = self.prepare(wasm_bytes).await
+            .with_context(|| format!("Failed to prepare WIT component for plugin '{}'", name))?;
+
+        self.load_prepared(name, prepared, schemas).await
+    }
+
+    /// Loads a component previously checked by [`WitPluginRuntime::prepare`].
+    pub async fn load_prepared(&self, name: &PluginKey, prepared: PreparedComponent, schemas: CompiledSchemas) -> Result<()> {
+        if name.key.is_empty() {
+            bail!("Plugin name cannot be empty");
+        }
+
         let loaded_plugin = LoadedWitPlugin {
-            component,
-            // info: info.clone(),
+            pre: prepared.pre,
+            schemas,
             loaded_at: std::time::SystemTime::now(),
-            execution_count: 0,
+            execution_count: AtomicU64::new(0),
         };
 
         {
@@ -122,223 +396,129 @@ impl WitPluginRuntime {
         }
         
         let mut plugins = self.plugins.write().await;
-        plugins.insert(name.to_string(), loaded_plugin);
+        plugins.insert(name.clone(), Arc::new(loaded_plugin));
         
         Ok(())
     }
 
-    pub async fn execute_wit_function( &self, plugin_name: &str, function_name: &str, params: JsonValue) -> Result<JsonValue> {
-        let mut plugins = self.plugins.write().await;
-        
-        let plugin = plugins.get_mut(plugin_name)
-            .with_context(|| format!("WIT Plugin '{}' not found", plugin_name))?;
-        
-        // Increment execution counter
-        plugin.execution_count += 1;
-        let execution_count = plugin.execution_count;
-        
-        info!("🚀 Executing WIT function: {}.{} (execution #{})", 
-              plugin_name, function_name, execution_count);
-        
-        // Execute the function
-        let result = self.execute_wit_component_function(&plugin.component, function_name, &params).await?;
-        
-        info!("✅ WIT function completed: {}.{}", plugin_name, function_name);
-        Ok(result)
+    pub async fn is_loaded(&self, name: &PluginKey) -> bool {
+        self.plugins.read().await.contains_key(name)
     }
 
-    async fn execute_wit_component_function(
-        &self,
-        component: &Component,
-        function_name: &str,
-        params: &JsonValue,
-    ) -> Result<JsonValue> {
-        let mut store = self.create_store()?;
-        
-        // Create linker and add WASI
-        let mut linker = Linker::new(&self.engine);
-        wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
-        
-        // Create component instance
-        let instance = Orchestrator::instantiate(&mut store, component, &linker)?;
-        let workflow_handler = instance.xarxa_api_workflow_ctrl().workflow_engine();
-        let engine = instance.xarxa_api_workflow_ctrl().call_create_workflow_engine(&mut store)?;
-
-        let result = match function_name {
-            "start-workflow" => {
-                let workflow_name = params.get("workflow_name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("default");
-                
-                let input = self.json_to_kvpairs(params.get("input").unwrap_or(&json!([])))?;
-                
-                match workflow_handler.call_start_workflow(&mut store, engine, workflow_name, &input) {
-                    Ok(Ok(workflow_run)) => {
-                        json!({
-                            "success": true,
-                            "result": format!("Workflow started successfully"),
-                            "workflow_run": format!("{:?}", workflow_run),
-                            "run_id": format!("run_{}", chrono::Utc::now().timestamp())
-                        })
-                    }
-                    Ok(Err(error)) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Workflow error: {:?}", error)
-                        })
-                    }
-                    Err(e) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Runtime error: {}", e)
-                        })
-                    }
-                }
-            }
-            "continue-workflow" => {
-                let run_id = params.get("run_id")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0); // todo handle err
-                
-                let history = History{ tasks_result:  vec![]};
-                
-                match workflow_handler.call_continue_workflow(&mut store, engine, run_id, &history) {
-                    Ok(Ok(result)) => {
-                        json!({
-                            "success": true,
-                            "result": result,
-                            "run_id": run_id
-                        })
-                    }
-                    Ok(Err(error)) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Workflow error: {:?}", error)
-                        })
-                    }
-                    Err(e) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Runtime error: {}", e)
-                        })
-                    }
-                }
-            }
-            "execute-activity" => {
-                let activity_name = params.get("activity_name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("default");
-                
-                let input = self.json_to_kvpairs(params.get("input").unwrap_or(&json!([])))?;
-                
-                match workflow_handler.call_execute_activity(&mut store, engine, activity_name, &input) {
-                    Ok(Ok(result)) => {
-                        json!({
-                            "success": true,
-                            "result": result,
-                            "activity": activity_name
-                        })
-                    }
-                    Ok(Err(error)) => {
-                        json!({
-                            "success": false,
-                            "error": error,
-                            "activity": activity_name
-                        })
-                    }
-                    Err(e) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Runtime error: {}", e)
-                        })
-                    }
-                }
+    /// Runs one decision task of a run and returns the guest's output.
+    ///
+    /// Instances keep nothing between calls, so the workflow is started afresh and
+    /// replayed with every activity outcome so far; the output says what it wants next.
+    /// Replaying upserts search attributes again, they are the same as before up to where it left off.
+    pub async fn decide(&self, name: &PluginKey, task: &DecisionTask<'_>) -> Result<DecisionOutput> {
+        let plugin = self.plugin(name).await?;
+
+        let workflow_type = task.workflow_type.to_string();
+        let run_seq = task.run_seq;
+        let cancel_requested = task.cancel_requested;
+        let timeout = task.timeout;
+        let input = json_to_kvpairs(task.input)
+            .context("Failed to convert workflow input")?;
+        let history = History {
+            tasks_result: task.history.iter()
+                .map(task_to_kvpair)
+                .collect::<Result<Vec<_>>>()?,
+        };
+        let search = SearchAttributeUpserts {
+            definitions: task.search_attributes.to_vec(),
+            values: Map::new(),
+        };
+        let engine = self.engine.clone();
+
+        // guests run synchronously, off the async workers and without holding the plugins lock
+        tokio::task::spawn_blocking(move || {
+            let mut store = create_store(&engine)?;
+            store.data_mut().search = Some(search);
+            store.data_mut().deadline = Some(std::time::Instant::now() + timeout);
+            let instance = plugin.pre.instantiate(&mut store)?;
+            let workflow_handler = instance.xarxa_api_workflow_ctrl().workflow_engine();
+            let engine = instance.xarxa_api_workflow_ctrl().call_create_workflow_engine(&mut store)?;
+
+            workflow_handler.call_start_workflow(&mut store, engine, &workflow_type, &input)?
+                .map_err(|e| anyhow!("Workflow '{}' failed to start: {:?}", workflow_type, e))?;
+
+            if cancel_requested {
+                workflow_handler.call_cancel_workflow(&mut store, engine, run_seq)?
+                    .map_err(|e| anyhow!("Workflow '{}' failed to take the cancellation: {:?}", workflow_type, e))?;
             }
-            // "signal-workflow" => {
-            //     let run_id = params.get("run_id")
-            //         .and_then(|v| v.as_u64())
-            //         .unwrap_or(0);
-                
-            //     // Create signal from parameters
-            //     let signal_data = params.get("signal").unwrap_or(&json!({}));
-                
-            //     #[warn(unreachable_code)]
-            //     let signal = engine::api::engine_types::Signal {
-            //         // name: signal_data.get("name")
-            //         //     .and_then(|v| v.as_str())
-            //         //     .unwrap_or("default")
-            //         //     .to_string(),
-            //         // payload: signal_data.get("payload")
-            //         //     .and_then(|v| v.as_str())
-            //         //     .unwrap_or("{}")
-            //         //     .to_string(),
-                    
-            //         payload: todo!(),
-            //     };
-                
-            //     match worker_handler.call_signal_workflow(&mut store, run_id, &signal) {
-            //         Ok(Ok(result)) => {
-            //             json!({
-            //                 "success": true,
-            //                 "result": result,
-            //                 "run_id": run_id,
-            //                 "signal": signal.payload,
-            //             })
-            //         }
-            //         Ok(Err(error)) => {
-            //             json!({
-            //                 "success": false,
-            //                 "error": format!("Workflow error: {:?}", error)
-            //             })
-            //         }
-            //         Err(e) => {
-            //             json!({
-            //                 "success": false,
-            //                 "error": format!("Runtime error: {}", e)
-            //             })
-            //         }
-            //     }
-            // }
-            "cancel-workflow" => {
-                let run_id = params.get("run_id")
-                    .and_then(|v| v.as_u64())
-                    .unwrap_or(0); // todo handle with err
-                
-                match workflow_handler.call_cancel_workflow(&mut store, engine, run_id) {
-                    Ok(Ok(result)) => {
-                        json!({
-                            "success": true,
-                            "result": result,
-                            "run_id": run_id,
-                            "action": "cancelled"
-                        })
-                    }
-                    Ok(Err(error)) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Workflow error: {:?}", error)
-                        })
-                    }
-                    Err(e) => {
-                        json!({
-                            "success": false,
-                            "error": format!("Runtime error: {}", e)
-                        })
-                    }
+
+            let output = workflow_handler.call_continue_workflow(&mut store, engine, run_seq, &history)?
+                .map_err(|e| anyhow!("Workflow '{}' failed: {:?}", workflow_type, e))?;
+
+            Ok(DecisionOutput {
+                output: guest_output_to_json(&output)
+                    .with_context(|| format!("Workflow '{}' returned an invalid decision", workflow_type))?,
+                search_attributes: store.data_mut().search.take()
+                    .map(|upserts| upserts.values)
+                    .unwrap_or_default(),
+            })
+        })
+        .await
+        .context("Decision task panicked")?
+    }
+
+    /// Runs an activity. `Ok(Err(_))` is a failure the activity reported itself,
+    /// or a result that breaks the declared schema; a terminated activity traps.
+    pub async fn run_activity(&self, name: &PluginKey, activity: &str, input: &JsonValue, cancellation: Arc<CancellationToken>, heartbeat: Arc<Heartbeat>) -> Result<Result<JsonValue, String>> {
+        let plugin = self.plugin(name).await?;
+
+        let activity = activity.to_string();
+        let input = json_to_kvpairs(input)
+            .context("Failed to convert activity input")?;
+        let engine = self.engine.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut store = create_store(&engine)?;
+            store.data_mut().cancellation = Some(cancellation);
+            store.data_mut().heartbeat = Some(heartbeat);
+            let instance = plugin.pre.instantiate(&mut store)?;
+            let workflow_handler = instance.xarxa_api_workflow_ctrl().workflow_engine();
+            let engine = instance.xarxa_api_workflow_ctrl().call_create_workflow_engine(&mut store)?;
+
+            let outcome = match workflow_handler.call_execute_activity(&mut store, engine, &activity, &input)? {
+                Ok(output) => {
+                    guest_output_to_json(&output)
+                        .map_err(|e| format!("{:#}", e))
+                        .and_then(|result| plugin.schemas.validate_activity_result(&activity, &result)
+                            .map(|()| result)
+                            .map_err(|violations| violations.to_string()))
                 }
-            }
-            _ => {
-                json!({
-                    "success": false,
-                    "error": format!("Unknown WIT function: {}", function_name)
-                })
-            }
-        };
-        
-        Ok(result)
+                Err(error) => Err(error),
+            };
+
+            Ok(outcome)
+        })
+        .await
+        .context("Activity panicked")?
     }
 
-    pub async fn remove_plugin(&self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// The loaded plugin, counted as executed once more.
+    async fn plugin(&self, name: &PluginKey) -> Result<Arc<LoadedWitPlugin>> {
+        let plugin = self.plugins.read().await
+            .get(name)
+            .cloned()
+            .with_context(|| format!("WIT Plugin '{}' not found", name))?;
+        plugin.execution_count.fetch_add(1, Ordering::Relaxed);
+
+        Ok(plugin)
+    }
+
+    /// Checks a workflow result against the output schema of the version.
+    pub async fn validate_output(&self, name: &PluginKey, output: &JsonValue) -> Result<Result<(), SchemaViolations>> {
+        let plugins = self.plugins.read().await;
+
+        let plugin = plugins.get(name)
+            .with_context(|| format!("WIT Plugin '{}' not found", name))?;
+
+        Ok(plugin.schemas.validate_output(output))
+    }
+
+    pub async fn remove_plugin(&self, name: &PluginKey) -> Result<(), Box<dyn std::error::Error>> {
         let mut plugins = self.plugins.write().await;
         
         if plugins.remove(name).is_some() {
@@ -349,80 +529,146 @@ impl WitPluginRuntime {
         }
     }
 
-    pub async fn reload_plugin(&self, name: &str, wasm_bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    /// Unloads every loaded version of a workflow, returns how many were removed.
+    pub async fn remove_workflow(&self, namespace_id: Uuid, key: &str) -> usize {
+        let mut plugins = self.plugins.write().await;
+        let before = plugins.len();
+
+        plugins.retain(|k, _| !(k.namespace_id == namespace_id && k.key == key));
+
+        let removed = before - plugins.len();
+        info!("Removed {} version(s) of plugin: {}/{}", removed, namespace_id, key);
+        removed
+    }
+
+    /// Unloads everything that belongs to a namespace, returns how many were removed.
+    pub async fn remove_namespace(&self, namespace_id: Uuid) -> usize {
+        let mut plugins = self.plugins.write().await;
+        let before = plugins.len();
+
+        plugins.retain(|k, _| k.namespace_id != namespace_id);
+
+        let removed = before - plugins.len();
+        info!("Removed {} plugin(s) of namespace: {}", removed, namespace_id);
+        removed
+    }
+
+    pub async fn reload_plugin(&self, name: &PluginKey, wasm_bytes: &[u8], schemas: CompiledSchemas) -> Result<(), Box<dyn std::error::Error>> {
         // Remove the old plugin
         self.remove_plugin(name).await.ok(); // Ignore error if plugin didn't exist
         
         // Load the new one
-        self.load_wit_plugin(name, wasm_bytes).await?;
+        self.load_wit_plugin(name, wasm_bytes, schemas).await?;
         
         info!("Reloaded plugin: {}", name);
         Ok(())
     }
 
-    pub async fn list_plugin_names(&self) -> Vec<String> {
+    pub async fn list_plugin_names(&self) -> Vec<PluginKey> {
         let plugins = self.plugins.read().await;
         plugins.keys().cloned().collect()
     }
+}
 
-    fn create_store(&self) -> Result<Store<HostState>> {
-        let wasi = WasiCtxBuilder::new()
-            .inherit_stdio()
-            .build();
-            
-        let host_state = HostState {
-            ctx: wasi,
-            table: ResourceTable::new(),
-            limits: StoreLimitsBuilder::new()
-                .memory_size(1 << 28) // 256 MB
-                .instances(10)
-                .build(),
-        };
-        
-        let mut store = Store::new(&self.engine, host_state);
-        store.limiter(|state| &mut state.limits);
-        
-        Ok(store)
-    }
+fn extract_wit_plugin_info(engine: &Engine, component: &Component, wasm_bytes: &[u8]) -> Result<WorkflowManifest> {
+    let mut manifest = read_manifest_section(wasm_bytes)?.unwrap_or_default();
+
+    manifest.exports = component.component_type()
+        .exports(engine)
+        .filter_map(|(name, item)| match item {
+            ComponentItem::ComponentInstance(instance) => Some(ExportedInterface {
+                name: name.to_string(),
+                functions: instance.exports(engine)
+                    .filter(|(_, item)| matches!(item, ComponentItem::ComponentFunc(_)))
+                    .map(|(name, _)| name.to_string())
+                    .collect(),
+            }),
+            ComponentItem::ComponentFunc(_) => Some(ExportedInterface {
+                name: name.to_string(),
+                functions: vec![name.to_string()],
+            }),
+            _ => None,
+        })
+        .collect();
 
-    fn json_to_kvpairs(&self, params: &JsonValue) -> Result<Vec<Kvpair>> {
-        let mut kvpairs = Vec::new();
+    Ok(manifest)
+}
+
+fn create_store(engine: &Engine) -> Result<Store<HostState>> {
+    let wasi = WasiCtxBuilder::new()
+        .inherit_stdio()
+        .build();
         
-        match params {
-            JsonValue::Array(arr) => {
-                // If already an array of kvpairs
-                for item in arr {
-                    if let Some(obj) = item.as_object() {
-                        if let (Some(key), Some(value)) = (obj.get("key"), obj.get("value")) {
-                            kvpairs.push(Kvpair {
-                                key: key.as_str().unwrap_or("").to_string(),
-                                value: Value::Str(value.as_str().unwrap_or("").to_string()),
-                            });
-                        }
-                    }
-                }
-            }
-            JsonValue::Object(obj) => {
-                // If regular object - convert to kvpairs
-                for (key, value) in obj {
-                    let value_str = match value {
-                        JsonValue::String(s) => s.clone(),
-                        JsonValue::Number(n) => n.to_string(),
-                        JsonValue::Bool(b) => b.to_string(),
-                        _ => serde_json::to_string(value)?,
-                    };
-                    
-                    kvpairs.push(Kvpair {
-                        key: key.clone(),
-                        value: Value::Str(value_str),
-                    });
-                }
+    let host_state = HostState {
+        ctx: wasi,
+        table: ResourceTable::new(),
+        limits: StoreLimitsBuilder::new()
+            .memory_size(1 << 28) // 256 MB
+            .instances(10)
+            .build(),
+        search: None,
+        cancellation: None,
+        heartbeat: None,
+        deadline: None,
+    };
+    
+    let mut store = Store::new(engine, host_state);
+    store.limiter(|state| &mut state.limits);
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(|store| match &store.data().cancellation {
+        Some(token) if token.is_timed_out() => bail!("Activity missed its heartbeat timeout"),
+        Some(token) if token.is_terminated() => bail!("Activity was terminated"),
+        _ if store.data().deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) => {
+            bail!("Decision task ran past its deadline")
+        }
+        _ => Ok(UpdateDeadline::Continue(1)),
+    });
+    
+    Ok(store)
+}
+
+/// An activity outcome as the guest receives it, keyed by the seq it was scheduled at.
+fn task_to_kvpair(task: &CompletedTask) -> Result<Kvpair> {
+    Ok(Kvpair {
+        key: task.id.to_string(),
+        value: json_to_value(&serde_json::to_value(task)?)?,
+    })
+}
+
+/// Host side of `xarxa:host/search-attributes#upsert`; errors go back to the guest.
+fn upsert_search_attributes(state: &mut HostState, attributes: &str) -> Result<(), String> {
+    let Some(upserts) = state.search.as_mut() else {
+        return Err("Search attributes can only be upserted by workflows, not activities".to_string());
+    };
+
+    let attributes: JsonValue = serde_json::from_str(attributes)
+        .map_err(|e| format!("Search attributes are not valid JSON: {}", e))?;
+    let attributes = search::normalize(&upserts.definitions, &attributes)
+        .map_err(|e| e.to_string())?;
+
+    upserts.values.extend(attributes);
+    Ok(())
+}
+
+fn read_manifest_section(wasm_bytes: &[u8]) -> Result<Option<WorkflowManifest>> {
+    let mut manifest = None;
+
+    for payload in wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+        if let wasmparser::Payload::CustomSection(section) = payload.context("Failed to parse WASM binary")? {
+            if section.name() != MANIFEST_SECTION {
+                continue;
             }
-            _ => {
-                // For other types create empty array
+
+            if manifest.is_some() {
+                bail!("Component contains more than one '{}' section", MANIFEST_SECTION);
             }
+
+            manifest = Some(
+                serde_json::from_slice::<WorkflowManifest>(section.data())
+                    .with_context(|| format!("Invalid '{}' section", MANIFEST_SECTION))?
+            );
         }
-        
-        Ok(kvpairs)
     }
+
+    Ok(manifest)
 }
\ No newline at end of file
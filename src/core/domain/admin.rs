@@ -0,0 +1,136 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A user as the administration API lists it.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub email: String,
+    pub super_admin: bool,
+    pub service_account: bool,
+    pub created_at: DateTime<Utc>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    /// Namespaces the user is a member of.
+    pub namespaces: i64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UserFilter {
+    /// Case-insensitive substring of the email.
+    pub q: Option<String>,
+    pub super_admin: Option<bool>,
+    pub disabled: Option<bool>,
+    pub service_account: Option<bool>,
+    /// Users created after this one, the `next_after` of the previous page.
+    pub after: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+impl UserFilter {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 500;
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT).clamp(1, Self::MAX_LIMIT)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserPage {
+    pub users: Vec<UserSummary>,
+    pub next_after: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserStatusUpdate {
+    pub disabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuperAdminUpdate {
+    pub super_admin: bool,
+}
+
+/// A namespace with what it holds and how much it is used.
+#[derive(Debug, Clone, Serialize)]
+pub struct NamespaceStats {
+    pub id: Uuid,
+    pub slug: String,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub members: i64,
+    pub workflows: i64,
+    pub versions: i64,
+    /// Size of every stored wasm version.
+    pub storage_bytes: i64,
+    pub runs: i64,
+    pub running_runs: i64,
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NamespaceStatsFilter {
+    /// Case-insensitive substring of the slug.
+    pub q: Option<String>,
+    pub archived: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ForceCancelRequest {
+    pub reason: Option<String>,
+}
+
+/// Plugins to unload on every node; leaving out `key` unloads the whole namespace,
+/// leaving out `version` every version of the workflow.
+#[derive(Debug, Deserialize)]
+pub struct UnloadRequest {
+    pub namespace: String,
+    pub key: Option<String>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum AdminError {
+    UserNotFound,
+    RunNotFound,
+    LastSuperAdmin,
+    NotAllowed(String),
+}
+
+impl std::fmt::Display for AdminError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdminError::UserNotFound => write!(f, "User not found"),
+            AdminError::RunNotFound => write!(f, "Run not found"),
+            AdminError::LastSuperAdmin => write!(f, "There must be at least one super admin left"),
+            AdminError::NotAllowed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+/// What the initial super admin bootstrap did.
+#[derive(Debug)]
+pub enum BootstrapOutcome {
+    /// There already is an active super admin, nothing was changed.
+    AlreadyPresent,
+    Promoted(UserSummary),
+    /// The account was created; its token is shown this once.
+    Created { user: UserSummary, token: String },
+}
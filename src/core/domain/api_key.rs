@@ -0,0 +1,115 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+
+use super::namespace::NamespaceRole;
+use super::scope::{Scope, TokenScopes};
+
+/// Every API key starts with this, which is how the auth extractor tells it from a JWT.
+pub const API_KEY_PREFIX: &str = "xk_";
+
+/// An API key as it is listed, never with its secret.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub namespace_id: Uuid,
+    /// The service account the key acts as.
+    pub user_id: Uuid,
+    pub name: String,
+    pub role: NamespaceRole,
+    pub prefix: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Scope names the key is limited to, all its role allows when absent.
+    pub scopes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct NewApiKey {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    pub role: NamespaceRole,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scopes: Option<Vec<Scope>>,
+}
+
+/// What the repository stores for a new key.
+#[derive(Debug)]
+pub struct NewApiKeyRecord {
+    pub name: String,
+    pub role: NamespaceRole,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub prefix: String,
+    pub secret_hash: String,
+    pub scopes: Option<Vec<String>>,
+}
+
+/// Returned once on creation, the token can not be shown again.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub key: ApiKey,
+    pub token: String,
+}
+
+/// What is needed to authenticate a request made with a key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyCredentials {
+    pub id: Uuid,
+    pub namespace_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub secret_hash: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub scopes: Option<Vec<String>>,
+}
+
+impl ApiKeyCredentials {
+    pub fn token_scopes(&self) -> TokenScopes {
+        TokenScopes::from_columns(self.scopes.clone(), Some(vec![self.namespace_id]))
+    }
+
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|exp| exp > now)
+    }
+}
+
+#[derive(Debug)]
+pub enum ApiKeyError {
+    NotFound,
+    AlreadyRevoked,
+    /// Roles a key can not be given by this caller.
+    NotAllowed(String),
+}
+
+impl Display for ApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "API key not found"),
+            Self::AlreadyRevoked => write!(f, "API key is already revoked"),
+            Self::NotAllowed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ApiKeyError {}
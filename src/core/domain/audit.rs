@@ -0,0 +1,229 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use serde_json::{json, Value as JsonValue};
+
+/// Chain of the entries that belong to no namespace, e.g. signups.
+pub const GLOBAL_CHAIN: Uuid = Uuid::nil();
+
+/// `prev_hash` of the first entry of every chain.
+pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// An entry to append to `audit_logs`.
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    pub namespace_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub object_type: Option<String>,
+    pub object_id: Option<Uuid>,
+    pub meta: JsonValue,
+}
+
+impl NewAuditEntry {
+    pub fn new(action: impl Into<String>) -> Self {
+        NewAuditEntry {
+            namespace_id: None,
+            user_id: None,
+            action: action.into(),
+            object_type: None,
+            object_id: None,
+            meta: JsonValue::Object(Default::default()),
+        }
+    }
+
+    pub fn namespace(mut self, namespace_id: Uuid) -> Self {
+        self.namespace_id = Some(namespace_id);
+        self
+    }
+
+    pub fn actor(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn object(mut self, object_type: impl Into<String>, object_id: Uuid) -> Self {
+        self.object_type = Some(object_type.into());
+        self.object_id = Some(object_id);
+        self
+    }
+
+    pub fn meta(mut self, meta: JsonValue) -> Self {
+        self.meta = meta;
+        self
+    }
+}
+
+/// An entry read back from `audit_logs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub namespace_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub object_type: Option<String>,
+    pub object_id: Option<Uuid>,
+    pub meta: JsonValue,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters of the audit endpoints, every filter is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditFilter {
+    pub namespace_id: Option<Uuid>,
+    /// Acting user.
+    pub actor: Option<Uuid>,
+    /// Exact action, or its prefix before a dot: `member` matches `member.add`.
+    pub action: Option<String>,
+    pub object_type: Option<String>,
+    pub object_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Cursor, only entries older than this id are returned.
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl AuditFilter {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 500;
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT).clamp(1, Self::MAX_LIMIT)
+    }
+}
+
+/// A page of entries; `next_before` is the cursor for the next page, if there is one.
+#[derive(Debug, Serialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub next_before: Option<i64>,
+}
+
+/// An entry as it is linked into its chain.
+#[derive(Debug, Clone)]
+pub struct ChainedEntry {
+    pub id: i64,
+    pub chain_id: Uuid,
+    pub seq: i64,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub object_type: Option<String>,
+    pub object_id: Option<Uuid>,
+    pub meta: JsonValue,
+    pub created_at: DateTime<Utc>,
+    pub prev_hash: Vec<u8>,
+    pub hash: Vec<u8>,
+}
+
+impl ChainedEntry {
+    /// `sha256(prev_hash || content)`, with the content serialized as a JSON array.
+    ///
+    /// Only columns nothing but an append ever writes are covered; `namespace_id` and
+    /// `user_id` are nulled by their foreign keys and are stood in for by `chain_id` and `actor_id`.
+    /// `created_at` is taken at microsecond precision, which is what Postgres keeps.
+    pub fn compute_hash(&self, prev_hash: &[u8]) -> Vec<u8> {
+        let content = json!([
+            self.chain_id,
+            self.seq,
+            self.actor_id,
+            self.action,
+            self.object_type,
+            self.object_id,
+            canonical_json(&self.meta),
+            self.created_at.timestamp_micros(),
+        ]);
+
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash);
+        hasher.update(content.to_string().as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// Objects with their keys sorted, JSONB hands them back in its own order.
+fn canonical_json(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            JsonValue::Object(keys.into_iter().map(|k| (k.clone(), canonical_json(&map[k]))).collect())
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(canonical_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// The newest entry of a chain.
+#[derive(Debug, Clone)]
+pub struct ChainHead {
+    pub chain_id: Uuid,
+    pub seq: i64,
+    pub hash: Vec<u8>,
+}
+
+/// A signed chain head.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditCheckpoint {
+    pub id: i64,
+    pub chain_id: Uuid,
+    pub seq: i64,
+    #[serde(with = "hex::serde")]
+    pub hash: Vec<u8>,
+    pub key_id: String,
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditCheckpoint {
+    /// The bytes the signature is made over.
+    pub fn signed_message(chain_id: Uuid, seq: i64, hash: &[u8]) -> Vec<u8> {
+        format!("xarxa-audit-checkpoint:{}:{}:{}", chain_id, seq, hex::encode(hash)).into_bytes()
+    }
+}
+
+/// Checkpoints with what is needed to check them away from this server.
+#[derive(Debug, Serialize)]
+pub struct CheckpointExport {
+    pub key_id: Option<String>,
+    /// Hex encoded ed25519 public key.
+    pub public_key: Option<String>,
+    /// Hex encoded public keys older checkpoints were signed with.
+    pub retired_public_keys: Vec<String>,
+    pub checkpoints: Vec<AuditCheckpoint>,
+}
+
+/// Where a chain stops being trustworthy.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainBreak {
+    pub seq: i64,
+    pub entry_id: Option<i64>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainVerification {
+    pub chain_id: Uuid,
+    /// Entries checked before the first break, all of them when there is none.
+    pub checked: u64,
+    pub head_seq: i64,
+    pub checkpoint_seq: Option<i64>,
+    /// Whether the latest checkpoint is signed with a known key; the chain is only checked against it then.
+    pub checkpoint_verified: bool,
+    pub first_break: Option<ChainBreak>,
+}
@@ -0,0 +1,61 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::fmt::Display;
+
+/// Longest `Idempotency-Key` accepted.
+pub const MAX_KEY_LEN: usize = 255;
+
+/// The response the first request with a key got, what retries with the key get as well.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub location: Option<String>,
+    pub body: Vec<u8>,
+    /// The response carried credentials, only its status was kept and it isn't replayed.
+    pub withheld: bool,
+}
+
+/// What the store knew about a key when it was claimed.
+#[derive(Debug)]
+pub enum IdempotencyClaim {
+    /// Unused or expired, the request goes ahead under it.
+    New,
+    /// Used before; the response is missing while that request is still in flight.
+    Existing { request_hash: String, response: Option<StoredResponse> },
+}
+
+#[derive(Debug)]
+pub enum IdempotencyError {
+    InvalidKey(String),
+    /// A request with the key is still being handled.
+    InFlight,
+    /// The key was used for a different request.
+    Mismatch,
+    /// The request was handled, but its response carried credentials that aren't kept.
+    Withheld,
+}
+
+impl Display for IdempotencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey(reason) => write!(f, "Invalid idempotency key: {}", reason),
+            Self::InFlight => write!(f, "A request with this idempotency key is still being processed"),
+            Self::Mismatch => write!(f, "The idempotency key was already used for a different request"),
+            Self::Withheld => write!(f, "The request with this idempotency key was handled, its response carried credentials and can not be replayed"),
+        }
+    }
+}
+
+impl std::error::Error for IdempotencyError {}
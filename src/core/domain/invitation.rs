@@ -0,0 +1,106 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use chrono::{DateTime, Utc};
+
+use super::namespace::NamespaceRole;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub namespace_id: Uuid,
+    pub email: String,
+    pub role: NamespaceRole,
+    pub invited_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Invitation {
+    pub fn is_pending(&self, now: DateTime<Utc>) -> bool {
+        self.accepted_at.is_none() && self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct NewInvitation {
+    #[validate(email)]
+    pub email: String,
+    pub role: NamespaceRole,
+}
+
+/// What the repository stores for a new invitation.
+#[derive(Debug)]
+pub struct NewInvitationRecord {
+    pub id: Uuid,
+    pub email: String,
+    pub role: NamespaceRole,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Who takes up an invitation: an account that exists, or one created by the accept itself.
+#[derive(Debug)]
+pub enum Invitee {
+    Existing(Uuid),
+    New { email: String, token_hash: String },
+}
+
+/// Returned once on creation, the token is what the invitee accepts with.
+#[derive(Debug, Serialize)]
+pub struct CreatedInvitation {
+    #[serde(flatten)]
+    pub invitation: Invitation,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitation {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AcceptedInvitation {
+    pub namespace_id: Uuid,
+    pub slug: String,
+    pub role: NamespaceRole,
+    pub email: String,
+    /// Only set when accepting created the account; shown this once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum InvitationError {
+    /// Unknown token, or an invitation that was accepted, revoked or has expired.
+    Invalid,
+    NotFound,
+    NotAllowed(String),
+}
+
+impl std::fmt::Display for InvitationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvitationError::Invalid => write!(f, "Invitation is invalid or no longer open"),
+            InvitationError::NotFound => write!(f, "Invitation not found"),
+            InvitationError::NotAllowed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for InvitationError {}
@@ -0,0 +1,94 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::schema::WorkflowSchemas;
+
+/// What a workflow component provides.
+///
+/// `workflows`, `activities`, `signals` and `queries` come from the `xarxa:manifest`
+/// custom section written by the SDK, `exports` is read from the component type itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowManifest {
+    #[serde(default)]
+    pub workflows: Vec<WorkflowTypeInfo>,
+    #[serde(default)]
+    pub activities: Vec<ActivityInfo>,
+    #[serde(default)]
+    pub signals: Vec<SignalInfo>,
+    #[serde(default)]
+    pub queries: Vec<QueryInfo>,
+    #[serde(default)]
+    pub exports: Vec<ExportedInterface>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowTypeInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_schema: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_schema: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_schema: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedInterface {
+    pub name: String,
+    pub functions: Vec<String>,
+}
+
+impl WorkflowManifest {
+    /// Schemas declared by the component itself.
+    ///
+    /// Workflow input/output schemas are only taken when the component has a single
+    /// workflow type, as a version carries one pair of them.
+    pub fn schemas(&self) -> WorkflowSchemas {
+        let (input, output) = match self.workflows.as_slice() {
+            [single] => (single.input_schema.clone(), single.output_schema.clone()),
+            _ => (None, None),
+        };
+
+        let activities = self.activities.iter()
+            .filter_map(|a| a.result_schema.clone().map(|s| (a.name.clone(), s)))
+            .collect();
+
+        WorkflowSchemas { input, output, activities }
+    }
+}
@@ -1,4 +1,17 @@
 pub mod workflow;
 pub mod wit;
 pub mod user;
-pub mod namespace;
\ No newline at end of file
+pub mod namespace;
+pub mod schema;
+pub mod manifest;
+pub mod audit;
+pub mod api_key;
+pub mod session;
+pub mod oidc;
+pub mod admin;
+pub mod cluster;
+pub mod invitation;
+pub mod scope;
+pub mod run;
+pub mod idempotency;
+pub mod search;
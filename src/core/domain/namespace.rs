@@ -15,9 +15,11 @@ use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 use chrono::{DateTime, Utc};
+use std::fmt::Display;
 
-#[derive(Debug, Clone, Serialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "namespace_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum NamespaceRole {
     Owner,
     Admin,
@@ -25,6 +27,62 @@ pub enum NamespaceRole {
     Viewer,
 }
 
+/// Actions that can be taken inside a namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// List and inspect workflows, versions and runs.
+    Read,
+    /// Start and signal runs.
+    Run,
+    /// Cancel and terminate runs, which an archived namespace still allows so its runs can wind down.
+    Stop,
+    /// Upload and unload workflow versions.
+    Upload,
+    /// Add, remove and change the role of members.
+    ManageMembers,
+    /// Rename and archive the namespace, read its audit log.
+    Administer,
+    /// Delete the namespace and what it owns.
+    Delete,
+}
+
+impl Permission {
+    /// Whether the permission changes something, which an archived namespace no longer allows.
+    pub fn is_write(&self) -> bool {
+        matches!(self, Self::Run | Self::Upload)
+    }
+}
+
+impl NamespaceRole {
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Owner => 3,
+            Self::Admin => 2,
+            Self::Editor => 1,
+            Self::Viewer => 0,
+        }
+    }
+
+    pub fn outranks(&self, other: NamespaceRole) -> bool {
+        self.rank() > other.rank()
+    }
+
+    /// Whether a member with this role may grant `other` or change a member holding it.
+    pub fn can_manage(&self, other: NamespaceRole) -> bool {
+        self.allows(Permission::ManageMembers) && (*self == Self::Owner || (self.rank() >= other.rank() && other != Self::Owner))
+    }
+
+    /// The permission matrix: every role has the permissions of the roles below it.
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::Read => true,
+            Permission::Run | Permission::Stop | Permission::Upload => matches!(self, Self::Owner | Self::Admin | Self::Editor),
+            Permission::ManageMembers | Permission::Administer => matches!(self, Self::Owner | Self::Admin),
+            Permission::Delete => matches!(self, Self::Owner),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct NewNamespace {
     #[validate(length(min = 5))]
@@ -37,6 +95,36 @@ pub struct Namespace {
     pub slug: String,
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+impl Namespace {
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct NamespaceUpdate {
+    #[validate(length(min = 5))]
+    pub slug: String,
+}
+
+/// A namespace found by slug; `alias` is set when the slug is an old one kept after a rename.
+#[derive(Debug)]
+pub struct ResolvedSlug {
+    pub namespace: Namespace,
+    pub alias: bool,
+}
+
+/// What has to be cleaned up outside the DB once a namespace row is gone.
+#[derive(Debug, Default)]
+pub struct DeletedNamespace {
+    pub slug: String,
+    pub workflow_keys: Vec<String>,
+    pub storage_urls: Vec<String>,
+    /// Runs that were still pending or running, they are deleted along with the namespace.
+    pub deleted_live_runs: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -45,4 +133,131 @@ pub struct NamespaceMember {
     pub user_id: Uuid,
     pub role: NamespaceRole,
     pub joined_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MemberView {
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: NamespaceRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct NewMember {
+    #[validate(email)]
+    pub email: String,
+    pub role: NamespaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MemberRoleUpdate {
+    pub role: NamespaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwnershipTransfer {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug)]
+pub enum NamespaceError {
+    NotFound,
+    SlugTaken(String),
+    Archived,
+}
+
+impl Display for NamespaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Namespace not found"),
+            Self::SlugTaken(slug) => write!(f, "Slug '{}' is already taken", slug),
+            Self::Archived => write!(f, "Namespace is archived"),
+        }
+    }
+}
+
+impl std::error::Error for NamespaceError {}
+
+/// Membership changes that are refused for business reasons rather than failures.
+#[derive(Debug)]
+pub enum MembershipError {
+    UserNotFound(String),
+    AlreadyMember,
+    NotMember,
+    LastOwner,
+    NotAllowed(String),
+}
+
+impl Display for MembershipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UserNotFound(email) => write!(f, "No user with email '{}'", email),
+            Self::AlreadyMember => write!(f, "The user is already a member of this namespace"),
+            Self::NotMember => write!(f, "The user is not a member of this namespace"),
+            Self::LastOwner => write!(f, "A namespace must keep at least one owner"),
+            Self::NotAllowed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MembershipError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERMISSIONS: [Permission; 7] = [
+        Permission::Read,
+        Permission::Run,
+        Permission::Stop,
+        Permission::Upload,
+        Permission::ManageMembers,
+        Permission::Administer,
+        Permission::Delete,
+    ];
+
+    fn granted(role: NamespaceRole) -> Vec<Permission> {
+        PERMISSIONS.into_iter().filter(|p| role.allows(*p)).collect()
+    }
+
+    #[test]
+    fn owner_holds_every_permission() {
+        assert_eq!(granted(NamespaceRole::Owner), PERMISSIONS);
+    }
+
+    #[test]
+    fn admin_holds_everything_but_delete() {
+        assert_eq!(granted(NamespaceRole::Admin), [
+            Permission::Read,
+            Permission::Run,
+            Permission::Stop,
+            Permission::Upload,
+            Permission::ManageMembers,
+            Permission::Administer,
+        ]);
+    }
+
+    #[test]
+    fn editor_reads_runs_and_uploads() {
+        assert_eq!(granted(NamespaceRole::Editor), [Permission::Read, Permission::Run, Permission::Stop, Permission::Upload]);
+    }
+
+    #[test]
+    fn viewer_only_reads() {
+        assert_eq!(granted(NamespaceRole::Viewer), [Permission::Read]);
+    }
+
+    #[test]
+    fn only_run_and_upload_are_writes() {
+        let writes: Vec<_> = PERMISSIONS.into_iter().filter(Permission::is_write).collect();
+        assert_eq!(writes, [Permission::Run, Permission::Upload]);
+    }
+
+    #[test]
+    fn admins_cannot_manage_owners() {
+        assert!(NamespaceRole::Owner.can_manage(NamespaceRole::Owner));
+        assert!(NamespaceRole::Admin.can_manage(NamespaceRole::Admin));
+        assert!(!NamespaceRole::Admin.can_manage(NamespaceRole::Owner));
+        assert!(!NamespaceRole::Editor.can_manage(NamespaceRole::Viewer));
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::namespace::NamespaceRole;
+
+/// A login in flight at the identity provider.
+#[derive(Debug, Clone)]
+pub struct OidcLogin {
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What the provider asserted about the user in a verified ID token.
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: String,
+    pub groups: Vec<String>,
+}
+
+/// Grants members of an IdP group a role in the namespace with the given slug.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupRoleRule {
+    pub group: String,
+    pub namespace: String,
+    pub role: NamespaceRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcCallback {
+    pub code: Option<String>,
+    pub state: String,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum OidcError {
+    /// Unknown, reused or expired `state`.
+    InvalidState,
+    /// The provider sent the user back with an error instead of a code.
+    Denied(String),
+    /// The ID token failed verification.
+    InvalidIdToken(String),
+    /// The ID token has no verified email to link the account by.
+    EmailMissing,
+}
+
+impl std::fmt::Display for OidcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OidcError::InvalidState => write!(f, "Sign-in attempt is unknown or expired, start over"),
+            OidcError::Denied(reason) => write!(f, "Identity provider refused the sign-in: {}", reason),
+            OidcError::InvalidIdToken(reason) => write!(f, "Invalid ID token: {}", reason),
+            OidcError::EmailMissing => write!(f, "Identity provider did not assert a verified email"),
+        }
+    }
+}
+
+impl std::error::Error for OidcError {}
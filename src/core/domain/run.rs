@@ -0,0 +1,503 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+
+use super::workflow::WorkflowVersion;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RunState {
+    /// Accepted, no executor picked it up yet.
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+    Terminated,
+}
+
+impl RunState {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Self::Pending | Self::Running)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Terminated => "terminated",
+        }
+    }
+}
+
+impl Display for RunState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Whether a run may take a business id earlier runs already used.
+/// Runs started with `allow_always` don't hold the id against anyone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ReusePolicy {
+    /// Refused while another run with the id is pending or running.
+    #[default]
+    RejectIfRunning,
+    /// Refused as well once a run with the id succeeded; failed, cancelled and terminated ones may be retried.
+    AllowIfFailed,
+    AllowAlways,
+}
+
+impl ReusePolicy {
+    /// Whether an earlier run in this state keeps the id from being used again.
+    pub fn blocks(&self, earlier: RunState) -> bool {
+        match self {
+            Self::RejectIfRunning => !earlier.is_finished(),
+            Self::AllowIfFailed => !earlier.is_finished() || earlier == RunState::Succeeded,
+            Self::AllowAlways => false,
+        }
+    }
+}
+
+/// Longest business id accepted.
+pub const MAX_BUSINESS_ID_LEN: usize = 255;
+
+/// A run as clients see it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Run {
+    pub id: Uuid,
+    pub namespace_id: Uuid,
+    pub workflow_id: Option<Uuid>,
+    pub workflow_key: String,
+    pub workflow_type: String,
+    pub version: String,
+    /// Caller's name for the run, see [`ReusePolicy`].
+    pub business_id: Option<String>,
+    /// Typed attributes the run can be found by, see [`super::search`].
+    pub search_attributes: JsonValue,
+    /// Run this one was started as a child of; stopping the parent stops it as well.
+    pub parent_run_id: Option<Uuid>,
+    pub state: RunState,
+    /// Set once cancellation was asked for, the run goes on until the workflow winds it down.
+    pub cancel_requested_at: Option<DateTime<Utc>>,
+    pub input: Option<JsonValue>,
+    pub result: Option<JsonValue>,
+    pub failure: Option<JsonValue>,
+    pub started_by: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunLinks {
+    #[serde(rename = "self")]
+    pub self_: String,
+    pub manifest: String,
+}
+
+impl RunLinks {
+    pub fn new(slug: &str, run_id: Uuid, workflow_key: &str, version: &str) -> Self {
+        RunLinks {
+            self_: format!("/api/ns/{}/runs/{}", slug, run_id),
+            manifest: format!("/api/ns/{}/workflows/{}/versions/{}/manifest", slug, workflow_key, version),
+        }
+    }
+}
+
+/// A run with where to find it and what it runs.
+#[derive(Debug, Serialize)]
+pub struct RunResource<T = Run> {
+    #[serde(flatten)]
+    pub run: T,
+    pub links: RunLinks,
+}
+
+impl RunResource {
+    pub fn new(run: Run, slug: &str) -> Self {
+        let links = RunLinks::new(slug, run.id, &run.workflow_key, &run.version);
+
+        RunResource { run, links }
+    }
+}
+
+impl RunResource<RunSummary> {
+    pub fn summary(run: RunSummary, slug: &str) -> Self {
+        let links = RunLinks::new(slug, run.id, &run.workflow_key, &run.version);
+
+        RunResource { run, links }
+    }
+}
+
+/// A run without its input and outcome, as runs are listed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub id: Uuid,
+    pub workflow_id: Option<Uuid>,
+    pub workflow_key: String,
+    pub workflow_type: String,
+    pub version: String,
+    pub business_id: Option<String>,
+    pub search_attributes: JsonValue,
+    pub parent_run_id: Option<Uuid>,
+    pub state: RunState,
+    pub cancel_requested_at: Option<DateTime<Utc>>,
+    pub started_by: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A run with everything that happened in it.
+#[derive(Debug, Serialize)]
+pub struct RunDetail {
+    #[serde(flatten)]
+    pub run: RunResource,
+    pub events: Vec<RunEvent>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RunFilter {
+    pub workflow_key: Option<String>,
+    pub version: Option<String>,
+    pub state: Option<RunState>,
+    pub business_id: Option<String>,
+    pub started_since: Option<DateTime<Utc>>,
+    pub started_until: Option<DateTime<Utc>>,
+    pub completed_since: Option<DateTime<Utc>>,
+    pub completed_until: Option<DateTime<Utc>>,
+    /// Search attribute conditions, see [`super::search::SearchQuery::parse`].
+    pub search: Option<String>,
+    /// Cursor, only runs started before this one are returned; run ids are UUIDv7 and sort by start.
+    pub before: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+impl RunFilter {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 500;
+
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT).clamp(1, Self::MAX_LIMIT)
+    }
+}
+
+/// A page of runs, newest first; `next_before` is the cursor for the next page, if there is one.
+#[derive(Debug, Serialize)]
+pub struct RunPage<T = RunSummary> {
+    pub runs: Vec<T>,
+    pub next_before: Option<Uuid>,
+}
+
+impl RunPage {
+    pub fn with_links(self, slug: &str) -> RunPage<RunResource<RunSummary>> {
+        RunPage {
+            runs: self.runs.into_iter().map(|r| RunResource::summary(r, slug)).collect(),
+            next_before: self.next_before,
+        }
+    }
+}
+
+/// Body of `POST /ns/{slug}/workflows/{key}/runs`.
+#[derive(Debug, Deserialize)]
+pub struct StartRun {
+    /// Checked against the input schema of the active version.
+    #[serde(default)]
+    pub input: JsonValue,
+    /// Workflow inside the component to run; only needed when it has more than one.
+    #[serde(default)]
+    pub workflow_type: Option<String>,
+    /// Caller's name for the business process, unique among its open runs in the namespace.
+    #[serde(default)]
+    pub business_id: Option<String>,
+    #[serde(default)]
+    pub reuse_policy: ReusePolicy,
+    /// Attributes the run can be found by; they have to be defined in the namespace.
+    #[serde(default)]
+    pub search_attributes: JsonValue,
+    /// Starts the run as a child of this running one in the same namespace.
+    #[serde(default)]
+    pub parent_run_id: Option<Uuid>,
+}
+
+/// Body of the cancel and terminate requests.
+#[derive(Debug, Default, Deserialize)]
+pub struct StopRun {
+    pub reason: Option<String>,
+}
+
+/// What cancelling or terminating a run reached.
+#[derive(Debug, Serialize)]
+pub struct StoppedRun<T = Run> {
+    #[serde(flatten)]
+    pub run: T,
+    /// Descendants that were still running and got stopped along with it.
+    pub children: Vec<Uuid>,
+}
+
+impl StoppedRun {
+    pub fn with_links(self, slug: &str) -> StoppedRun<RunResource> {
+        StoppedRun {
+            run: RunResource::new(self.run, slug),
+            children: self.children,
+        }
+    }
+}
+
+/// What the executor driving a run learns when it renews its claim.
+#[derive(Debug, Clone, Copy)]
+pub struct RunStatus {
+    pub cancel_requested: bool,
+}
+
+#[derive(Debug)]
+pub struct NewRun {
+    pub id: Uuid,
+    pub namespace_id: Uuid,
+    pub workflow_id: Uuid,
+    pub workflow_version_id: Uuid,
+    pub workflow_key: String,
+    pub workflow_type: String,
+    pub version: String,
+    pub business_id: Option<String>,
+    pub reuse_policy: ReusePolicy,
+    pub search_attributes: JsonValue,
+    pub parent_run_id: Option<Uuid>,
+    pub input: JsonValue,
+    pub started_by: Uuid,
+}
+
+pub mod event {
+    pub const RUN_STARTED: &str = "run.started";
+    pub const RUN_COMPLETED: &str = "run.completed";
+    pub const RUN_FAILED: &str = "run.failed";
+    pub const RUN_CANCEL_REQUESTED: &str = "run.cancel_requested";
+    pub const RUN_CANCELLED: &str = "run.cancelled";
+    pub const RUN_TERMINATED: &str = "run.terminated";
+    pub const ACTIVITY_SCHEDULED: &str = "activity.scheduled";
+    pub const ACTIVITY_COMPLETED: &str = "activity.completed";
+    pub const ACTIVITY_FAILED: &str = "activity.failed";
+    pub const ACTIVITY_TIMED_OUT: &str = "activity.timed_out";
+    pub const SEARCH_ATTRIBUTES_UPSERTED: &str = "search_attributes.upserted";
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunEvent {
+    pub seq: i32,
+    pub kind: String,
+    pub payload: JsonValue,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRunEvent {
+    pub kind: &'static str,
+    pub payload: JsonValue,
+}
+
+impl NewRunEvent {
+    pub fn new(kind: &'static str, payload: JsonValue) -> Self {
+        NewRunEvent { kind, payload }
+    }
+}
+
+/// A run an executor holds the lease of, with what it needs to drive it.
+#[derive(Debug)]
+pub struct ClaimedRun {
+    pub run: Run,
+    pub claim: RunClaim,
+    /// The numeric run id handed to the guest.
+    pub seq: i64,
+    /// `None` once the version was deleted, the run can't go on then.
+    pub version: Option<WorkflowVersion>,
+    pub storage_url: Option<String>,
+    pub events: Vec<RunEvent>,
+}
+
+/// An executor's hold on a run. Its writes name the claim, so once the lease ran out
+/// and another executor took the run over, the writes of the one before are refused.
+#[derive(Debug, Clone, Copy)]
+pub struct RunClaim {
+    pub run_id: Uuid,
+    pub token: Uuid,
+}
+
+impl ClaimedRun {
+    /// Activities that finished, in the order they did, which is what the guest is replayed with.
+    pub fn completed_tasks(&self) -> Vec<CompletedTask> {
+        self.events.iter()
+            .filter(|e| e.kind == event::ACTIVITY_COMPLETED || e.kind == event::ACTIVITY_FAILED)
+            .map(|e| CompletedTask {
+                id: e.payload["scheduled"].as_i64().unwrap_or_default(),
+                name: e.payload["name"].as_str().unwrap_or_default().to_string(),
+                result: e.payload.get("result").cloned(),
+                error: e.payload.get("error").and_then(|v| v.as_str()).map(str::to_string),
+            })
+            .collect()
+    }
+
+    /// Activities scheduled before the executor holding the run went away, they run again.
+    pub fn unfinished_activities(&self) -> Vec<(i64, ActivityCall)> {
+        let finished: Vec<i64> = self.completed_tasks().iter().map(|t| t.id).collect();
+
+        self.events.iter()
+            .filter(|e| e.kind == event::ACTIVITY_SCHEDULED && !finished.contains(&(e.seq as i64)))
+            .map(|e| (e.seq as i64, ActivityCall {
+                name: e.payload["name"].as_str().unwrap_or_default().to_string(),
+                input: e.payload.get("input").cloned().unwrap_or_default(),
+                heartbeat_timeout_secs: e.payload.get("heartbeat_timeout_secs").and_then(|v| v.as_u64()),
+                max_attempts: e.payload.get("max_attempts").and_then(|v| v.as_u64()).map(|n| n as u32),
+            }))
+            .collect()
+    }
+}
+
+/// An activity outcome as the guest is told about it; `id` is the seq of its `activity.scheduled` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletedTask {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityCall {
+    pub name: String,
+    #[serde(default)]
+    pub input: JsonValue,
+    /// Seconds the activity may go without a heartbeat before its attempt fails and is retried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_timeout_secs: Option<u64>,
+    /// Attempts the activity gets when it keeps missing heartbeats, [`DEFAULT_MAX_ATTEMPTS`] if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+}
+
+impl ActivityCall {
+    pub fn heartbeat_timeout(&self) -> Option<std::time::Duration> {
+        self.heartbeat_timeout_secs.map(std::time::Duration::from_secs)
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1)
+    }
+}
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Largest heartbeat details an activity may send.
+pub const MAX_HEARTBEAT_DETAILS_BYTES: usize = 64 * 1024;
+
+/// An attempt at a scheduled activity, with the last heartbeat details of the attempts before it.
+#[derive(Debug, Clone)]
+pub struct ActivityAttempt {
+    pub attempt: i32,
+    pub heartbeat_details: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum DecisionOutput {
+    Waiting {
+        #[serde(default)]
+        activities: Vec<ActivityCall>,
+    },
+    Failed {
+        error: String,
+    },
+    Completed {
+        #[serde(default)]
+        result: JsonValue,
+    },
+    Cancelled {
+        #[serde(default)]
+        details: JsonValue,
+    },
+}
+
+/// What a workflow wants next, read from the output of `continue-workflow`.
+///
+/// `{"status": "waiting", "activities": [{"name", "input"}]}` schedules activities,
+/// `{"status": "failed", "error"}` fails the run, `{"status": "completed", "result"}`
+/// completes it and `{"status": "cancelled", "details"}` ends it as cancelled once the
+/// workflow wound down after a cancellation request. Output without a `status` is the result itself, which is what
+/// workflows written before the engine drove runs return; one with a `status` that doesn't parse is invalid.
+#[derive(Debug)]
+pub enum Decision {
+    Schedule(Vec<ActivityCall>),
+    Fail(String),
+    Complete(JsonValue),
+    Cancelled(JsonValue),
+    Invalid(String),
+}
+
+impl Decision {
+    pub fn from_output(output: JsonValue) -> Self {
+        if output.get("status").is_none() {
+            return Self::Complete(output);
+        }
+
+        match serde_json::from_value::<DecisionOutput>(output) {
+            Ok(DecisionOutput::Waiting { activities }) => Self::Schedule(activities),
+            Ok(DecisionOutput::Failed { error }) => Self::Fail(error),
+            Ok(DecisionOutput::Completed { result }) => Self::Complete(result),
+            Ok(DecisionOutput::Cancelled { details }) => Self::Cancelled(details),
+            Err(e) => Self::Invalid(format!("Workflow returned an invalid decision: {}", e)),
+        }
+    }
+}
+
+/// Why a run failed, as stored in its `failure` column.
+pub fn failure(kind: &str, message: impl Into<String>) -> JsonValue {
+    json!({ "kind": kind, "message": message.into() })
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    NotFound,
+    /// The component has no workflow of this type, or more than one and none was named.
+    UnknownWorkflowType(String),
+    InvalidBusinessId(String),
+    /// The reuse policy refused the business id because of this earlier run.
+    BusinessIdInUse(Uuid),
+    /// Only pending and running runs can be cancelled, terminated or get children.
+    NotActive(RunState),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Run not found"),
+            Self::UnknownWorkflowType(reason) => write!(f, "{}", reason),
+            Self::InvalidBusinessId(reason) => write!(f, "Invalid business id: {}", reason),
+            Self::BusinessIdInUse(run_id) => write!(f, "Business id is already used by run {}", run_id),
+            Self::NotActive(state) => write!(f, "Run is already {}", state),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
@@ -0,0 +1,139 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use anyhow::Result;
+use jsonschema::Validator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// JSON Schemas declared by a workflow version.
+///
+/// `activities` maps an activity name to the schema its result must satisfy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowSchemas {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub activities: HashMap<String, JsonValue>,
+}
+
+impl WorkflowSchemas {
+    pub fn is_empty(&self) -> bool {
+        self.input.is_none() && self.output.is_none() && self.activities.is_empty()
+    }
+
+    /// Compiles every declared schema; the ones that are not a valid JSON Schema are all reported,
+    /// each at its path in the schemas document.
+    pub fn compile(&self) -> Result<CompiledSchemas, SchemaViolations> {
+        let mut violations = Vec::new();
+
+        let input = self.input.as_ref()
+            .and_then(|s| compile_schema(s, "/input", &mut violations));
+
+        let output = self.output.as_ref()
+            .and_then(|s| compile_schema(s, "/output", &mut violations));
+
+        let activities = self.activities.iter()
+            .filter_map(|(name, s)| {
+                compile_schema(s, &format!("/activities/{}", name), &mut violations)
+                    .map(|validator| (name.clone(), validator))
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(CompiledSchemas { input, output, activities })
+        } else {
+            Err(SchemaViolations { target: "schemas document".to_string(), violations })
+        }
+    }
+}
+
+fn compile_schema(schema: &JsonValue, path: &str, violations: &mut Vec<Violation>) -> Option<Validator> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => Some(validator),
+        Err(e) => {
+            violations.push(Violation {
+                path: format!("{}{}", path, e.instance_path),
+                message: e.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Ready-to-use validators built from [`WorkflowSchemas`].
+#[derive(Default)]
+pub struct CompiledSchemas {
+    input: Option<Validator>,
+    output: Option<Validator>,
+    activities: HashMap<String, Validator>,
+}
+
+impl CompiledSchemas {
+    pub fn validate_input(&self, value: &JsonValue) -> Result<(), SchemaViolations> {
+        validate(self.input.as_ref(), value, "workflow input")
+    }
+
+    pub fn validate_output(&self, value: &JsonValue) -> Result<(), SchemaViolations> {
+        validate(self.output.as_ref(), value, "workflow output")
+    }
+
+    pub fn validate_activity_result(&self, activity: &str, value: &JsonValue) -> Result<(), SchemaViolations> {
+        validate(self.activities.get(activity), value, &format!("result of activity '{}'", activity))
+    }
+}
+
+fn validate(validator: Option<&Validator>, value: &JsonValue, target: &str) -> Result<(), SchemaViolations> {
+    let Some(validator) = validator else {
+        return Ok(());
+    };
+
+    let violations: Vec<Violation> = validator.iter_errors(value)
+        .map(|e| Violation {
+            path: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(SchemaViolations { target: target.to_string(), violations })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+/// A value did not match the schema declared for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaViolations {
+    pub target: String,
+    pub violations: Vec<Violation>,
+}
+
+impl Display for SchemaViolations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The {} does not match its schema ({} violation(s))", self.target, self.violations.len())
+    }
+}
+
+impl std::error::Error for SchemaViolations {}
@@ -0,0 +1,153 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use super::namespace::Permission;
+
+/// What a token may be used for, on top of what the role of its user allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    /// List and inspect workflows, versions and their schemas.
+    #[serde(rename = "workflows:read")]
+    WorkflowsRead,
+    /// Start runs.
+    #[serde(rename = "workflows:run")]
+    WorkflowsRun,
+    /// Upload workflow versions.
+    #[serde(rename = "workflows:publish")]
+    WorkflowsPublish,
+    /// List and inspect runs.
+    #[serde(rename = "runs:read")]
+    RunsRead,
+    /// Members, API keys, invitations, the audit log and the namespace itself.
+    #[serde(rename = "namespace:admin")]
+    NamespaceAdmin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 5] = [
+        Self::WorkflowsRead,
+        Self::WorkflowsRun,
+        Self::WorkflowsPublish,
+        Self::RunsRead,
+        Self::NamespaceAdmin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WorkflowsRead => "workflows:read",
+            Self::WorkflowsRun => "workflows:run",
+            Self::WorkflowsPublish => "workflows:publish",
+            Self::RunsRead => "runs:read",
+            Self::NamespaceAdmin => "namespace:admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+
+    /// The scope an endpoint guarded by `permission` needs unless it names another one.
+    pub fn for_permission(permission: Permission) -> Scope {
+        match permission {
+            Permission::Read => Self::WorkflowsRead,
+            Permission::Run | Permission::Stop => Self::WorkflowsRun,
+            Permission::Upload => Self::WorkflowsPublish,
+            Permission::ManageMembers | Permission::Administer | Permission::Delete => Self::NamespaceAdmin,
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Limits a token carries; a field left out does not restrict anything,
+/// so tokens issued without scopes keep doing all their user's role allows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenScopes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<Scope>>,
+    /// Namespaces the token can be used in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespaces: Option<Vec<Uuid>>,
+}
+
+impl TokenScopes {
+    /// Reads the `scopes` and `namespace_ids` columns; names no longer known grant nothing.
+    pub fn from_columns(scopes: Option<Vec<String>>, namespaces: Option<Vec<Uuid>>) -> Self {
+        TokenScopes {
+            scopes: scopes.map(|names| names.iter().filter_map(|s| Scope::parse(s)).collect()),
+            namespaces,
+        }
+    }
+
+    pub fn scope_names(&self) -> Option<Vec<String>> {
+        self.scopes.as_ref().map(|scopes| scopes.iter().map(|s| s.as_str().to_string()).collect())
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.scopes.is_some() || self.namespaces.is_some()
+    }
+
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.as_ref().is_none_or(|scopes| scopes.contains(&scope))
+    }
+
+    pub fn allows_namespace(&self, namespace_id: Uuid) -> bool {
+        self.namespaces.as_ref().is_none_or(|namespaces| namespaces.contains(&namespace_id))
+    }
+
+    /// Whether these limits grant nothing `granted` doesn't, so a restricted token only hands out narrower ones.
+    pub fn is_within(&self, granted: &TokenScopes) -> bool {
+        let scopes_within = match (&self.scopes, &granted.scopes) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(asked), Some(_)) => asked.iter().all(|s| granted.allows(*s)),
+        };
+
+        let namespaces_within = match (&self.namespaces, &granted.namespaces) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some(asked), Some(_)) => asked.iter().all(|ns| granted.allows_namespace(*ns)),
+        };
+
+        scopes_within && namespaces_within
+    }
+}
+
+#[derive(Debug)]
+pub enum ScopeError {
+    /// The token does not carry the scope an endpoint needs.
+    Missing(Scope),
+    NamespaceNotAllowed,
+    /// A restricted token asked for a token with more than it has.
+    Escalation,
+}
+
+impl Display for ScopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(scope) => write!(f, "Token lacks the '{}' scope", scope),
+            Self::NamespaceNotAllowed => write!(f, "Token can not be used in this namespace"),
+            Self::Escalation => write!(f, "A token can not grant more than its own scopes"),
+        }
+    }
+}
+
+impl std::error::Error for ScopeError {}
@@ -0,0 +1,258 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use std::fmt::Display;
+use uuid::Uuid;
+
+/// Attributes a run can hold in a namespace.
+pub const MAX_DEFINITIONS: usize = 100;
+/// Longest keyword value kept.
+pub const MAX_KEYWORD_LEN: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SearchAttributeKind {
+    Keyword,
+    Int,
+    Datetime,
+    Bool,
+}
+
+impl SearchAttributeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Int => "int",
+            Self::Datetime => "datetime",
+            Self::Bool => "bool",
+        }
+    }
+
+    /// Whether runs can be filtered by a range of the attribute, not only by its value.
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, Self::Int | Self::Datetime)
+    }
+
+    /// The value as it is stored, `None` when it isn't one of this kind.
+    /// Datetimes are kept in UTC, so equal instants compare equal.
+    fn normalize(&self, value: &JsonValue) -> Option<JsonValue> {
+        match (self, value) {
+            (Self::Keyword, JsonValue::String(s)) if s.len() <= MAX_KEYWORD_LEN => Some(value.clone()),
+            (Self::Int, JsonValue::Number(n)) => n.as_i64().map(JsonValue::from),
+            (Self::Datetime, JsonValue::String(s)) => DateTime::parse_from_rfc3339(s).ok()
+                .map(|d| JsonValue::String(d.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Micros, true))),
+            (Self::Bool, JsonValue::Bool(_)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Reads a value written in a run filter.
+    fn parse(&self, raw: &str) -> Option<JsonValue> {
+        let value = match self {
+            Self::Keyword | Self::Datetime => JsonValue::String(raw.to_string()),
+            Self::Int => JsonValue::from(raw.parse::<i64>().ok()?),
+            Self::Bool => JsonValue::Bool(raw.parse::<bool>().ok()?),
+        };
+
+        self.normalize(&value)
+    }
+}
+
+impl Display for SearchAttributeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A search attribute registered in a namespace; runs can only hold registered ones.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchAttributeDefinition {
+    pub name: String,
+    pub kind: SearchAttributeKind,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewSearchAttribute {
+    pub name: String,
+    pub kind: SearchAttributeKind,
+}
+
+impl NewSearchAttribute {
+    /// Lowercase letters, digits and underscores, starting with a letter; `=`, `<` and `,` would break filters.
+    pub fn validate(&self) -> Result<(), SearchAttributeError> {
+        let mut chars = self.name.chars();
+        let valid = self.name.len() <= 64
+            && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+        if !valid {
+            return Err(SearchAttributeError::InvalidName(self.name.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks attributes a run is given against the definitions of its namespace,
+/// returning them as they are stored. A `null` value removes the attribute.
+pub fn normalize(definitions: &[SearchAttributeDefinition], attributes: &JsonValue) -> Result<Map<String, JsonValue>, SearchAttributeError> {
+    let attributes = match attributes {
+        JsonValue::Null => return Ok(Map::new()),
+        JsonValue::Object(attributes) => attributes,
+        _ => return Err(SearchAttributeError::NotAnObject),
+    };
+
+    attributes.iter()
+        .map(|(name, value)| {
+            let definition = find(definitions, name)?;
+            if value.is_null() {
+                return Ok((name.clone(), JsonValue::Null));
+            }
+
+            let value = definition.kind.normalize(value)
+                .ok_or_else(|| SearchAttributeError::InvalidValue { name: name.clone(), kind: definition.kind })?;
+
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+fn find<'a>(definitions: &'a [SearchAttributeDefinition], name: &str) -> Result<&'a SearchAttributeDefinition, SearchAttributeError> {
+    definitions.iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| SearchAttributeError::Unknown(name.to_string()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl RangeOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RangeCondition {
+    pub name: String,
+    pub kind: SearchAttributeKind,
+    pub op: RangeOp,
+    /// Normalized like stored values, as text.
+    pub value: String,
+}
+
+/// Search attribute conditions of a run filter, all of which a run has to meet.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Attributes the run has to hold with these values.
+    pub equals: Map<String, JsonValue>,
+    pub ranges: Vec<RangeCondition>,
+}
+
+impl SearchQuery {
+    /// Parses `name=value` and `name>value` (also `>=`, `<`, `<=`) conditions, separated by commas:
+    /// `customer_id=c-42,amount>=100,created<2025-10-01T00:00:00Z`.
+    pub fn parse(definitions: &[SearchAttributeDefinition], query: &str) -> Result<Self, SearchAttributeError> {
+        let mut parsed = SearchQuery::default();
+
+        for condition in query.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let at = condition.find(['=', '<', '>'])
+                .ok_or_else(|| SearchAttributeError::InvalidQuery(format!("'{}' has no operator", condition)))?;
+            let (name, rest) = condition.split_at(at);
+
+            let (op, raw) = if let Some(raw) = rest.strip_prefix(">=") {
+                (Some(RangeOp::Gte), raw)
+            } else if let Some(raw) = rest.strip_prefix("<=") {
+                (Some(RangeOp::Lte), raw)
+            } else if let Some(raw) = rest.strip_prefix('>') {
+                (Some(RangeOp::Gt), raw)
+            } else if let Some(raw) = rest.strip_prefix('<') {
+                (Some(RangeOp::Lt), raw)
+            } else {
+                (None, &rest[1..])
+            };
+
+            let name = name.trim();
+            let definition = find(definitions, name)?;
+            let value = definition.kind.parse(raw.trim())
+                .ok_or_else(|| SearchAttributeError::InvalidValue { name: name.to_string(), kind: definition.kind })?;
+
+            match op {
+                None => {
+                    parsed.equals.insert(name.to_string(), value);
+                }
+                Some(op) if definition.kind.is_ordered() => parsed.ranges.push(RangeCondition {
+                    name: name.to_string(),
+                    kind: definition.kind,
+                    op,
+                    value: match value {
+                        JsonValue::String(s) => s,
+                        other => other.to_string(),
+                    },
+                }),
+                Some(op) => {
+                    return Err(SearchAttributeError::InvalidQuery(format!(
+                        "'{}' can't be compared with {}, only int and datetime attributes can", name, op.as_str(),
+                    )));
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[derive(Debug)]
+pub enum SearchAttributeError {
+    InvalidName(String),
+    AlreadyDefined(String),
+    TooMany,
+    NotFound(String),
+    Unknown(String),
+    NotAnObject,
+    InvalidValue { name: String, kind: SearchAttributeKind },
+    InvalidQuery(String),
+}
+
+impl Display for SearchAttributeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "Invalid search attribute name '{}': use lowercase letters, digits and underscores, starting with a letter", name),
+            Self::AlreadyDefined(name) => write!(f, "Search attribute '{}' already exists", name),
+            Self::TooMany => write!(f, "A namespace can't have more than {} search attributes", MAX_DEFINITIONS),
+            Self::NotFound(name) => write!(f, "Search attribute '{}' not found", name),
+            Self::Unknown(name) => write!(f, "Search attribute '{}' is not defined in this namespace", name),
+            Self::NotAnObject => write!(f, "Search attributes must be a JSON object"),
+            Self::InvalidValue { name, kind } => write!(f, "Search attribute '{}' expects a {} value", name, kind),
+            Self::InvalidQuery(reason) => write!(f, "Invalid search query: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SearchAttributeError {}
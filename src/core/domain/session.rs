@@ -0,0 +1,100 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::fmt::Display;
+
+use super::scope::TokenScopes;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Scope names its access tokens are limited to, see [`TokenScopes`].
+    pub scopes: Option<Vec<String>>,
+    pub namespace_ids: Option<Vec<Uuid>>,
+}
+
+impl Session {
+    pub fn token_scopes(&self) -> TokenScopes {
+        TokenScopes::from_columns(self.scopes.clone(), self.namespace_ids.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct NewSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_hash: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub scopes: Option<Vec<String>>,
+    pub namespace_ids: Option<Vec<Uuid>>,
+}
+
+/// A session with the hash of its current refresh secret.
+#[derive(Debug)]
+pub struct SessionRecord {
+    pub session: Session,
+    pub refresh_hash: String,
+}
+
+/// A session that was just started or refreshed, with what the client has to keep.
+#[derive(Debug)]
+pub struct IssuedSession {
+    pub session: Session,
+    pub refresh_token: String,
+}
+
+/// Where a sign-in came from, kept so users can tell their sessions apart.
+#[derive(Debug, Default)]
+pub struct SessionOrigin {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevokeTokenRequest {
+    pub access_token: String,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    InvalidRefreshToken,
+    NotFound,
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRefreshToken => write!(f, "Invalid or expired refresh token"),
+            Self::NotFound => write!(f, "Session not found"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
@@ -16,6 +16,8 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
 
+use super::scope::TokenScopes;
+
 pub type Token = String;
 
 #[allow(dead_code)]
@@ -27,6 +29,13 @@ pub struct User {
     pub token: Token,
     pub super_admin: bool,
     pub created_at: DateTime<Utc>,
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+impl User {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -46,4 +55,95 @@ pub struct UserAuth {
     pub email: String,
     #[validate(length(min = 24))]
     pub password: Token,
-}
\ No newline at end of file
+    /// Limits for the tokens of the session signed in to, none by default.
+    #[serde(flatten)]
+    pub limits: TokenScopes,
+}
+#[derive(Debug)]
+pub enum SignInError {
+    InvalidCredentials,
+    Disabled,
+    TooManyAttempts { retry_after_secs: u64 },
+}
+
+impl std::fmt::Display for SignInError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignInError::InvalidCredentials => write!(f, "Wrong email or password"),
+            SignInError::Disabled => write!(f, "This account is disabled"),
+            SignInError::TooManyAttempts { retry_after_secs } => {
+                write!(f, "Too many failed sign-ins, try again in {} seconds", retry_after_secs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignInError {}
+
+/// Who may create an account through `POST /auth/signup` or a first single sign-on.
+///
+/// Invitations create accounts regardless; the inviting member is the gate there.
+#[derive(Debug, Clone)]
+pub enum RegistrationMode {
+    Open,
+    InviteOnly,
+    AllowedDomains(Vec<String>),
+    Disabled,
+}
+
+impl RegistrationMode {
+    /// `domains` is a comma separated list, only used by the `allowed_domains` mode.
+    pub fn parse(mode: &str, domains: Option<&str>) -> Result<Self, anyhow::Error> {
+        match mode {
+            "open" => Ok(RegistrationMode::Open),
+            "invite_only" => Ok(RegistrationMode::InviteOnly),
+            "disabled" => Ok(RegistrationMode::Disabled),
+            "allowed_domains" => {
+                let domains: Vec<String> = domains.unwrap_or_default()
+                    .split(',')
+                    .map(|d| d.trim().trim_start_matches('@').to_lowercase())
+                    .filter(|d| !d.is_empty())
+                    .collect();
+
+                anyhow::ensure!(!domains.is_empty(), "Registration mode 'allowed_domains' needs at least one domain");
+                Ok(RegistrationMode::AllowedDomains(domains))
+            }
+            other => anyhow::bail!("Unknown registration mode '{}'", other),
+        }
+    }
+
+    pub fn check(&self, email: &str) -> Result<(), RegistrationError> {
+        match self {
+            RegistrationMode::Open => Ok(()),
+            RegistrationMode::InviteOnly => Err(RegistrationError::InviteOnly),
+            RegistrationMode::Disabled => Err(RegistrationError::Closed),
+            RegistrationMode::AllowedDomains(domains) => {
+                let domain = email.rsplit_once('@').map(|(_, d)| d.to_lowercase()).unwrap_or_default();
+                if domains.contains(&domain) {
+                    Ok(())
+                } else {
+                    Err(RegistrationError::DomainNotAllowed(domain))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RegistrationError {
+    Closed,
+    InviteOnly,
+    DomainNotAllowed(String),
+}
+
+impl std::fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistrationError::Closed => write!(f, "Registration is closed"),
+            RegistrationError::InviteOnly => write!(f, "Registration is by invitation only"),
+            RegistrationError::DomainNotAllowed(domain) => write!(f, "Registration is not open to '{}' addresses", domain),
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
@@ -15,7 +15,9 @@ use md5::Digest;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
+
+use super::schema::WorkflowSchemas;
+use super::manifest::WorkflowManifest;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Workflow {
@@ -38,13 +40,6 @@ pub struct NewWorkflowParams {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WorkflowResponse {
-    pub success: bool,
-    pub result: Option<JsonValue>,
-    pub error: Option<String>,
-}
-
 #[derive(Debug)]
 pub struct NewWorkflow {
     pub key: String,
@@ -53,4 +48,27 @@ pub struct NewWorkflow {
     pub wasm_md5: Digest,
     pub wasm_size_bytes: usize,
     pub storage_url: String,
+    pub schemas: WorkflowSchemas,
+    pub manifest: WorkflowManifest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkflowVersion {
+    pub id: Uuid,
+    pub workflow_id: Uuid,
+    pub version: String,
+    pub wasm_size_bytes: i64,
+    pub created_by: Uuid,
+    pub changelog: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub schemas: WorkflowSchemas,
+    pub manifest: WorkflowManifest,
+}
+
+/// Where the wasm of a workflow version lives, used to reconcile the DB with the blob store.
+#[derive(Debug, Clone)]
+pub struct VersionBlobRef {
+    pub version_id: Uuid,
+    pub storage_url: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::domain::audit::{AuditCheckpoint, AuditEntry, AuditFilter, ChainHead, ChainedEntry, NewAuditEntry};
+
+/// Where audit entries are written to and read back from.
+///
+/// Repository changes that run in a transaction append their entry in that same
+/// transaction; everything else records through this port right after the change.
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entry: &NewAuditEntry) -> Result<(), anyhow::Error>;
+    /// Newest first, at most `limit` entries.
+    async fn query(&self, filter: &AuditFilter, limit: i64) -> Result<Vec<AuditEntry>, anyhow::Error>;
+    /// Chained entries with `seq > after_seq`, oldest first.
+    async fn chain_entries(&self, chain_id: Uuid, after_seq: i64, limit: i64) -> Result<Vec<ChainedEntry>, anyhow::Error>;
+    async fn chain_heads(&self) -> Result<Vec<ChainHead>, anyhow::Error>;
+    async fn save_checkpoint(&self, head: &ChainHead, key_id: &str, signature: &[u8]) -> Result<AuditCheckpoint, anyhow::Error>;
+    async fn latest_checkpoint(&self, chain_id: Uuid) -> Result<Option<AuditCheckpoint>, anyhow::Error>;
+    /// Newest first; every chain when `chain_id` is `None`.
+    async fn checkpoints(&self, chain_id: Option<Uuid>) -> Result<Vec<AuditCheckpoint>, anyhow::Error>;
+}
@@ -0,0 +1,29 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone)]
+pub struct BlobInfo {
+    pub key: String,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), anyhow::Error>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, anyhow::Error>;
+    async fn delete(&self, key: &str) -> Result<(), anyhow::Error>;
+    async fn list(&self, prefix: &str) -> Result<Vec<BlobInfo>, anyhow::Error>;
+}
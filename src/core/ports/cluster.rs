@@ -0,0 +1,24 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+
+use crate::core::domain::cluster::ClusterEvent;
+
+/// Fan-out of [`ClusterEvent`]s to every running node.
+#[async_trait]
+pub trait ClusterBus: Send + Sync {
+    async fn publish(&self, event: &ClusterEvent) -> Result<(), anyhow::Error>;
+    /// Waits for the next event published by any node, this one included.
+    async fn recv(&self) -> Result<ClusterEvent, anyhow::Error>;
+}
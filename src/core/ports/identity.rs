@@ -0,0 +1,25 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use async_trait::async_trait;
+
+use crate::core::domain::oidc::Identity;
+
+/// An OpenID Connect provider users sign in through.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    /// Where to send the browser to sign in; `code_challenge` is the S256 PKCE challenge.
+    fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> String;
+    /// Redeems an authorization code and verifies the ID token that comes back.
+    async fn exchange(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<Identity, anyhow::Error>;
+}
@@ -11,5 +11,9 @@
  * SPDX-License-Identifier: AGPL-3.0-or-later
  */
 
+pub mod audit;
+pub mod blob;
+pub mod cluster;
+pub mod identity;
 pub mod storage;
 pub mod wit;
\ No newline at end of file
@@ -12,12 +12,22 @@
  */
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value as JsonValue};
 use uuid::Uuid;
 
 use crate::core::domain::{
+    api_key::{ApiKey, ApiKeyCredentials, NewApiKeyRecord},
     user::{NewUser, User},
-    workflow::{NewWorkflow, Workflow},
-    namespace::{NewNamespace, Namespace, NamespaceRole},
+    session::{NewSession, Session, SessionRecord},
+    oidc::OidcLogin,
+    invitation::{Invitation, Invitee, NewInvitationRecord},
+    admin::{NamespaceStats, NamespaceStatsFilter, UserFilter, UserSummary},
+    workflow::{NewWorkflow, Workflow, WorkflowVersion, VersionBlobRef},
+    run::{ActivityAttempt, ClaimedRun, RunClaim, NewRun, NewRunEvent, Run, RunEvent, RunFilter, RunState, RunStatus, RunSummary, StoppedRun},
+    idempotency::{IdempotencyClaim, StoredResponse},
+    search::{NewSearchAttribute, SearchAttributeDefinition, SearchQuery},
+    namespace::{DeletedNamespace, MemberView, NewNamespace, Namespace, NamespaceRole, ResolvedSlug},
 };
 
 
@@ -25,14 +35,18 @@ use crate::core::domain::{
 pub trait WorkflowRepository: Send + Sync {
     async fn insert(&self, user_id: Uuid, ns: Uuid, w: &NewWorkflow) -> Result<Workflow, anyhow::Error>;
     // async fn find_by_id(&self, id: Uuid) -> Result<Option<Workflow>, anyhow::Error>;
-    // async fn find_by_key(&self, key: &str) -> Result<Option<Workflow>, anyhow::Error>;
+    async fn find_by_key(&self, ns_id: Uuid, key: &str) -> Result<Option<Workflow>, anyhow::Error>;
     async fn find_all(&self, ns_id: Uuid) -> Result<Vec<Workflow>, anyhow::Error>;
+    async fn find_version(&self, ns_id: Uuid, key: &str, version: &str) -> Result<Option<WorkflowVersion>, anyhow::Error>;
+    async fn blob_refs(&self) -> Result<Vec<VersionBlobRef>, anyhow::Error>;
 }
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn create(&self, u: &NewUser, token_hash: String) -> Result<User, anyhow::Error>; 
     async fn find_by_email(&self, email: &str) ->  Result<Option<User>, anyhow::Error>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, anyhow::Error>;
+    async fn update_token_hash(&self, id: Uuid, token_hash: String) -> Result<(), anyhow::Error>;
 }
 
 #[async_trait]
@@ -40,4 +54,124 @@ pub trait NamespaceRepository: Send + Sync {
     async fn create(&self, uid: Uuid, ns: &NewNamespace) -> Result<Namespace, anyhow::Error>; 
     async fn find_by_uid(&self, uid: Uuid) ->  Result<Vec<Namespace>, anyhow::Error>;
     async fn role_by_uid(&self, uid: Uuid, ns_id: Uuid) ->  Result<Option<NamespaceRole>, anyhow::Error>;
-}
\ No newline at end of file
+    async fn find_by_id(&self, ns_id: Uuid) -> Result<Option<Namespace>, anyhow::Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<ResolvedSlug>, anyhow::Error>;
+    async fn rename(&self, actor: Uuid, ns_id: Uuid, slug: &str) -> Result<Namespace, anyhow::Error>;
+    async fn set_archived(&self, actor: Uuid, ns_id: Uuid, archived: bool) -> Result<Namespace, anyhow::Error>;
+    async fn delete(&self, actor: Uuid, ns_id: Uuid) -> Result<DeletedNamespace, anyhow::Error>;
+    async fn members(&self, ns_id: Uuid) -> Result<Vec<MemberView>, anyhow::Error>;
+    async fn add_member(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid, role: NamespaceRole) -> Result<(), anyhow::Error>;
+    async fn set_member_role(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid, role: NamespaceRole) -> Result<(), anyhow::Error>;
+    async fn remove_member(&self, actor: Uuid, ns_id: Uuid, user_id: Uuid) -> Result<(), anyhow::Error>;
+    async fn transfer_ownership(&self, actor: Uuid, ns_id: Uuid, new_owner: Uuid) -> Result<(), anyhow::Error>;
+}
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Creates the key together with its service account and the account's membership.
+    async fn create(&self, actor: Uuid, ns_id: Uuid, k: &NewApiKeyRecord) -> Result<ApiKey, anyhow::Error>;
+    async fn find_all(&self, ns_id: Uuid) -> Result<Vec<ApiKey>, anyhow::Error>;
+    async fn find_credentials(&self, prefix: &str) -> Result<Option<ApiKeyCredentials>, anyhow::Error>;
+    async fn touch(&self, key_id: Uuid) -> Result<(), anyhow::Error>;
+    /// Revokes the key and removes its service account from the namespace.
+    async fn revoke(&self, actor: Uuid, ns_id: Uuid, key_id: Uuid) -> Result<ApiKey, anyhow::Error>;
+}
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn create(&self, s: &NewSession) -> Result<Session, anyhow::Error>;
+    async fn find(&self, id: Uuid) -> Result<Option<SessionRecord>, anyhow::Error>;
+    /// Swaps the refresh hash only if it is still `old_hash`, so a refresh token works once.
+    async fn rotate_refresh(&self, id: Uuid, old_hash: &str, new_hash: &str, expires_at: DateTime<Utc>) -> Result<Option<Session>, anyhow::Error>;
+    /// Active sessions, newest first.
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<Session>, anyhow::Error>;
+    async fn revoke(&self, user_id: Uuid, id: Uuid) -> Result<bool, anyhow::Error>;
+    async fn revoke_all(&self, user_id: Uuid) -> Result<u64, anyhow::Error>;
+    async fn deny_token(&self, jti: Uuid, expires_at: DateTime<Utc>) -> Result<(), anyhow::Error>;
+    /// Whether the session is gone or revoked, or the token itself is denied.
+    async fn is_revoked(&self, session_id: Uuid, jti: Uuid) -> Result<bool, anyhow::Error>;
+}
+
+#[async_trait]
+pub trait IdentityRepository: Send + Sync {
+    async fn save_login(&self, login: &OidcLogin) -> Result<(), anyhow::Error>;
+    /// Consumes the login so its `state` can't be replayed; older than `not_before` counts as gone.
+    async fn take_login(&self, state: &str, not_before: DateTime<Utc>) -> Result<Option<OidcLogin>, anyhow::Error>;
+    async fn find_user(&self, issuer: &str, subject: &str) -> Result<Option<User>, anyhow::Error>;
+    /// Links the provider account to the user, or records a new login on an existing link.
+    async fn link(&self, issuer: &str, subject: &str, user_id: Uuid) -> Result<bool, anyhow::Error>;
+}
+
+/// Queries and changes across all namespaces, for super admins.
+#[async_trait]
+pub trait AdminRepository: Send + Sync {
+    /// One more than the filter's limit is asked for to tell whether there's a next page.
+    async fn find_users(&self, filter: &UserFilter, limit: i64) -> Result<Vec<UserSummary>, anyhow::Error>;
+    async fn set_disabled(&self, actor: Uuid, user_id: Uuid, disabled: bool) -> Result<UserSummary, anyhow::Error>;
+    /// Refuses to demote the last super admin.
+    async fn set_super_admin(&self, actor: Uuid, user_id: Uuid, super_admin: bool) -> Result<UserSummary, anyhow::Error>;
+    async fn namespace_stats(&self, filter: &NamespaceStatsFilter) -> Result<Vec<NamespaceStats>, anyhow::Error>;
+}
+
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    /// Replaces an open invitation for the same email.
+    async fn create(&self, actor: Uuid, ns_id: Uuid, i: &NewInvitationRecord) -> Result<Invitation, anyhow::Error>;
+    async fn find_pending(&self, ns_id: Uuid) -> Result<Vec<Invitation>, anyhow::Error>;
+    async fn find_with_hash(&self, id: Uuid) -> Result<Option<(Invitation, String)>, anyhow::Error>;
+    async fn revoke(&self, actor: Uuid, ns_id: Uuid, id: Uuid) -> Result<Invitation, anyhow::Error>;
+    /// Makes the invitee a member with the invited role, unless they already are one.
+    /// A new invitee's account is created in the same transaction.
+    async fn accept(&self, id: Uuid, invitee: &Invitee) -> Result<Invitation, anyhow::Error>;
+}
+
+#[async_trait]
+pub trait RunRepository: Send + Sync {
+    /// Stores a pending run with its `run.started` event.
+    async fn create(&self, r: &NewRun) -> Result<Run, anyhow::Error>;
+    async fn find(&self, ns_id: Uuid, run_id: Uuid) -> Result<Option<Run>, anyhow::Error>;
+    /// The namespace a run belongs to, for callers that aren't scoped to one.
+    async fn namespace_of(&self, run_id: Uuid) -> Result<Option<Uuid>, anyhow::Error>;
+    /// Newest first.
+    async fn list(&self, ns_id: Uuid, filter: &RunFilter, search: &SearchQuery, limit: i64) -> Result<Vec<RunSummary>, anyhow::Error>;
+    async fn events(&self, run_id: Uuid) -> Result<Vec<RunEvent>, anyhow::Error>;
+    /// Takes the lease of the oldest run no executor holds, marking it running.
+    async fn claim(&self, lease_secs: f64) -> Result<Option<ClaimedRun>, anyhow::Error>;
+    /// Appends to the history and renews the lease, returning the seqs the events got;
+    /// `None` once the run is no longer running under the claim.
+    async fn append_events(&self, claim: RunClaim, events: &[NewRunEvent], lease_secs: f64) -> Result<Option<Vec<i32>>, anyhow::Error>;
+    /// Merges normalized attributes into those of a running run, `null` values remove one;
+    /// false if the run is no longer running under the claim.
+    async fn upsert_search_attributes(&self, claim: RunClaim, attributes: &Map<String, JsonValue>, lease_secs: f64) -> Result<bool, anyhow::Error>;
+    /// Records the request on the run and its active descendants; their workflows are told on their next decision.
+    async fn request_cancel(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, reason: Option<&str>) -> Result<StoppedRun, anyhow::Error>;
+    /// Ends the run and its active descendants right away, whatever their workflows would do.
+    async fn terminate(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, reason: Option<&str>) -> Result<StoppedRun, anyhow::Error>;
+    /// Renews the lease while the run is still running under the claim, `None` once it isn't.
+    async fn renew(&self, claim: RunClaim, lease_secs: f64) -> Result<Option<RunStatus>, anyhow::Error>;
+    /// Starts the next attempt at a scheduled activity, the first one if none was made yet;
+    /// `None` once the run is no longer running under the claim.
+    async fn start_attempt(&self, claim: RunClaim, scheduled: i64) -> Result<Option<ActivityAttempt>, anyhow::Error>;
+    /// Stores the heartbeat of the current attempt and renews the lease; false once the run is no longer running.
+    async fn heartbeat(&self, claim: RunClaim, scheduled: i64, details: &str, lease_secs: f64) -> Result<bool, anyhow::Error>;
+    /// Ends a running run with its final event; false if it had already ended or the claim was lost.
+    async fn finish(&self, claim: RunClaim, state: RunState, result: Option<&JsonValue>, failure: Option<&JsonValue>, event: &NewRunEvent) -> Result<bool, anyhow::Error>;
+}
+
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Records the key as in flight until `locked_until` unless it is taken, telling what was there.
+    /// Expired keys and in-flight ones whose lock ran out are taken over.
+    async fn claim(&self, user_id: Uuid, key: &str, request_hash: &str, locked_until: DateTime<Utc>, expires_at: DateTime<Utc>) -> Result<IdempotencyClaim, anyhow::Error>;
+    async fn complete(&self, user_id: Uuid, key: &str, response: &StoredResponse) -> Result<(), anyhow::Error>;
+    /// Frees the key, so the request can be tried again with it.
+    async fn release(&self, user_id: Uuid, key: &str) -> Result<(), anyhow::Error>;
+}
+
+#[async_trait]
+pub trait SearchAttributeRepository: Send + Sync {
+    async fn find_all(&self, ns_id: Uuid) -> Result<Vec<SearchAttributeDefinition>, anyhow::Error>;
+    /// Refuses names already defined and more than [`crate::core::domain::search::MAX_DEFINITIONS`].
+    async fn define(&self, actor: Uuid, ns_id: Uuid, a: &NewSearchAttribute) -> Result<SearchAttributeDefinition, anyhow::Error>;
+    /// Values runs already hold stay, they can no longer be set or searched by.
+    async fn remove(&self, actor: Uuid, ns_id: Uuid, name: &str) -> Result<(), anyhow::Error>;
+}
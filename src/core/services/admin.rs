@@ -0,0 +1,170 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::bail;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::core::ports::storage::{AdminRepository, NamespaceRepository, RunRepository, SessionRepository};
+use crate::core::domain::admin::{
+    AdminError, BootstrapOutcome, NamespaceStats, NamespaceStatsFilter, UnloadRequest, UserFilter, UserPage, UserSummary,
+};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::cluster::ClusterEvent;
+use crate::core::domain::namespace::NamespaceError;
+use crate::core::domain::run::StoppedRun;
+use crate::core::domain::user::NewUser;
+use super::audit::AuditService;
+use super::cluster::ClusterService;
+use super::user::UserService;
+
+/// Operations across all users and namespaces; callers must be super admins.
+pub struct AdminService {
+    repo: Arc<dyn AdminRepository>,
+    sessions: Arc<dyn SessionRepository>,
+    namespaces: Arc<dyn NamespaceRepository>,
+    runs: Arc<dyn RunRepository>,
+    cluster: Arc<ClusterService>,
+    users: Arc<UserService>,
+    audit: Arc<AuditService>,
+}
+
+impl AdminService {
+    #[cold]
+    pub fn new(
+        repo: Arc<dyn AdminRepository>,
+        sessions: Arc<dyn SessionRepository>,
+        namespaces: Arc<dyn NamespaceRepository>,
+        runs: Arc<dyn RunRepository>,
+        cluster: Arc<ClusterService>,
+        users: Arc<UserService>,
+        audit: Arc<AuditService>,
+    ) -> Self {
+        AdminService {
+            repo,
+            sessions,
+            namespaces,
+            runs,
+            cluster,
+            users,
+            audit,
+        }
+    }
+
+    pub async fn users(&self, filter: &UserFilter) -> Result<UserPage, anyhow::Error> {
+        let limit = filter.limit();
+        let mut users = self.repo.find_users(filter, limit + 1).await?;
+
+        let next_after = if users.len() as i64 > limit {
+            users.truncate(limit as usize);
+            users.last().map(|u| u.id)
+        } else {
+            None
+        };
+
+        Ok(UserPage { users, next_after })
+    }
+
+    /// Disabling also ends every session of the user, so their access tokens stop working.
+    pub async fn set_disabled(&self, actor: Uuid, user_id: Uuid, disabled: bool) -> Result<UserSummary, anyhow::Error> {
+        if disabled && actor == user_id {
+            bail!(AdminError::NotAllowed("You can not disable yourself".to_string()));
+        }
+
+        let user = self.repo.set_disabled(actor, user_id, disabled).await?;
+
+        if disabled {
+            self.sessions.revoke_all(user_id).await?;
+        }
+
+        Ok(user)
+    }
+
+    /// Demotion ends the user's sessions, access tokens carry the flag until they expire otherwise.
+    pub async fn set_super_admin(&self, actor: Uuid, user_id: Uuid, super_admin: bool) -> Result<UserSummary, anyhow::Error> {
+        let user = self.repo.set_super_admin(actor, user_id, super_admin).await?;
+
+        if !super_admin {
+            self.sessions.revoke_all(user_id).await?;
+        }
+
+        Ok(user)
+    }
+
+    pub async fn namespaces(&self, filter: &NamespaceStatsFilter) -> Result<Vec<NamespaceStats>, anyhow::Error> {
+        self.repo.namespace_stats(filter).await
+    }
+
+    /// Terminates a run in whatever namespace, along with its active children.
+    pub async fn cancel_run(&self, actor: Uuid, run_id: Uuid, reason: Option<&str>) -> Result<StoppedRun, anyhow::Error> {
+        let ns_id = self.runs.namespace_of(run_id).await?
+            .ok_or(AdminError::RunNotFound)?;
+
+        self.runs.terminate(actor, ns_id, run_id, reason).await
+    }
+
+    /// Asks every node to unload the plugins, this one included.
+    pub async fn unload_plugins(&self, actor: Uuid, req: &UnloadRequest) -> Result<(), anyhow::Error> {
+        if req.version.is_some() && req.key.is_none() {
+            bail!(AdminError::NotAllowed("A version can only be unloaded together with its workflow key".to_string()));
+        }
+
+        let ns = self.namespaces.find_by_slug(&req.namespace).await?
+            .ok_or(NamespaceError::NotFound)?
+            .namespace;
+
+        self.cluster.publish(&ClusterEvent::UnloadPlugins {
+            namespace_id: ns.id,
+            key: req.key.clone(),
+            version: req.version.clone(),
+        }).await?;
+
+        self.audit.record(NewAuditEntry::new("plugin.unload")
+            .namespace(ns.id)
+            .actor(actor)
+            .object("namespace", ns.id)
+            .meta(json!({ "key": req.key, "version": req.version }))
+        ).await;
+
+        Ok(())
+    }
+
+    /// Makes `email` a super admin, creating the account if needed; `only_if_none` leaves
+    /// everything as it is once any active super admin exists, so it is safe to run on every start.
+    pub async fn bootstrap_super_admin(&self, email: &str, only_if_none: bool) -> Result<BootstrapOutcome, anyhow::Error> {
+        if only_if_none {
+            let active = UserFilter { super_admin: Some(true), disabled: Some(false), ..Default::default() };
+            if !self.repo.find_users(&active, 1).await?.is_empty() {
+                return Ok(BootstrapOutcome::AlreadyPresent);
+            }
+        }
+
+        match self.users.find_by_email(email).await? {
+            Some(user) => {
+                if user.is_disabled() {
+                    self.repo.set_disabled(user.id, user.id, false).await?;
+                }
+                let summary = self.repo.set_super_admin(user.id, user.id, true).await?;
+
+                Ok(BootstrapOutcome::Promoted(summary))
+            }
+            None => {
+                let user = self.users.create(&NewUser { email: email.to_string() }).await?;
+                let summary = self.repo.set_super_admin(user.id, user.id, true).await?;
+
+                Ok(BootstrapOutcome::Created { user: summary, token: user.token })
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::bail;
+use chrono::Utc;
+use rand::{self, Rng};
+use rand::distr::Alphanumeric;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::ports::storage::ApiKeyRepository;
+use crate::core::domain::api_key::{ApiKey, ApiKeyCredentials, ApiKeyError, CreatedApiKey, NewApiKey, NewApiKeyRecord, API_KEY_PREFIX};
+use crate::core::domain::namespace::NamespaceRole;
+
+const PREFIX_LEN: usize = 12;
+const SECRET_LEN: usize = 48;
+
+/// Keys look like `xk_<prefix>_<secret>`; the prefix finds the key, only a hash of the secret is stored.
+pub struct ApiKeyService {
+    repo: Arc<dyn ApiKeyRepository>,
+}
+
+impl ApiKeyService {
+    #[cold]
+    pub fn new(repo: Arc<dyn ApiKeyRepository>) -> Self {
+        ApiKeyService {
+            repo,
+        }
+    }
+
+    pub async fn create(&self, actor: Uuid, actor_role: NamespaceRole, ns_id: Uuid, k: &NewApiKey) -> Result<CreatedApiKey, anyhow::Error> {
+        if k.role == NamespaceRole::Owner || !actor_role.can_manage(k.role) {
+            bail!(ApiKeyError::NotAllowed(format!("You can not create API keys as {:?}", k.role)));
+        }
+
+        if k.expires_at.is_some_and(|exp| exp <= Utc::now()) {
+            bail!(ApiKeyError::NotAllowed("Expiry must be in the future".to_string()));
+        }
+
+        let prefix = random_string(PREFIX_LEN);
+        let secret = random_string(SECRET_LEN);
+
+        let record = NewApiKeyRecord {
+            name: k.name.clone(),
+            role: k.role,
+            expires_at: k.expires_at,
+            prefix: prefix.clone(),
+            secret_hash: hash_secret(&secret),
+            scopes: k.scopes.as_ref().map(|scopes| scopes.iter().map(|s| s.as_str().to_string()).collect()),
+        };
+
+        let key = self.repo.create(actor, ns_id, &record).await?;
+
+        Ok(CreatedApiKey {
+            key,
+            token: format!("{}{}_{}", API_KEY_PREFIX, prefix, secret),
+        })
+    }
+
+    pub async fn find_all(&self, ns_id: Uuid) -> Result<Vec<ApiKey>, anyhow::Error> {
+        self.repo.find_all(ns_id).await
+    }
+
+    pub async fn revoke(&self, actor: Uuid, ns_id: Uuid, key_id: Uuid) -> Result<ApiKey, anyhow::Error> {
+        self.repo.revoke(actor, ns_id, key_id).await
+    }
+
+    /// Returns the key a bearer token belongs to, if it is valid, unexpired and not revoked.
+    pub async fn authenticate(&self, token: &str) -> Result<Option<ApiKeyCredentials>, anyhow::Error> {
+        let Some((prefix, secret)) = token.strip_prefix(API_KEY_PREFIX).and_then(|t| t.split_once('_')) else {
+            return Ok(None);
+        };
+
+        let Some(credentials) = self.repo.find_credentials(prefix).await? else {
+            return Ok(None);
+        };
+
+        let matches: bool = hash_secret(secret).as_bytes().ct_eq(credentials.secret_hash.as_bytes()).into();
+        if !matches || !credentials.is_usable(Utc::now()) {
+            return Ok(None);
+        }
+
+        if let Err(e) = self.repo.touch(credentials.id).await {
+            warn!("Failed to record use of API key '{}': {}", prefix, e);
+        }
+
+        Ok(Some(credentials))
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
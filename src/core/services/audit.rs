@@ -0,0 +1,278 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Context;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::core::ports::audit::AuditLog;
+use crate::core::domain::audit::{
+    AuditCheckpoint, AuditFilter, AuditPage, ChainBreak, ChainVerification, CheckpointExport, NewAuditEntry, GENESIS_HASH,
+};
+
+/// Entries fetched per round trip while walking a chain.
+const VERIFY_BATCH: i64 = 1000;
+
+/// Signs chain heads with an ed25519 key, so exported checkpoints can be checked with the public key alone.
+pub struct CheckpointSigner {
+    key: SigningKey,
+    key_id: String,
+}
+
+impl CheckpointSigner {
+    /// `seed` is the hex encoded 32 byte secret key.
+    pub fn from_hex(seed: &str) -> Result<Self, anyhow::Error> {
+        let bytes: [u8; 32] = hex::decode(seed.trim())
+            .context("Audit signing key is not valid hex")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Audit signing key must be 32 bytes"))?;
+
+        let key = SigningKey::from_bytes(&bytes);
+        let key_id = key_id(&key.verifying_key());
+
+        Ok(CheckpointSigner { key, key_id })
+    }
+
+    pub fn public_key(&self) -> String {
+        hex::encode(self.key.verifying_key().as_bytes())
+    }
+}
+
+/// Parses the comma separated, hex encoded public keys checkpoints were signed with before
+/// the current key, so those checkpoints still verify after a rotation.
+pub fn parse_retired_keys(keys: Option<&str>) -> Result<Vec<VerifyingKey>, anyhow::Error> {
+    keys.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| {
+            let bytes: [u8; 32] = hex::decode(key)
+                .with_context(|| format!("Retired audit key '{}' is not valid hex", key))?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Retired audit key '{}' must be 32 bytes", key))?;
+
+            VerifyingKey::from_bytes(&bytes).with_context(|| format!("Retired audit key '{}' is not an ed25519 public key", key))
+        })
+        .collect()
+}
+
+/// The id checkpoints name their key by: the first 8 bytes of the public key, hex encoded.
+fn key_id(key: &VerifyingKey) -> String {
+    hex::encode(&key.as_bytes()[..8])
+}
+
+fn verify_checkpoint(key: &VerifyingKey, checkpoint: &AuditCheckpoint) -> bool {
+    let Ok(signature) = Signature::from_slice(&checkpoint.signature) else {
+        return false;
+    };
+
+    let message = AuditCheckpoint::signed_message(checkpoint.chain_id, checkpoint.seq, &checkpoint.hash);
+    key.verify(&message, &signature).is_ok()
+}
+
+pub struct AuditService {
+    log: Arc<dyn AuditLog>,
+    signer: Option<CheckpointSigner>,
+    /// Keys checkpoints are verified with by key id, the signer's and the retired ones.
+    verifying_keys: HashMap<String, VerifyingKey>,
+}
+
+impl AuditService {
+    #[cold]
+    pub fn new(log: Arc<dyn AuditLog>, signer: Option<CheckpointSigner>, retired_keys: Vec<VerifyingKey>) -> Self {
+        let verifying_keys = retired_keys.into_iter()
+            .chain(signer.as_ref().map(|s| s.key.verifying_key()))
+            .map(|key| (key_id(&key), key))
+            .collect();
+
+        AuditService {
+            log,
+            signer,
+            verifying_keys,
+        }
+    }
+
+    /// Records a change that is already committed.
+    ///
+    /// A failure here can no longer undo the change, so it is logged instead of returned.
+    pub async fn record(&self, entry: NewAuditEntry) {
+        if let Err(e) = self.log.record(&entry).await {
+            error!("❌ Failed to record audit entry '{}': {}", entry.action, e);
+        }
+    }
+
+    pub async fn query(&self, filter: &AuditFilter) -> Result<AuditPage, anyhow::Error> {
+        let limit = filter.limit();
+
+        // one extra row tells whether there is a next page
+        let mut entries = self.log.query(filter, limit + 1).await?;
+
+        let next_before = if entries.len() as i64 > limit {
+            entries.truncate(limit as usize);
+            entries.last().map(|e| e.id)
+        } else {
+            None
+        };
+
+        Ok(AuditPage { entries, next_before })
+    }
+
+    /// Walks a chain from its first entry and reports the first entry that is missing,
+    /// out of place or altered, and whether the chain still reaches its latest checkpoint.
+    ///
+    /// A checkpoint signed with a key this server doesn't know is reported as unverified
+    /// and the chain is not checked against it.
+    pub async fn verify(&self, chain_id: Uuid) -> Result<ChainVerification, anyhow::Error> {
+        let checkpoint = self.log.latest_checkpoint(chain_id).await?;
+
+        let mut result = ChainVerification {
+            chain_id,
+            checked: 0,
+            head_seq: 0,
+            checkpoint_seq: checkpoint.as_ref().map(|c| c.seq),
+            checkpoint_verified: false,
+            first_break: None,
+        };
+
+        let checkpoint = match checkpoint {
+            Some(cp) => match self.verifying_keys.get(&cp.key_id) {
+                Some(key) if !verify_checkpoint(key, &cp) => {
+                    result.first_break = Some(ChainBreak {
+                        seq: cp.seq,
+                        entry_id: None,
+                        reason: format!("Checkpoint {} has an invalid signature", cp.id),
+                    });
+                    return Ok(result);
+                }
+                Some(_) => {
+                    result.checkpoint_verified = true;
+                    Some(cp)
+                }
+                None => {
+                    warn!("⚠️  Checkpoint {} of chain {} is signed with unknown key {}, not checking against it", cp.id, chain_id, cp.key_id);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut prev_hash = GENESIS_HASH.to_vec();
+
+        'walk: loop {
+            let batch = self.log.chain_entries(chain_id, result.head_seq, VERIFY_BATCH).await?;
+
+            for entry in &batch {
+                let expected = result.head_seq + 1;
+
+                let reason = if entry.seq != expected {
+                    Some(format!("Entries {} to {} are missing", expected, entry.seq - 1))
+                } else if entry.prev_hash != prev_hash {
+                    Some("Entry does not link to the one before it".to_string())
+                } else if entry.compute_hash(&prev_hash) != entry.hash {
+                    Some("Entry content does not match its hash".to_string())
+                } else if checkpoint.as_ref().is_some_and(|cp| cp.seq == entry.seq && cp.hash != entry.hash) {
+                    Some("Entry differs from the signed checkpoint".to_string())
+                } else {
+                    None
+                };
+
+                if let Some(reason) = reason {
+                    result.first_break = Some(ChainBreak { seq: expected, entry_id: Some(entry.id), reason });
+                    break 'walk;
+                }
+
+                prev_hash = entry.hash.clone();
+                result.head_seq = entry.seq;
+                result.checked += 1;
+            }
+
+            if (batch.len() as i64) < VERIFY_BATCH {
+                break;
+            }
+        }
+
+        // a chain cut off at its end links up fine, only the checkpoint tells
+        if let Some(cp) = &checkpoint
+            && result.first_break.is_none() && result.head_seq < cp.seq
+        {
+            result.first_break = Some(ChainBreak {
+                seq: result.head_seq + 1,
+                entry_id: None,
+                reason: format!("Entries {} to {} covered by checkpoint {} are missing", result.head_seq + 1, cp.seq, cp.id),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Signs the head of every chain that moved since its last checkpoint.
+    pub async fn checkpoint(&self) -> Result<usize, anyhow::Error> {
+        let Some(signer) = &self.signer else {
+            return Ok(0);
+        };
+
+        let mut created = 0;
+
+        for head in self.log.chain_heads().await? {
+            let latest = self.log.latest_checkpoint(head.chain_id).await?;
+            if latest.is_some_and(|cp| cp.seq >= head.seq) {
+                continue;
+            }
+
+            let message = AuditCheckpoint::signed_message(head.chain_id, head.seq, &head.hash);
+            let signature = signer.key.sign(&message);
+
+            self.log.save_checkpoint(&head, &signer.key_id, &signature.to_bytes()).await?;
+            created += 1;
+        }
+
+        Ok(created)
+    }
+
+    pub async fn run_checkpoints(&self, interval: std::time::Duration, mut shutdown_rx: oneshot::Receiver<()>) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    match self.checkpoint().await {
+                        Ok(created) => info!("🔏 Signed {} audit checkpoint(s)", created),
+                        Err(e) => error!("❌ Audit checkpointing failed: {}", e),
+                    }
+                }
+                _ = &mut shutdown_rx => {
+                    info!("🛑 Received shutdown signal, stopping audit checkpoints");
+                    break;
+                }
+            }
+        }
+    }
+
+    pub async fn export_checkpoints(&self, chain_id: Option<Uuid>) -> Result<CheckpointExport, anyhow::Error> {
+        let checkpoints = self.log.checkpoints(chain_id).await?;
+
+        Ok(CheckpointExport {
+            key_id: self.signer.as_ref().map(|s| s.key_id.clone()),
+            public_key: self.signer.as_ref().map(|s| s.public_key()),
+            retired_public_keys: self.verifying_keys.values()
+                .filter(|key| self.signer.as_ref().is_none_or(|s| s.key.verifying_key() != **key))
+                .map(|key| hex::encode(key.as_bytes()))
+                .collect(),
+            checkpoints,
+        })
+    }
+}
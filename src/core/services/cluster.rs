@@ -0,0 +1,80 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{error, info};
+
+use crate::core::ports::cluster::ClusterBus;
+use crate::core::domain::cluster::ClusterEvent;
+use crate::adapters::wasmtime::wit_runtime::{PluginKey, WitPluginRuntime};
+
+/// Wait before listening again after the bus failed.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Applies cluster events to this node's runtime, including the ones it published itself.
+pub struct ClusterService {
+    bus: Arc<dyn ClusterBus>,
+    wit_runtime: Arc<WitPluginRuntime>,
+}
+
+impl ClusterService {
+    #[cold]
+    pub fn new(bus: Arc<dyn ClusterBus>, wit_runtime: Arc<WitPluginRuntime>) -> Self {
+        ClusterService {
+            bus,
+            wit_runtime,
+        }
+    }
+
+    pub async fn publish(&self, event: &ClusterEvent) -> Result<(), anyhow::Error> {
+        self.bus.publish(event).await
+    }
+
+    pub async fn run(&self, mut shutdown_rx: oneshot::Receiver<()>) {
+        loop {
+            tokio::select! {
+                event = self.bus.recv() => {
+                    match event {
+                        Ok(event) => self.apply(event).await,
+                        Err(e) => {
+                            error!("❌ Cluster event bus failed, retrying in {:?}: {}", RETRY_DELAY, e);
+                            tokio::time::sleep(RETRY_DELAY).await;
+                        }
+                    }
+                }
+                _ = &mut shutdown_rx => {
+                    info!("🛑 Received shutdown signal, stopping cluster event listener");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn apply(&self, event: ClusterEvent) {
+        match event {
+            ClusterEvent::UnloadPlugins { namespace_id, key: Some(key), version: Some(version) } => {
+                let plugin = PluginKey::new(namespace_id, key, version);
+                let removed = self.wit_runtime.remove_plugin(&plugin).await.is_ok();
+                info!("Cluster unload of {}: {}", plugin, if removed { "removed" } else { "not loaded" });
+            }
+            ClusterEvent::UnloadPlugins { namespace_id, key: Some(key), version: None } => {
+                self.wit_runtime.remove_workflow(namespace_id, &key).await;
+            }
+            ClusterEvent::UnloadPlugins { namespace_id, key: None, .. } => {
+                self.wit_runtime.remove_namespace(namespace_id).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+//! Stored user credentials.
+//!
+//! New hashes are argon2id PHC strings (`$argon2id$v=19$...`), which carry their own
+//! algorithm and parameters. Rows written before that hold the legacy `salt:hash`
+//! format, a single SHA-256 round over token and salt; they are still accepted and
+//! reported as needing a rehash so the caller can upgrade them after a good sign-in.
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Outcome of checking a token against its stored hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    Invalid,
+    Valid,
+    /// Correct token, but stored in an outdated format or with outdated parameters.
+    ValidNeedsRehash,
+}
+
+impl Verification {
+    pub fn is_valid(self) -> bool {
+        self != Verification::Invalid
+    }
+}
+
+fn argon2() -> Argon2<'static> {
+    // argon2id v19 with the OWASP recommended parameters
+    Argon2::default()
+}
+
+/// Hashes a token into the current storage format.
+pub fn hash(token: &str) -> Result<String, anyhow::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let hash = argon2().hash_password(token.as_bytes(), &salt)
+        .map_err(|e| anyhow::anyhow!("Failed to hash credential: {}", e))?;
+
+    Ok(hash.to_string())
+}
+
+pub fn verify(token: &str, stored: &str) -> Verification {
+    if stored.starts_with('$') {
+        return verify_argon2(token, stored);
+    }
+
+    verify_legacy(token, stored)
+}
+
+/// Burns the same time as a real verification, for sign-ins of unknown emails.
+pub fn verify_dummy(token: &str) {
+    static DUMMY: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| {
+        hash("dummy credential").unwrap_or_default()
+    });
+
+    let _ = verify_argon2(token, &DUMMY);
+}
+
+fn verify_argon2(token: &str, stored: &str) -> Verification {
+    let Ok(parsed) = PasswordHash::new(stored) else {
+        return Verification::Invalid;
+    };
+
+    if argon2().verify_password(token.as_bytes(), &parsed).is_err() {
+        return Verification::Invalid;
+    }
+
+    let current = argon2();
+    let outdated = parsed.algorithm != argon2::Algorithm::Argon2id.ident()
+        || parsed.version != Some(argon2::Version::V0x13.into())
+        || argon2::Params::try_from(&parsed).map_or(true, |p| &p != current.params());
+
+    if outdated { Verification::ValidNeedsRehash } else { Verification::Valid }
+}
+
+fn verify_legacy(token: &str, stored: &str) -> Verification {
+    let Some((salt, hash)) = stored.split_once(':') else {
+        return Verification::Invalid;
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hasher.update(salt.as_bytes());
+    let computed = format!("{:x}", hasher.finalize());
+
+    if computed.as_bytes().ct_eq(hash.as_bytes()).into() {
+        Verification::ValidNeedsRehash
+    } else {
+        Verification::Invalid
+    }
+}
@@ -0,0 +1,69 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::bail;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::core::ports::storage::IdempotencyRepository;
+use crate::core::domain::idempotency::{IdempotencyClaim, IdempotencyError, StoredResponse, MAX_KEY_LEN};
+
+/// How long a key stays in flight before a retry may take it over, in case the
+/// request holding it died without freeing it.
+const IN_FLIGHT_LOCK_SECS: i64 = 120;
+
+/// Remembers the response to each `Idempotency-Key` a caller sends, so a retried request
+/// gets it again instead of doing its work twice.
+pub struct IdempotencyService {
+    repo: Arc<dyn IdempotencyRepository>,
+    ttl: Duration,
+}
+
+impl IdempotencyService {
+    #[cold]
+    pub fn new(repo: Arc<dyn IdempotencyRepository>, ttl: Duration) -> Self {
+        IdempotencyService {
+            repo,
+            ttl,
+        }
+    }
+
+    /// Takes the key for a request, returning the stored response when it was handled before.
+    pub async fn begin(&self, user_id: Uuid, key: &str, request_hash: &str) -> Result<Option<StoredResponse>, anyhow::Error> {
+        if key.is_empty() || key.len() > MAX_KEY_LEN {
+            bail!(IdempotencyError::InvalidKey(format!("it must be 1 to {} characters long", MAX_KEY_LEN)));
+        }
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            bail!(IdempotencyError::InvalidKey("it can only hold visible ASCII characters".to_string()));
+        }
+
+        let now = Utc::now();
+        match self.repo.claim(user_id, key, request_hash, now + Duration::seconds(IN_FLIGHT_LOCK_SECS), now + self.ttl).await? {
+            IdempotencyClaim::New => Ok(None),
+            IdempotencyClaim::Existing { request_hash: stored, .. } if stored != request_hash => bail!(IdempotencyError::Mismatch),
+            IdempotencyClaim::Existing { response: None, .. } => bail!(IdempotencyError::InFlight),
+            IdempotencyClaim::Existing { response: Some(response), .. } if response.withheld => bail!(IdempotencyError::Withheld),
+            IdempotencyClaim::Existing { response: Some(response), .. } => Ok(Some(response)),
+        }
+    }
+
+    pub async fn complete(&self, user_id: Uuid, key: &str, response: &StoredResponse) -> Result<(), anyhow::Error> {
+        self.repo.complete(user_id, key, response).await
+    }
+
+    /// Gives the key up after a request that should be retried, like one that hit a server error.
+    pub async fn release(&self, user_id: Uuid, key: &str) -> Result<(), anyhow::Error> {
+        self.repo.release(user_id, key).await
+    }
+}
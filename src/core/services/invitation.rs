@@ -0,0 +1,127 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::bail;
+use chrono::{Duration, Utc};
+use rand::{self, Rng};
+use rand::distr::Alphanumeric;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::core::ports::storage::{InvitationRepository, NamespaceRepository};
+use crate::core::domain::invitation::{
+    AcceptedInvitation, CreatedInvitation, Invitation, InvitationError, Invitee, NewInvitation, NewInvitationRecord,
+};
+use crate::core::domain::namespace::{NamespaceError, NamespaceRole};
+use super::user::{new_credential, UserService};
+
+const INVITATION_SECRET_LEN: usize = 40;
+
+/// Namespace invitations; tokens look like `<invitation id>.<secret>`, only a hash of the secret is stored.
+pub struct InvitationService {
+    repo: Arc<dyn InvitationRepository>,
+    namespaces: Arc<dyn NamespaceRepository>,
+    users: Arc<UserService>,
+    ttl: Duration,
+}
+
+impl InvitationService {
+    #[cold]
+    pub fn new(repo: Arc<dyn InvitationRepository>, namespaces: Arc<dyn NamespaceRepository>, users: Arc<UserService>, ttl: Duration) -> Self {
+        InvitationService {
+            repo,
+            namespaces,
+            users,
+            ttl,
+        }
+    }
+
+    pub async fn create(&self, actor: Uuid, actor_role: NamespaceRole, ns_id: Uuid, i: &NewInvitation) -> Result<CreatedInvitation, anyhow::Error> {
+        if i.role == NamespaceRole::Owner || !actor_role.can_manage(i.role) {
+            bail!(InvitationError::NotAllowed(format!("You can not invite members as {:?}", i.role)));
+        }
+
+        let id = Uuid::now_v7();
+        let secret: String = rand::rng()
+            .sample_iter(&Alphanumeric)
+            .take(INVITATION_SECRET_LEN)
+            .map(char::from)
+            .collect();
+
+        let invitation = self.repo.create(actor, ns_id, &NewInvitationRecord {
+            id,
+            email: i.email.to_lowercase(),
+            role: i.role,
+            token_hash: hash_secret(&secret),
+            expires_at: Utc::now() + self.ttl,
+        }).await?;
+
+        Ok(CreatedInvitation {
+            invitation,
+            token: format!("{}.{}", id, secret),
+        })
+    }
+
+    pub async fn find_pending(&self, ns_id: Uuid) -> Result<Vec<Invitation>, anyhow::Error> {
+        self.repo.find_pending(ns_id).await
+    }
+
+    pub async fn revoke(&self, actor: Uuid, ns_id: Uuid, id: Uuid) -> Result<Invitation, anyhow::Error> {
+        self.repo.revoke(actor, ns_id, id).await
+    }
+
+    /// Joins the invited email to the namespace, creating its account first if there is none.
+    pub async fn accept(&self, token: &str) -> Result<AcceptedInvitation, anyhow::Error> {
+        let Some((id, secret)) = token.split_once('.') else {
+            bail!(InvitationError::Invalid);
+        };
+        let id = Uuid::parse_str(id).map_err(|_| InvitationError::Invalid)?;
+
+        let (invitation, token_hash) = self.repo.find_with_hash(id).await?
+            .ok_or(InvitationError::Invalid)?;
+
+        let matches: bool = hash_secret(secret).as_bytes().ct_eq(token_hash.as_bytes()).into();
+        if !matches || !invitation.is_pending(Utc::now()) {
+            bail!(InvitationError::Invalid);
+        }
+
+        let (invitee, email, password) = match self.users.find_by_email(&invitation.email).await? {
+            Some(user) => (Invitee::Existing(user.id), user.email, None),
+            None => {
+                // the account is created with the accept, so a failed accept leaves none behind
+                let (password, token_hash) = new_credential().await?;
+                let invitee = Invitee::New { email: invitation.email.clone(), token_hash };
+                (invitee, invitation.email.clone(), Some(password))
+            }
+        };
+
+        let invitation = self.repo.accept(invitation.id, &invitee).await?;
+
+        let ns = self.namespaces.find_by_id(invitation.namespace_id).await?
+            .ok_or(NamespaceError::NotFound)?;
+
+        Ok(AcceptedInvitation {
+            namespace_id: ns.id,
+            slug: ns.slug,
+            role: invitation.role,
+            email,
+            password,
+        })
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
@@ -1,3 +1,16 @@
 pub mod workflow;
 pub mod user;
-pub mod namespace;
\ No newline at end of file
+pub mod namespace;
+pub mod reconciler;
+pub mod audit;
+pub mod api_key;
+pub mod session;
+pub mod credential;
+pub mod rate_limit;
+pub mod sso;
+pub mod cluster;
+pub mod admin;
+pub mod invitation;
+pub mod run;
+pub mod run_executor;
+pub mod idempotency;
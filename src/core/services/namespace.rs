@@ -12,32 +12,152 @@
  */
 
 use std::sync::Arc;
+use anyhow::bail;
+use serde_json::json;
+use tracing::warn;
 use uuid::Uuid;
 
-use crate::core::ports::storage::NamespaceRepository;
-use crate::core::domain::namespace::{Namespace, NamespaceRole, NewNamespace};
+use crate::core::ports::blob::BlobStore;
+use crate::core::ports::storage::{NamespaceRepository, UserRepository};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::namespace::{
+    DeletedNamespace, MemberView, MembershipError, Namespace, NamespaceError, NamespaceRole, NewMember, NewNamespace, ResolvedSlug,
+};
+use crate::adapters::wasmtime::wit_runtime::WitPluginRuntime;
+use super::audit::AuditService;
 
 pub struct NamespaceService{
     repo: Arc<dyn NamespaceRepository>,
+    users: Arc<dyn UserRepository>,
+    blobs: Arc<dyn BlobStore>,
+    wit_runtime: Arc<WitPluginRuntime>,
+    audit: Arc<AuditService>,
 }
 
 impl NamespaceService {
     #[cold]
-    pub fn new(r: Arc<dyn NamespaceRepository>) -> Self {
+    pub fn new(r: Arc<dyn NamespaceRepository>, users: Arc<dyn UserRepository>, blobs: Arc<dyn BlobStore>, wit_runtime: Arc<WitPluginRuntime>, audit: Arc<AuditService>) -> Self {
         NamespaceService{
             repo: r,
+            users,
+            blobs,
+            wit_runtime,
+            audit,
         }
     }
 
     pub async fn create(&self, uid: Uuid, ns: &NewNamespace) -> Result<Namespace, anyhow::Error> {
-        self.repo.create(uid, ns).await
+        let namespace = self.repo.create(uid, ns).await?;
+
+        self.audit.record(NewAuditEntry::new("namespace.create")
+            .namespace(namespace.id)
+            .actor(uid)
+            .object("namespace", namespace.id)
+            .meta(json!({ "slug": namespace.slug }))
+        ).await;
+
+        Ok(namespace)
     }
 
     pub async fn find_all(&self, uid: Uuid) -> Result<Vec<Namespace>, anyhow::Error> {
         self.repo.find_by_uid(uid).await
     }
 
+    pub async fn get(&self, ns_id: Uuid) -> Result<Namespace, anyhow::Error> {
+        let ns = self.repo.find_by_id(ns_id).await?
+            .ok_or(NamespaceError::NotFound)?;
+
+        Ok(ns)
+    }
+
+    /// Looks a namespace up by its current slug or by one it had before a rename.
+    pub async fn resolve_slug(&self, slug: &str) -> Result<ResolvedSlug, anyhow::Error> {
+        let resolved = self.repo.find_by_slug(slug).await?
+            .ok_or(NamespaceError::NotFound)?;
+
+        Ok(resolved)
+    }
+
+    /// Changes the slug, the old one keeps resolving to this namespace as an alias.
+    pub async fn rename(&self, actor: Uuid, ns_id: Uuid, slug: &str) -> Result<Namespace, anyhow::Error> {
+        self.repo.rename(actor, ns_id, slug).await
+    }
+
+    /// Archived namespaces keep their history readable but accept no new uploads or runs;
+    /// runs already going can still be cancelled or terminated.
+    pub async fn archive(&self, actor: Uuid, ns_id: Uuid) -> Result<Namespace, anyhow::Error> {
+        self.repo.set_archived(actor, ns_id, true).await
+    }
+
+    pub async fn unarchive(&self, actor: Uuid, ns_id: Uuid) -> Result<Namespace, anyhow::Error> {
+        self.repo.set_archived(actor, ns_id, false).await
+    }
+
+    /// Deletes the namespace with everything in it.
+    ///
+    /// Live runs are deleted with it, not cancelled: an executor still driving one finds it gone
+    /// at its next write or lease renewal and stops. Blobs and loaded components are cleaned up
+    /// afterwards; a blob that fails to go is left to the reconciler.
+    pub async fn delete(&self, actor: Uuid, ns_id: Uuid) -> Result<DeletedNamespace, anyhow::Error> {
+        let deleted = self.repo.delete(actor, ns_id).await?;
+
+        self.wit_runtime.remove_namespace(ns_id).await;
+
+        for url in &deleted.storage_urls {
+            if let Err(e) = self.blobs.delete(url).await {
+                warn!("Failed to remove blob '{}' of deleted namespace '{}', leaving it to the reconciler: {}", url, deleted.slug, e);
+            }
+        }
+
+        Ok(deleted)
+    }
+
     pub async fn ns_role_by_uid(&self, uid: Uuid, ns_id: Uuid) -> Result<Option<NamespaceRole>, anyhow::Error> {
         self.repo.role_by_uid(uid, ns_id).await
     }
+
+    pub async fn members(&self, ns_id: Uuid) -> Result<Vec<MemberView>, anyhow::Error> {
+        self.repo.members(ns_id).await
+    }
+
+    pub async fn add_member(&self, actor: Uuid, actor_role: NamespaceRole, ns_id: Uuid, m: &NewMember) -> Result<(), anyhow::Error> {
+        if m.role == NamespaceRole::Owner || !actor_role.can_manage(m.role) {
+            bail!(MembershipError::NotAllowed(format!("You can not add members as {:?}", m.role)));
+        }
+
+        let user = self.users.find_by_email(&m.email).await?
+            .ok_or_else(|| MembershipError::UserNotFound(m.email.clone()))?;
+
+        self.repo.add_member(actor, ns_id, user.id, m.role).await
+    }
+
+    pub async fn change_role(&self, actor: Uuid, actor_role: NamespaceRole, ns_id: Uuid, user_id: Uuid, role: NamespaceRole) -> Result<(), anyhow::Error> {
+        let current = self.repo.role_by_uid(user_id, ns_id).await?
+            .ok_or(MembershipError::NotMember)?;
+
+        if !actor_role.can_manage(current) || !actor_role.can_manage(role) {
+            bail!(MembershipError::NotAllowed(format!("You can not change a {:?} to {:?}", current, role)));
+        }
+
+        self.repo.set_member_role(actor, ns_id, user_id, role).await
+    }
+
+    pub async fn remove_member(&self, actor: Uuid, actor_role: NamespaceRole, ns_id: Uuid, user_id: Uuid) -> Result<(), anyhow::Error> {
+        let current = self.repo.role_by_uid(user_id, ns_id).await?
+            .ok_or(MembershipError::NotMember)?;
+
+        if !actor_role.can_manage(current) {
+            bail!(MembershipError::NotAllowed(format!("You can not remove a {:?}", current)));
+        }
+
+        self.repo.remove_member(actor, ns_id, user_id).await
+    }
+
+    pub async fn leave(&self, uid: Uuid, ns_id: Uuid) -> Result<(), anyhow::Error> {
+        self.repo.remove_member(uid, ns_id, uid).await
+    }
+
+    pub async fn transfer_ownership(&self, actor: Uuid, ns_id: Uuid, new_owner: Uuid) -> Result<(), anyhow::Error> {
+        self.repo.transfer_ownership(actor, ns_id, new_owner).await
+    }
 }
\ No newline at end of file
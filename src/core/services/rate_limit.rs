@@ -0,0 +1,92 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries are swept once the map grows past this, so a spray of emails can't grow it forever.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Subject {
+    Email(String),
+    Ip(IpAddr),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started: Instant,
+    failures: u32,
+}
+
+/// Counts failed sign-ins per email and per client IP in fixed windows.
+///
+/// Kept in memory: each node limits on its own, which is enough to make guessing
+/// tokens impractical without a shared store.
+pub struct SignInLimiter {
+    max_failures: u32,
+    window: Duration,
+    windows: Mutex<HashMap<Subject, Window>>,
+}
+
+impl SignInLimiter {
+    #[cold]
+    pub fn new(max_failures: u32, window: Duration) -> Self {
+        SignInLimiter {
+            max_failures,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long the caller has to wait before trying again, if they are locked out.
+    pub fn retry_after(&self, email: &str, ip: Option<IpAddr>) -> Option<Duration> {
+        let now = Instant::now();
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        subjects(email, ip)
+            .filter_map(|s| windows.get(&s))
+            .filter(|w| w.failures >= self.max_failures)
+            .filter_map(|w| (w.started + self.window).checked_duration_since(now))
+            .max()
+    }
+
+    pub fn record_failure(&self, email: &str, ip: Option<IpAddr>) {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if windows.len() > SWEEP_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < self.window);
+        }
+
+        for subject in subjects(email, ip) {
+            let w = windows.entry(subject).or_insert(Window { started: now, failures: 0 });
+            if now.duration_since(w.started) >= self.window {
+                *w = Window { started: now, failures: 0 };
+            }
+            w.failures += 1;
+        }
+    }
+
+    /// A good sign-in clears the email; the IP keeps its count, it may be guessing other accounts.
+    pub fn record_success(&self, email: &str) {
+        self.windows.lock().unwrap_or_else(|e| e.into_inner()).remove(&Subject::Email(email.to_lowercase()));
+    }
+}
+
+fn subjects(email: &str, ip: Option<IpAddr>) -> impl Iterator<Item = Subject> {
+    std::iter::once(Subject::Email(email.to_lowercase()))
+        .chain(ip.map(Subject::Ip))
+}
@@ -0,0 +1,116 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+
+use crate::core::ports::blob::BlobStore;
+use crate::core::ports::storage::WorkflowRepository;
+use super::workflow::WASM_BLOB_PREFIX;
+
+/// Removes blobs an interrupted workflow upload left behind, i.e. ones no version points to.
+///
+/// Versions whose blob is missing from the store are only reported: an upload stores the
+/// blob before committing the version, so a missing one means data changed outside of Xarxa,
+/// or a blob kept in another bucket, and committed rows are never deleted.
+pub struct BlobReconciler {
+    repo: Arc<dyn WorkflowRepository>,
+    blobs: Arc<dyn BlobStore>,
+    grace: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct ReconcileReport {
+    pub orphaned_blobs: usize,
+    pub missing_blobs: usize,
+}
+
+impl BlobReconciler {
+    #[cold]
+    pub fn new(repo: Arc<dyn WorkflowRepository>, blobs: Arc<dyn BlobStore>, grace: Duration) -> Self {
+        BlobReconciler {
+            repo,
+            blobs,
+            grace,
+        }
+    }
+
+    pub async fn run(&self, interval: std::time::Duration, mut shutdown_rx: oneshot::Receiver<()>) {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    match self.reconcile().await {
+                        Ok(report) => info!(
+                            "🧹 Reconciled blob store: {} orphaned blob(s) removed, {} version(s) missing their blob",
+                            report.orphaned_blobs, report.missing_blobs,
+                        ),
+                        Err(e) => error!("❌ Blob reconciliation failed: {}", e),
+                    }
+                }
+                _ = &mut shutdown_rx => {
+                    info!("🛑 Received shutdown signal, stopping reconciler");
+                    break;
+                }
+            }
+        }
+    }
+
+    pub async fn reconcile(&self) -> Result<ReconcileReport, anyhow::Error> {
+        // Anything younger than the grace period may belong to an upload that is still in flight.
+        let cutoff = Utc::now() - self.grace;
+        let mut report = ReconcileReport::default();
+
+        // The whole bucket is listed so versions stored before WASM_BLOB_PREFIX existed are checked too.
+        let blobs = self.blobs.list("").await?;
+        let refs = self.repo.blob_refs().await?;
+
+        let referenced: HashSet<&str> = refs.iter()
+            .filter_map(|r| r.storage_url.as_deref())
+            .collect();
+
+        for blob in &blobs {
+            let settled = blob.last_modified.map(|t| t < cutoff).unwrap_or(false);
+
+            // Only blobs written by the workflow service are ever removed.
+            let owned = blob.key.starts_with(WASM_BLOB_PREFIX);
+
+            if owned && settled && !referenced.contains(blob.key.as_str()) {
+                match self.blobs.delete(&blob.key).await {
+                    Ok(()) => report.orphaned_blobs += 1,
+                    Err(e) => warn!("Failed to remove orphaned blob '{}': {}", blob.key, e),
+                }
+            }
+        }
+
+        let stored: HashSet<&str> = blobs.iter().map(|b| b.key.as_str()).collect();
+
+        for r in refs.iter().filter(|r| r.created_at < cutoff) {
+            let missing = match r.storage_url.as_deref() {
+                Some(url) if !url.is_empty() => !stored.contains(url),
+                _ => true,
+            };
+
+            if missing {
+                warn!("Workflow version '{}' points to blob {:?}, which is not in the store", r.version_id, r.storage_url);
+                report.missing_blobs += 1;
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::{anyhow, bail};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::core::ports::storage::{RunRepository, SearchAttributeRepository, WorkflowRepository};
+use crate::core::domain::manifest::WorkflowManifest;
+use crate::core::domain::run::{NewRun, Run, RunDetail, RunError, RunFilter, RunPage, RunResource, StartRun, StopRun, StoppedRun, MAX_BUSINESS_ID_LEN};
+use crate::core::domain::workflow::Workflow;
+use crate::core::domain::search::{self, NewSearchAttribute, SearchAttributeDefinition, SearchQuery};
+
+/// Starts runs and reads them back; the [`super::run_executor::RunExecutor`] drives them.
+pub struct RunService {
+    repo: Arc<dyn RunRepository>,
+    workflows: Arc<dyn WorkflowRepository>,
+    search_attributes: Arc<dyn SearchAttributeRepository>,
+    /// Wakes the executor so a new run doesn't wait for its next poll.
+    wakeup: Arc<Notify>,
+}
+
+impl RunService {
+    #[cold]
+    pub fn new(repo: Arc<dyn RunRepository>, workflows: Arc<dyn WorkflowRepository>, search_attributes: Arc<dyn SearchAttributeRepository>, wakeup: Arc<Notify>) -> Self {
+        RunService {
+            repo,
+            workflows,
+            search_attributes,
+            wakeup,
+        }
+    }
+
+    /// Starts a run on the active version of the workflow, checking its input first.
+    pub async fn start(&self, actor: Uuid, workflow: &Workflow, req: StartRun) -> Result<Run, anyhow::Error> {
+        let version = self.workflows.find_version(workflow.namespace_id, &workflow.key, &workflow.active_version).await?
+            .ok_or_else(|| anyhow!("Active version '{}' of workflow '{}' is missing", workflow.active_version, workflow.key))?;
+
+        version.schemas.compile()?.validate_input(&req.input)?;
+
+        let workflow_type = workflow_type(&version.manifest, &workflow.key, req.workflow_type)?;
+
+        if let Some(business_id) = &req.business_id {
+            if business_id.trim().is_empty() {
+                bail!(RunError::InvalidBusinessId("it can not be empty".to_string()));
+            }
+            if business_id.len() > MAX_BUSINESS_ID_LEN {
+                bail!(RunError::InvalidBusinessId(format!("it can not be longer than {} bytes", MAX_BUSINESS_ID_LEN)));
+            }
+        }
+
+        let search_attributes = if req.search_attributes.is_null() {
+            serde_json::Map::new()
+        } else {
+            let definitions = self.search_attributes.find_all(workflow.namespace_id).await?;
+            let mut attributes = search::normalize(&definitions, &req.search_attributes)?;
+            attributes.retain(|_, value| !value.is_null());
+            attributes
+        };
+
+        let run = self.repo.create(&NewRun {
+            id: Uuid::now_v7(),
+            namespace_id: workflow.namespace_id,
+            workflow_id: workflow.id,
+            workflow_version_id: version.id,
+            workflow_key: workflow.key.clone(),
+            workflow_type,
+            version: version.version,
+            business_id: req.business_id,
+            reuse_policy: req.reuse_policy,
+            search_attributes: search_attributes.into(),
+            input: req.input,
+            parent_run_id: req.parent_run_id,
+            started_by: actor,
+        }).await?;
+
+        self.wakeup.notify_one();
+
+        Ok(run)
+    }
+
+    pub async fn find(&self, ns_id: Uuid, run_id: Uuid) -> Result<Run, anyhow::Error> {
+        let run = self.repo.find(ns_id, run_id).await?
+            .ok_or(RunError::NotFound)?;
+
+        Ok(run)
+    }
+
+    /// The run with its event history, linked under the namespace slug.
+    pub async fn detail(&self, ns_id: Uuid, slug: &str, run_id: Uuid) -> Result<RunDetail, anyhow::Error> {
+        let run = self.find(ns_id, run_id).await?;
+        let events = self.repo.events(run.id).await?;
+
+        Ok(RunDetail {
+            run: RunResource::new(run, slug),
+            events,
+        })
+    }
+
+    pub async fn list(&self, ns_id: Uuid, filter: &RunFilter) -> Result<RunPage, anyhow::Error> {
+        let limit = filter.limit();
+
+        let search = match &filter.search {
+            Some(query) => SearchQuery::parse(&self.search_attributes.find_all(ns_id).await?, query)?,
+            None => SearchQuery::default(),
+        };
+
+        // one extra row tells whether there is a next page
+        let mut runs = self.repo.list(ns_id, filter, &search, limit + 1).await?;
+
+        let next_before = if runs.len() as i64 > limit {
+            runs.truncate(limit as usize);
+            runs.last().map(|r| r.id)
+        } else {
+            None
+        };
+
+        Ok(RunPage { runs, next_before })
+    }
+
+    /// Asks the run and its active children to cancel; their workflows decide how.
+    pub async fn cancel(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, req: &StopRun) -> Result<StoppedRun, anyhow::Error> {
+        let stopped = self.repo.request_cancel(actor, ns_id, run_id, req.reason.as_deref()).await?;
+
+        // the workflows get to react now rather than at their next poll
+        self.wakeup.notify_one();
+
+        Ok(stopped)
+    }
+
+    /// Ends the run and its active children right away.
+    pub async fn terminate(&self, actor: Uuid, ns_id: Uuid, run_id: Uuid, req: &StopRun) -> Result<StoppedRun, anyhow::Error> {
+        self.repo.terminate(actor, ns_id, run_id, req.reason.as_deref()).await
+    }
+
+    pub async fn search_attributes(&self, ns_id: Uuid) -> Result<Vec<SearchAttributeDefinition>, anyhow::Error> {
+        self.search_attributes.find_all(ns_id).await
+    }
+
+    pub async fn define_search_attribute(&self, actor: Uuid, ns_id: Uuid, a: &NewSearchAttribute) -> Result<SearchAttributeDefinition, anyhow::Error> {
+        a.validate()?;
+
+        self.search_attributes.define(actor, ns_id, a).await
+    }
+
+    pub async fn remove_search_attribute(&self, actor: Uuid, ns_id: Uuid, name: &str) -> Result<(), anyhow::Error> {
+        self.search_attributes.remove(actor, ns_id, name).await
+    }
+}
+
+/// The workflow inside the component to run: the one asked for, or the only one there is.
+/// Components without a manifest are taken to hold a single workflow named like their key.
+fn workflow_type(manifest: &WorkflowManifest, key: &str, requested: Option<String>) -> Result<String, anyhow::Error> {
+    match (requested, manifest.workflows.as_slice()) {
+        (Some(name), []) => Ok(name),
+        (Some(name), declared) => {
+            if !declared.iter().any(|w| w.name == name) {
+                bail!(RunError::UnknownWorkflowType(format!("Workflow '{}' has no workflow type '{}'", key, name)));
+            }
+            Ok(name)
+        }
+        (None, []) => Ok(key.to_string()),
+        (None, [single]) => Ok(single.name.clone()),
+        (None, _) => bail!(RunError::UnknownWorkflowType(format!(
+            "Workflow '{}' holds several workflow types, name one with 'workflow_type'", key,
+        ))),
+    }
+}
@@ -0,0 +1,408 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use serde_json::{json, Map, Value as JsonValue};
+use tokio::sync::{oneshot, Notify};
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+use crate::core::ports::blob::BlobStore;
+use crate::core::ports::storage::{RunRepository, SearchAttributeRepository};
+use crate::core::domain::run::{event, failure, ActivityCall, ClaimedRun, RunClaim, CompletedTask, Decision, NewRunEvent, RunState};
+use crate::adapters::wasmtime::wit_runtime::{CancellationToken, DecisionTask, Heartbeat, PluginKey, WitPluginRuntime};
+
+/// Decision tasks one run may take, so a workflow that never finishes can't hold an executor forever.
+const MAX_DECISIONS: usize = 1000;
+
+/// How often a running activity is checked on: the lease is renewed, heartbeats are stored,
+/// timeouts enforced and cancellations of its run passed on at this pace.
+const ACTIVITY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Drives runs to completion: asks the workflow what it wants next, runs the activities
+/// it schedules and records every step, so a run survives the executor going away.
+///
+/// Runs are claimed with a lease that each recorded step renews, and a timer renews while an
+/// activity runs. When the lease runs out, another executor takes the run over and replays it
+/// from its history; activities that were scheduled but never finished run again. Writes name
+/// the claim, so the executor that lost it can't record anything anymore.
+///
+/// A cancellation request is handed to the workflow at its next decision task, which may
+/// still schedule cleanup activities; activities running when it comes in are told through
+/// their cancellation token. A termination traps the running activity and ends the run.
+///
+/// Up to `max_concurrent` runs are driven at the same time, each in its own task. A decision
+/// task that runs past its timeout is trapped and fails the run.
+///
+/// Activities with a heartbeat timeout are run again when they go quiet for longer than it,
+/// up to their attempts; each attempt can read the heartbeat details the one before sent last.
+pub struct RunExecutor {
+    runs: Arc<dyn RunRepository>,
+    search_attributes: Arc<dyn SearchAttributeRepository>,
+    blobs: Arc<dyn BlobStore>,
+    wit_runtime: Arc<WitPluginRuntime>,
+    wakeup: Arc<Notify>,
+    lease: std::time::Duration,
+    decision_timeout: std::time::Duration,
+    max_concurrent: usize,
+}
+
+impl RunExecutor {
+    #[cold]
+    pub fn new(
+        runs: Arc<dyn RunRepository>,
+        search_attributes: Arc<dyn SearchAttributeRepository>,
+        blobs: Arc<dyn BlobStore>,
+        wit_runtime: Arc<WitPluginRuntime>,
+        lease: std::time::Duration,
+        decision_timeout: std::time::Duration,
+        max_concurrent: usize,
+    ) -> Self {
+        RunExecutor {
+            runs,
+            search_attributes,
+            blobs,
+            wit_runtime,
+            wakeup: Arc::new(Notify::new()),
+            lease,
+            decision_timeout,
+            max_concurrent: max_concurrent.max(1),
+        }
+    }
+
+    /// Notified when a run is started, see [`super::run::RunService`].
+    pub fn wakeup(&self) -> Arc<Notify> {
+        self.wakeup.clone()
+    }
+
+    pub async fn run(self: Arc<Self>, poll_interval: std::time::Duration, mut shutdown_rx: oneshot::Receiver<()>) {
+        let mut ticker = tokio::time::interval(poll_interval);
+        let mut driving = JoinSet::new();
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.wakeup.notified() => {}
+                Some(joined) = driving.join_next() => {
+                    if let Err(e) = joined {
+                        error!("❌ Run task failed: {}", e);
+                    }
+                }
+                _ = &mut shutdown_rx => {
+                    info!("🛑 Received shutdown signal, stopping run executor");
+                    break;
+                }
+            }
+
+            if let Err(e) = self.fill(&mut driving).await {
+                error!("❌ Failed to claim runs: {}", e);
+            }
+        }
+
+        // the runs keep their leases, another executor picks them up once those run out
+        driving.shutdown().await;
+    }
+
+    /// Claims runs that are due until `max_concurrent` are being driven.
+    async fn fill(self: &Arc<Self>, driving: &mut JoinSet<()>) -> Result<(), anyhow::Error> {
+        while driving.len() < self.max_concurrent {
+            let Some(claimed) = self.runs.claim(self.lease.as_secs_f64()).await? else {
+                break;
+            };
+            let run_id = claimed.run.id;
+            let executor = self.clone();
+
+            driving.spawn(async move {
+                // the run keeps its lease, it is picked up again once that runs out
+                if let Err(e) = executor.drive(claimed).await {
+                    error!("❌ Failed to drive run {}: {:#}", run_id, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn drive(&self, claimed: ClaimedRun) -> Result<(), anyhow::Error> {
+        let run = &claimed.run;
+
+        let Some(version) = &claimed.version else {
+            return self.fail(claimed.claim, "version_missing", "The version the run was started on no longer exists").await;
+        };
+
+        let key = PluginKey::new(run.namespace_id, &run.workflow_key, &run.version);
+        if !self.wit_runtime.is_loaded(&key).await {
+            let Some(storage_url) = claimed.storage_url.as_deref().filter(|url| !url.is_empty()) else {
+                return self.fail(claimed.claim, "version_missing", "The wasm of the version is not stored").await;
+            };
+
+            let wasm = self.blobs.get(storage_url).await?;
+            self.wit_runtime.load_wit_plugin(&key, &wasm, version.schemas.compile()?).await?;
+        }
+
+        let input = run.input.clone().unwrap_or_default();
+        let mut history = claimed.completed_tasks();
+        let definitions = self.search_attributes.find_all(run.namespace_id).await?;
+        let mut attributes = run.search_attributes.as_object().cloned().unwrap_or_default();
+        let mut cancel_requested = run.cancel_requested_at.is_some();
+
+        for (scheduled, call) in claimed.unfinished_activities() {
+            match self.run_activity(&key, claimed.claim, &call, Some(scheduled), cancel_requested).await? {
+                Some(task) => history.push(task),
+                None => return Ok(()),
+            }
+        }
+
+        for _ in 0..MAX_DECISIONS {
+            match self.runs.renew(claimed.claim, self.lease.as_secs_f64()).await? {
+                Some(status) => cancel_requested = status.cancel_requested,
+                None => return Ok(()),
+            }
+
+            let task = DecisionTask {
+                workflow_type: &run.workflow_type,
+                run_seq: claimed.seq as u64,
+                input: &input,
+                history: &history,
+                search_attributes: &definitions,
+                cancel_requested,
+                timeout: self.decision_timeout,
+            };
+            let decided = match self.wit_runtime.decide(&key, &task).await {
+                Ok(decided) => decided,
+                Err(e) => return self.fail(claimed.claim, "workflow_error", format!("{:#}", e)).await,
+            };
+
+            // replays upsert what is already stored, only changes are written
+            let changed = changes(&attributes, decided.search_attributes);
+            if !changed.is_empty() {
+                if !self.runs.upsert_search_attributes(claimed.claim, &changed, self.lease.as_secs_f64()).await? {
+                    return Ok(());
+                }
+                for (name, value) in changed {
+                    match value {
+                        JsonValue::Null => attributes.remove(&name),
+                        value => attributes.insert(name, value),
+                    };
+                }
+            }
+            let output = decided.output;
+
+            let calls = match Decision::from_output(output) {
+                Decision::Complete(result) => {
+                    if let Err(violations) = self.wit_runtime.validate_output(&key, &result).await? {
+                        return self.fail(claimed.claim, "invalid_output", violations.to_string()).await;
+                    }
+
+                    let completed = NewRunEvent::new(event::RUN_COMPLETED, json!({ "result": result }));
+                    if self.runs.finish(claimed.claim, RunState::Succeeded, Some(&result), None, &completed).await? {
+                        info!("✅ Run {} of {} succeeded", run.id, key);
+                    }
+                    return Ok(());
+                }
+                Decision::Fail(error) => return self.fail(claimed.claim, "workflow_failed", error).await,
+                Decision::Invalid(error) => return self.fail(claimed.claim, "invalid_decision", error).await,
+                Decision::Cancelled(details) => {
+                    let cancelled = NewRunEvent::new(event::RUN_CANCELLED, json!({ "details": details }));
+                    if self.runs.finish(claimed.claim, RunState::Cancelled, None, None, &cancelled).await? {
+                        info!("🛑 Run {} of {} cancelled", run.id, key);
+                    }
+                    return Ok(());
+                }
+                Decision::Schedule(calls) if calls.is_empty() => {
+                    return self.fail(claimed.claim, "workflow_stuck", "Workflow is waiting without scheduling anything").await;
+                }
+                Decision::Schedule(calls) => calls,
+            };
+
+            for call in calls {
+                match self.run_activity(&key, claimed.claim, &call, None, cancel_requested).await? {
+                    Some(task) => history.push(task),
+                    // ended from outside, e.g. terminated
+                    None => return Ok(()),
+                }
+            }
+        }
+
+        self.fail(claimed.claim, "too_many_decisions", format!("Workflow did not finish within {} decision tasks", MAX_DECISIONS)).await
+    }
+
+    /// Runs an activity, recording it as scheduled first unless that already happened.
+    /// `None` means the run stopped running meanwhile. Activities scheduled after the run was
+    /// asked to cancel are its cleanup, they are only told about a termination. Attempts that
+    /// miss their heartbeat timeout are retried.
+    async fn run_activity(&self, key: &PluginKey, claim: RunClaim, call: &ActivityCall, scheduled: Option<i64>, cancel_requested: bool) -> Result<Option<CompletedTask>, anyhow::Error> {
+        let lease = self.lease.as_secs_f64();
+        let run_id = claim.run_id;
+
+        let scheduled = match scheduled {
+            Some(seq) => seq,
+            None => {
+                let scheduled_event = NewRunEvent::new(event::ACTIVITY_SCHEDULED, serde_json::to_value(call)?);
+                let Some(seqs) = self.runs.append_events(claim, &[scheduled_event], lease).await? else {
+                    return Ok(None);
+                };
+                seqs[0] as i64
+            }
+        };
+
+        let outcome = loop {
+            let Some(attempt) = self.runs.start_attempt(claim, scheduled).await? else {
+                return Ok(None);
+            };
+            let token = Arc::new(CancellationToken::default());
+            let heartbeat = Arc::new(Heartbeat::resuming(attempt.heartbeat_details));
+
+            let watcher = tokio::spawn(ActivityWatch {
+                runs: self.runs.clone(),
+                claim,
+                scheduled,
+                lease_secs: lease,
+                heartbeat_timeout: call.heartbeat_timeout(),
+                cancel_requested,
+                token: token.clone(),
+                heartbeat: heartbeat.clone(),
+            }.run());
+
+            // a trap is the activity's failure, not the run's
+            let outcome = self.wit_runtime.run_activity(key, &call.name, &call.input, token.clone(), heartbeat.clone())
+                .await
+                .unwrap_or_else(|e| Err(format!("{:#}", e)));
+            watcher.abort();
+
+            if !token.is_timed_out() {
+                break outcome;
+            }
+
+            let timed_out = NewRunEvent::new(event::ACTIVITY_TIMED_OUT, json!({
+                "scheduled": scheduled,
+                "name": call.name,
+                "attempt": attempt.attempt,
+                "details": heartbeat.details(),
+            }));
+            if self.runs.append_events(claim, &[timed_out], lease).await?.is_none() {
+                return Ok(None);
+            }
+
+            if attempt.attempt as u32 >= call.max_attempts() {
+                break Err(format!("Activity missed its heartbeat timeout on all {} attempts", attempt.attempt));
+            }
+            info!("🔁 Retrying activity {} of run {}, attempt {} missed its heartbeat timeout", call.name, run_id, attempt.attempt);
+        };
+
+        let (kind, task) = match outcome {
+            Ok(result) => (event::ACTIVITY_COMPLETED, CompletedTask {
+                id: scheduled,
+                name: call.name.clone(),
+                result: Some(result),
+                error: None,
+            }),
+            Err(error) => (event::ACTIVITY_FAILED, CompletedTask {
+                id: scheduled,
+                name: call.name.clone(),
+                result: None,
+                error: Some(error),
+            }),
+        };
+
+        let finished = NewRunEvent::new(kind, json!({
+            "scheduled": scheduled,
+            "name": task.name,
+            "result": task.result,
+            "error": task.error,
+        }));
+        if self.runs.append_events(claim, &[finished], lease).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(task))
+    }
+
+    async fn fail(&self, claim: RunClaim, kind: &str, message: impl Into<String>) -> Result<(), anyhow::Error> {
+        let failure = failure(kind, message);
+
+        if self.runs.finish(claim, RunState::Failed, None, Some(&failure), &NewRunEvent::new(event::RUN_FAILED, failure.clone())).await? {
+            warn!("⚠️  Run {} failed: {}", claim.run_id, failure);
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps an eye on a running activity: renews the lease of the run, stores its heartbeats,
+/// times it out when they stop coming and passes a cancellation or termination of the run on to it.
+struct ActivityWatch {
+    runs: Arc<dyn RunRepository>,
+    claim: RunClaim,
+    scheduled: i64,
+    lease_secs: f64,
+    heartbeat_timeout: Option<std::time::Duration>,
+    /// Cleanup activities are scheduled after the request, they aren't told about it.
+    cancel_requested: bool,
+    token: Arc<CancellationToken>,
+    heartbeat: Arc<Heartbeat>,
+}
+
+impl ActivityWatch {
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(ACTIVITY_POLL_INTERVAL);
+        ticker.tick().await;
+
+        let mut beats = 0;
+        let mut last_beat = tokio::time::Instant::now();
+
+        loop {
+            ticker.tick().await;
+
+            if self.heartbeat.beats() != beats {
+                beats = self.heartbeat.beats();
+                last_beat = tokio::time::Instant::now();
+
+                let details = self.heartbeat.details().unwrap_or_default();
+                match self.runs.heartbeat(self.claim, self.scheduled, &details, self.lease_secs).await {
+                    Ok(true) => {}
+                    Ok(false) => return self.terminate(),
+                    Err(e) => warn!("⚠️  Failed to store a heartbeat of run {}: {}", self.claim.run_id, e),
+                }
+            } else if self.heartbeat_timeout.is_some_and(|timeout| last_beat.elapsed() > timeout) {
+                warn!("⚠️  Activity {} of run {} missed its heartbeat timeout", self.scheduled, self.claim.run_id);
+                self.token.time_out();
+                return;
+            }
+
+            // activities that never heartbeat still keep the run from being taken over
+            match self.runs.renew(self.claim, self.lease_secs).await {
+                Ok(Some(status)) => {
+                    if status.cancel_requested && !self.cancel_requested && !self.token.is_cancelled() {
+                        info!("🛑 Cancelling the running activity of run {}", self.claim.run_id);
+                        self.token.cancel();
+                    }
+                }
+                Ok(None) => return self.terminate(),
+                Err(e) => warn!("⚠️  Failed to renew the lease of run {}: {}", self.claim.run_id, e),
+            }
+        }
+    }
+
+    fn terminate(&self) {
+        info!("🛑 Terminating the running activity of run {}", self.claim.run_id);
+        self.token.terminate();
+    }
+}
+
+/// Upserted attributes that differ from the stored ones.
+fn changes(stored: &Map<String, JsonValue>, upserted: Map<String, JsonValue>) -> Map<String, JsonValue> {
+    upserted.into_iter()
+        .filter(|(name, value)| stored.get(name).unwrap_or(&JsonValue::Null) != value)
+        .collect()
+}
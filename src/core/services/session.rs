@@ -0,0 +1,198 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::sync::Arc;
+use anyhow::bail;
+use chrono::{Duration, Utc};
+use rand::{self, Rng};
+use rand::distr::Alphanumeric;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::ports::storage::{SessionRepository, UserRepository};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::session::{IssuedSession, NewSession, Session, SessionError, SessionOrigin};
+use crate::core::domain::scope::{ScopeError, TokenScopes};
+use crate::core::domain::user::User;
+use super::audit::AuditService;
+
+const REFRESH_SECRET_LEN: usize = 48;
+
+/// Sign-in sessions: short-lived access tokens are issued against a session and
+/// renewed with its refresh token, which is replaced on every use.
+///
+/// Refresh tokens look like `<session id>.<secret>`, only a hash of the secret is stored.
+pub struct SessionService {
+    repo: Arc<dyn SessionRepository>,
+    users: Arc<dyn UserRepository>,
+    audit: Arc<AuditService>,
+    access_ttl: Duration,
+    refresh_ttl: Duration,
+}
+
+impl SessionService {
+    #[cold]
+    pub fn new(repo: Arc<dyn SessionRepository>, users: Arc<dyn UserRepository>, audit: Arc<AuditService>, access_ttl: Duration, refresh_ttl: Duration) -> Self {
+        SessionService {
+            repo,
+            users,
+            audit,
+            access_ttl,
+            refresh_ttl,
+        }
+    }
+
+    pub fn access_ttl(&self) -> Duration {
+        self.access_ttl
+    }
+
+    /// Starts a session whose access tokens carry `scopes`; the default leaves them unrestricted.
+    pub async fn start(&self, user: &User, origin: SessionOrigin, scopes: TokenScopes) -> Result<IssuedSession, anyhow::Error> {
+        let id = Uuid::now_v7();
+        let secret = refresh_secret();
+
+        let session = self.repo.create(&NewSession {
+            id,
+            user_id: user.id,
+            refresh_hash: hash_secret(&secret),
+            user_agent: origin.user_agent,
+            ip: origin.ip,
+            expires_at: Utc::now() + self.refresh_ttl,
+            scopes: scopes.scope_names(),
+            namespace_ids: scopes.namespaces,
+        }).await?;
+
+        Ok(IssuedSession {
+            session,
+            refresh_token: format!("{}.{}", id, secret),
+        })
+    }
+
+    /// Starts a restricted session for a signed-in user, e.g. for a token handed to CI.
+    ///
+    /// `granted` are the limits of the token asking, the new session can not exceed them.
+    pub async fn start_scoped(&self, user_id: Uuid, granted: &TokenScopes, origin: SessionOrigin, scopes: TokenScopes) -> Result<(IssuedSession, User), anyhow::Error> {
+        if !scopes.is_within(granted) {
+            bail!(ScopeError::Escalation);
+        }
+
+        let user = self.users.find_by_id(user_id).await?
+            .filter(|u| !u.is_disabled())
+            .ok_or(SessionError::NotFound)?;
+
+        let issued = self.start(&user, origin, scopes.clone()).await?;
+
+        self.audit.record(NewAuditEntry::new("session.start_scoped")
+            .actor(user_id)
+            .object("session", issued.session.id)
+            .meta(json!(scopes))
+        ).await;
+
+        Ok((issued, user))
+    }
+
+    /// Replaces the refresh token of a session and returns the user to issue a new access token for.
+    ///
+    /// A refresh token used a second time means it was copied, so the whole session is revoked.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<(IssuedSession, User), anyhow::Error> {
+        let Some((id, secret)) = refresh_token.split_once('.') else {
+            bail!(SessionError::InvalidRefreshToken);
+        };
+        let id = Uuid::parse_str(id).map_err(|_| SessionError::InvalidRefreshToken)?;
+
+        let record = self.repo.find(id).await?
+            .ok_or(SessionError::InvalidRefreshToken)?;
+
+        let presented = hash_secret(secret);
+        if presented != record.refresh_hash {
+            if record.session.revoked_at.is_none() {
+                warn!("⚠️  Refresh token of session {} was reused, revoking the session", id);
+                self.repo.revoke(record.session.user_id, id).await?;
+                self.audit.record(NewAuditEntry::new("session.refresh_reuse")
+                    .actor(record.session.user_id)
+                    .object("session", id)
+                ).await;
+            }
+            bail!(SessionError::InvalidRefreshToken);
+        }
+
+        let new_secret = refresh_secret();
+        let session = self.repo.rotate_refresh(id, &presented, &hash_secret(&new_secret), Utc::now() + self.refresh_ttl).await?
+            .ok_or(SessionError::InvalidRefreshToken)?;
+
+        let user = self.users.find_by_id(session.user_id).await?
+            .filter(|u| !u.is_disabled())
+            .ok_or(SessionError::InvalidRefreshToken)?;
+
+        Ok((IssuedSession { session, refresh_token: format!("{}.{}", id, new_secret) }, user))
+    }
+
+    pub async fn find_all(&self, user_id: Uuid) -> Result<Vec<Session>, anyhow::Error> {
+        self.repo.find_by_user(user_id).await
+    }
+
+    pub async fn revoke(&self, user_id: Uuid, session_id: Uuid) -> Result<(), anyhow::Error> {
+        if !self.repo.revoke(user_id, session_id).await? {
+            bail!(SessionError::NotFound);
+        }
+
+        self.audit.record(NewAuditEntry::new("session.revoke")
+            .actor(user_id)
+            .object("session", session_id)
+        ).await;
+
+        Ok(())
+    }
+
+    pub async fn revoke_all(&self, user_id: Uuid) -> Result<u64, anyhow::Error> {
+        let revoked = self.repo.revoke_all(user_id).await?;
+
+        self.audit.record(NewAuditEntry::new("session.revoke_all")
+            .actor(user_id)
+            .object("user", user_id)
+            .meta(json!({ "sessions": revoked }))
+        ).await;
+
+        Ok(revoked)
+    }
+
+    /// Rejects a single access token until it would have expired anyway.
+    pub async fn deny_token(&self, actor: Uuid, jti: Uuid, expires_at: chrono::DateTime<Utc>) -> Result<(), anyhow::Error> {
+        self.repo.deny_token(jti, expires_at).await?;
+
+        self.audit.record(NewAuditEntry::new("token.revoke")
+            .actor(actor)
+            .meta(json!({ "jti": jti }))
+        ).await;
+
+        Ok(())
+    }
+
+    pub async fn is_revoked(&self, session_id: Uuid, jti: Uuid) -> Result<bool, anyhow::Error> {
+        self.repo.is_revoked(session_id, jti).await
+    }
+}
+
+fn refresh_secret() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(REFRESH_SECRET_LEN)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
@@ -0,0 +1,186 @@
+/*
+ * Project: Xarxa — Durable WASM Workflow Orchestrator
+ * Copyright (c) 2025 Xarxa Systems
+ *
+ * Xarxa is licensed under the GNU Affero General Public License v3.0 (AGPL-3.0).
+ * See the LICENSE file in the project root for the full license text.
+ *
+ * Commercial licensing (MIT / proprietary) is available.
+ * Contact: contact@xarxa.io
+ *
+ * SPDX-License-Identifier: AGPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::bail;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::{self, Rng};
+use rand::distr::Alphanumeric;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::core::ports::identity::IdentityProvider;
+use crate::core::ports::storage::{IdentityRepository, NamespaceRepository, UserRepository};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::namespace::NamespaceRole;
+use crate::core::domain::oidc::{GroupRoleRule, Identity, OidcCallback, OidcError, OidcLogin};
+use crate::core::domain::user::{NewUser, RegistrationMode, SignInError, User};
+use super::audit::AuditService;
+
+/// How long the user has to come back from the identity provider.
+const LOGIN_TTL_MINUTES: i64 = 10;
+
+/// Stored as the token hash of accounts made through single sign-on; it matches no token.
+const NO_TOKEN: &str = "!";
+
+/// Single sign-on through an OpenID Connect provider.
+///
+/// Users are found by the provider account they signed in with before, then by email;
+/// unknown emails get a new account if the [`RegistrationMode`] lets them sign up. Group rules only ever add members or raise roles,
+/// access granted by hand in Xarxa is never taken away by a sign-in.
+pub struct SsoService {
+    provider: Arc<dyn IdentityProvider>,
+    identities: Arc<dyn IdentityRepository>,
+    users: Arc<dyn UserRepository>,
+    namespaces: Arc<dyn NamespaceRepository>,
+    audit: Arc<AuditService>,
+    registration: RegistrationMode,
+    rules: Vec<GroupRoleRule>,
+}
+
+impl SsoService {
+    #[cold]
+    pub fn new(
+        provider: Arc<dyn IdentityProvider>,
+        identities: Arc<dyn IdentityRepository>,
+        users: Arc<dyn UserRepository>,
+        namespaces: Arc<dyn NamespaceRepository>,
+        audit: Arc<AuditService>,
+        registration: RegistrationMode,
+        rules: Vec<GroupRoleRule>,
+    ) -> Self {
+        SsoService {
+            provider,
+            identities,
+            users,
+            namespaces,
+            audit,
+            registration,
+            rules,
+        }
+    }
+
+    /// Starts a login and returns the provider URL to send the browser to.
+    pub async fn begin(&self) -> Result<String, anyhow::Error> {
+        let login = OidcLogin {
+            state: random_string(32),
+            nonce: random_string(32),
+            code_verifier: random_string(64),
+            created_at: Utc::now(),
+        };
+
+        self.identities.save_login(&login).await?;
+
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(login.code_verifier.as_bytes()));
+
+        Ok(self.provider.authorization_url(&login.state, &login.nonce, &challenge))
+    }
+
+    /// Finishes a login the provider redirected back, returning the signed in user.
+    pub async fn complete(&self, callback: &OidcCallback) -> Result<User, anyhow::Error> {
+        let not_before = Utc::now() - Duration::minutes(LOGIN_TTL_MINUTES);
+        let login = self.identities.take_login(&callback.state, not_before).await?
+            .ok_or(OidcError::InvalidState)?;
+
+        if let Some(error) = &callback.error {
+            let reason = callback.error_description.as_deref().unwrap_or(error);
+            return Err(OidcError::Denied(reason.to_string()).into());
+        }
+
+        let code = callback.code.as_deref()
+            .ok_or_else(|| OidcError::Denied("no authorization code".to_string()))?;
+
+        let identity = self.provider.exchange(code, &login.code_verifier, &login.nonce).await?;
+        let user = self.provision(&identity).await?;
+        if user.is_disabled() {
+            bail!(SignInError::Disabled);
+        }
+
+        self.apply_group_rules(&user, &identity.groups).await;
+
+        Ok(user)
+    }
+
+    async fn provision(&self, identity: &Identity) -> Result<User, anyhow::Error> {
+        if let Some(user) = self.identities.find_user(&identity.issuer, &identity.subject).await? {
+            self.identities.link(&identity.issuer, &identity.subject, user.id).await?;
+            return Ok(user);
+        }
+
+        let user = match self.users.find_by_email(&identity.email).await? {
+            Some(user) => user,
+            None => {
+                self.registration.check(&identity.email)?;
+
+                let user = self.users.create(&NewUser { email: identity.email.clone() }, NO_TOKEN.to_string()).await?;
+
+                self.audit.record(NewAuditEntry::new("user.signup")
+                    .actor(user.id)
+                    .object("user", user.id)
+                    .meta(json!({ "email": user.email, "issuer": identity.issuer }))
+                ).await;
+
+                user
+            }
+        };
+
+        if self.identities.link(&identity.issuer, &identity.subject, user.id).await? {
+            self.audit.record(NewAuditEntry::new("user.identity_link")
+                .actor(user.id)
+                .object("user", user.id)
+                .meta(json!({ "issuer": identity.issuer, "subject": identity.subject }))
+            ).await;
+        }
+
+        Ok(user)
+    }
+
+    /// Best-effort: a rule that can't be applied is logged and does not fail the sign-in.
+    async fn apply_group_rules(&self, user: &User, groups: &[String]) {
+        let mut grants: HashMap<&str, NamespaceRole> = HashMap::new();
+        for rule in self.rules.iter().filter(|r| groups.contains(&r.group)) {
+            grants.entry(rule.namespace.as_str())
+                .and_modify(|role| if rule.role.outranks(*role) { *role = rule.role })
+                .or_insert(rule.role);
+        }
+
+        for (slug, role) in grants {
+            if let Err(e) = self.grant(user, slug, role).await {
+                warn!("Failed to apply group rule for namespace '{}' to user '{}': {}", slug, user.id, e);
+            }
+        }
+    }
+
+    async fn grant(&self, user: &User, slug: &str, role: NamespaceRole) -> Result<(), anyhow::Error> {
+        let ns = self.namespaces.find_by_slug(slug).await?
+            .ok_or_else(|| anyhow::anyhow!("no namespace '{}'", slug))?
+            .namespace;
+
+        match self.namespaces.role_by_uid(user.id, ns.id).await? {
+            None => self.namespaces.add_member(user.id, ns.id, user.id, role).await,
+            Some(current) if role.outranks(current) => self.namespaces.set_member_role(user.id, ns.id, user.id, role).await,
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
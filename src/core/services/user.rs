@@ -11,82 +11,152 @@
  * SPDX-License-Identifier: AGPL-3.0-or-later
  */
 
+use std::net::IpAddr;
 use std::sync::Arc;
 use anyhow::{bail, Result};
 use rand::{self, Rng};
 use rand::distr::Alphanumeric;
-use sha2::{Digest, Sha256};
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
 
-use crate::core::ports::storage::UserRepository;
-use crate::core::domain::user::{NewUser, User};
+use crate::core::ports::storage::{SessionRepository, UserRepository};
+use crate::core::domain::audit::NewAuditEntry;
+use crate::core::domain::user::{NewUser, RegistrationMode, SignInError, User};
+use super::audit::AuditService;
+use super::credential::{self, Verification};
+use super::rate_limit::SignInLimiter;
 
 
 pub struct UserService{
     repo: Arc<dyn UserRepository>,
+    sessions: Arc<dyn SessionRepository>,
+    audit: Arc<AuditService>,
+    limiter: SignInLimiter,
+    registration: RegistrationMode,
 }
 
 impl UserService {
     #[cold]
-    pub fn new(r: Arc<dyn UserRepository>) -> Self {
+    pub fn new(
+        r: Arc<dyn UserRepository>,
+        sessions: Arc<dyn SessionRepository>,
+        audit: Arc<AuditService>,
+        limiter: SignInLimiter,
+        registration: RegistrationMode,
+    ) -> Self {
         UserService{
             repo: r,
+            sessions,
+            audit,
+            limiter,
+            registration,
         }
     }
 
-    pub async fn auth(&self, email: String, token: String) -> Result<User> {
-        match self.repo.find_by_email(&email).await? {
-            Some(u) => {
-                if verify_token(&token, &u.token).await {
-                   Ok(u)
-                } else {
-                    bail!("Wrong email or password")
+    /// Checks the credentials of a sign-in; hashes in an outdated format are upgraded on success.
+    pub async fn auth(&self, email: String, token: String, ip: Option<IpAddr>) -> Result<User> {
+        if let Some(wait) = self.limiter.retry_after(&email, ip) {
+            bail!(SignInError::TooManyAttempts { retry_after_secs: wait.as_secs().max(1) });
+        }
+
+        let user = self.repo.find_by_email(&email).await?;
+
+        let verification = {
+            let stored = user.as_ref().map(|u| u.token.clone());
+            let token = token.clone();
+            tokio::task::spawn_blocking(move || match stored {
+                Some(stored) => credential::verify(&token, &stored),
+                None => {
+                    credential::verify_dummy(&token);
+                    Verification::Invalid
                 }
-            },
-            None => bail!("Wrong email or password")
+            }).await?
+        };
+
+        let Some(mut u) = user.filter(|_| verification.is_valid()) else {
+            self.limiter.record_failure(&email, ip);
+            bail!(SignInError::InvalidCredentials);
+        };
+
+        self.limiter.record_success(&email);
+
+        if u.is_disabled() {
+            bail!(SignInError::Disabled);
+        }
+
+        if verification == Verification::ValidNeedsRehash
+            && let Err(e) = self.upgrade_hash(&mut u, token).await
+        {
+            warn!("Failed to upgrade credential hash of user '{}': {}", u.id, e);
         }
+
+        Ok(u)
+    }
+
+    /// Self-service registration, subject to the configured [`RegistrationMode`].
+    pub async fn signup(&self, u: &NewUser) -> Result<User, anyhow::Error> {
+        self.registration.check(&u.email)?;
+
+        self.create(u).await
+    }
+
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, anyhow::Error> {
+        self.repo.find_by_email(email).await
     }
 
+    /// Creates an account whatever the registration mode; the token is in `User::token` this once.
     pub async fn create(&self, u: &NewUser) -> Result<User, anyhow::Error> {
-        let real_token: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(64)
-            .map(char::from)
-            .collect();
-
-        let salt: String = rand::rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-
-        let mut hasher = Sha256::new();
-        hasher.update(real_token.as_bytes());
-        hasher.update(salt.as_bytes());
-        let hash = format!("{:x}", hasher.finalize());
-        
-        let token_hash = format!("{}:{}", salt, hash);
+        let (real_token, token_hash) = new_credential().await?;
 
         let mut u = self.repo.create(u, token_hash).await?;
         u.token = real_token;
 
+        self.audit.record(NewAuditEntry::new("user.signup")
+            .actor(u.id)
+            .object("user", u.id)
+            .meta(json!({ "email": u.email }))
+        ).await;
+
         Ok(u)
     }
-}
 
-#[cold]
-async fn verify_token(token: &str, stored_hash: &str) -> bool {
-    let parts: Vec<&str> = stored_hash.split(':').collect();
-    if parts.len() != 2 {
-        return false;
+    /// Replaces the user's token and ends all their sessions; the new token is returned once.
+    pub async fn rotate_token(&self, uid: Uuid) -> Result<String, anyhow::Error> {
+        let (real_token, token_hash) = new_credential().await?;
+
+        self.repo.update_token_hash(uid, token_hash).await?;
+        let sessions = self.sessions.revoke_all(uid).await?;
+
+        self.audit.record(NewAuditEntry::new("user.rotate_token")
+            .actor(uid)
+            .object("user", uid)
+            .meta(json!({ "revoked_sessions": sessions }))
+        ).await;
+
+        Ok(real_token)
+    }
+
+    async fn upgrade_hash(&self, u: &mut User, token: String) -> Result<()> {
+        let token_hash = tokio::task::spawn_blocking(move || credential::hash(&token)).await??;
+
+        self.repo.update_token_hash(u.id, token_hash.clone()).await?;
+        u.token = token_hash;
+
+        Ok(())
     }
-    
-    let salt = parts[0];
-    let hash = parts[1];
-    
-    let mut hasher = Sha256::new();
-    hasher.update(token.as_bytes());
-    hasher.update(salt.as_bytes());
-    let computed_hash = format!("{:x}", hasher.finalize());
-    
-    computed_hash == hash
-}
\ No newline at end of file
+}
+
+/// A new random token and the hash stored for it.
+pub(super) async fn new_credential() -> Result<(String, String)> {
+    let real_token: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect();
+
+    let token = real_token.clone();
+    let token_hash = tokio::task::spawn_blocking(move || credential::hash(&token)).await??;
+
+    Ok((real_token, token_hash))
+}
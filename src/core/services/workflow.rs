@@ -12,37 +12,62 @@
  */
 
 use std::sync::Arc;
-use anyhow::{ensure};
-use aws_sdk_s3::client::Client as s3c;
+use anyhow::ensure;
+use serde_json::json;
+use tracing::{error, warn};
 use uuid::Uuid;
 
+use crate::core::ports::blob::BlobStore;
 use crate::core::ports::storage::WorkflowRepository;
-use crate::core::domain::workflow::{NewWorkflowParams, Workflow, NewWorkflow};
-use crate::adapters::wasmtime::wit_runtime::WitPluginRuntime;
+use crate::core::domain::workflow::{NewWorkflowParams, Workflow, NewWorkflow, WorkflowVersion};
+use crate::core::domain::schema::WorkflowSchemas;
+use crate::core::domain::audit::NewAuditEntry;
+use crate::adapters::wasmtime::wit_runtime::{PluginKey, WitPluginRuntime};
+use super::audit::AuditService;
+
+/// Prefix of every workflow wasm stored in the blob store.
+pub const WASM_BLOB_PREFIX: &str = "workflows/";
 
 pub struct WorkflowService{
     repo: Arc<dyn WorkflowRepository>,
-    s3_client: Arc<s3c>,
+    blobs: Arc<dyn BlobStore>,
     wit_runtime: Arc<WitPluginRuntime>,
+    audit: Arc<AuditService>,
 }
 
 impl WorkflowService {
     #[cold]
-    pub fn new(repo: Arc<dyn WorkflowRepository>, s3_client: Arc<s3c>, wit_runtime: Arc<WitPluginRuntime>) -> Self {
+    pub fn new(repo: Arc<dyn WorkflowRepository>, blobs: Arc<dyn BlobStore>, wit_runtime: Arc<WitPluginRuntime>, audit: Arc<AuditService>) -> Self {
         WorkflowService{
             repo,
-            s3_client,
+            blobs,
             wit_runtime,
+            audit,
         }
     }
 
-    pub async fn create(&self, user_id: Uuid, namespace_id: Uuid, wp: NewWorkflowParams, wasm_bytes: &[u8]) -> Result<Workflow, anyhow::Error> {
-        if wasm_bytes.len() >= 4 {
-            let magic = &wasm_bytes[0..4];
-            ensure!(magic == b"\0asm", "Invalid WASM file format");
-        }
+    /// Creates a workflow with its first version.
+    ///
+    /// Everything that can be checked up front is checked before any side effect,
+    /// then the blob is stored and only after that the DB transaction is committed.
+    /// A failed commit removes the blob again; whatever is left behind by a crash
+    /// in between is picked up by the [`super::reconciler::BlobReconciler`].
+    pub async fn create(&self, user_id: Uuid, namespace_id: Uuid, wp: NewWorkflowParams, wasm_bytes: &[u8], schemas: WorkflowSchemas) -> Result<Workflow, anyhow::Error> {
+        ensure!(!wp.key.trim().is_empty(), "Workflow key cannot be empty");
+
+        // 0. compile and check the component against the orchestrator world before anything is stored
+        let prepared = self.wit_runtime.prepare(wasm_bytes).await?;
+        let manifest = prepared.manifest().clone();
+
+        // Schemas uploaded with the wasm take precedence over the ones the component declares.
+        let schemas = if schemas.is_empty() { manifest.schemas() } else { schemas };
+        let compiled_schemas = schemas.compile()?;
         
-        let hash = md5::compute(&wasm_bytes);
+        let hash = md5::compute(wasm_bytes);
+
+        // Every upload gets its own blob, so removing it on failure can never hit a blob
+        // that belongs to an already committed version.
+        let blob_key = format!("{}{}/{}.wasm", WASM_BLOB_PREFIX, namespace_id, Uuid::now_v7());
 
         let w = NewWorkflow{
             key: wp.key.clone(),
@@ -50,48 +75,43 @@ impl WorkflowService {
             description: wp.description.clone(),
             wasm_md5: hash,
             wasm_size_bytes: wasm_bytes.len(),
-            storage_url: "".to_string(),
+            storage_url: blob_key.clone(),
+            schemas,
+            manifest,
         };
 
-        // 1. create a new workflow + workflow version
-        let db_result = self.repo.insert(user_id,namespace_id, &w).await?;
-
-        let key = format!(
-            "{}.wasm", w.key,
-        );
-
-        let bucket = "xarxa-s3".to_string();
-
-        // 2. save to s3
-        self.s3_client
-            .put_object()
-            .bucket(&bucket)
-            .key(&key)
-            .body(wasm_bytes.to_vec().into())
-            .acl(aws_sdk_s3::types::ObjectCannedAcl::BucketOwnerFullControl)
-            .send()
-            .await?;
-
-            // .map_err(|err| Error::InternalServerError(err.to_string()))?;
-
-        // 3. add to runtime
-        self.wit_runtime.load_wit_plugin(&w.key, wasm_bytes).await?;
-
-
-        let resp = Workflow { 
-            id: db_result.id,
-            namespace_id: db_result.namespace_id, 
-            key: db_result.key, 
-            display_name: db_result.display_name, 
-            description: db_result.description, 
-            active_version: "".to_string(), 
-            is_archived: db_result.is_archived, 
-            created_by: db_result.created_by, 
-            created_at: db_result.created_at, 
-            updated_at: db_result.updated_at,
+        // 1. save the wasm to the blob store
+        self.blobs.put(&blob_key, wasm_bytes.to_vec()).await?;
+
+        // 2. create a new workflow + workflow version in a single transaction
+        let db_result = match self.repo.insert(user_id, namespace_id, &w).await {
+            Ok(wf) => wf,
+            Err(e) => {
+                if let Err(cleanup) = self.blobs.delete(&blob_key).await {
+                    warn!("Failed to remove blob '{}' after a failed insert, leaving it to the reconciler: {}", blob_key, cleanup);
+                }
+                return Err(e);
+            }
         };
 
-        Ok(resp)
+        self.audit.record(NewAuditEntry::new("workflow.create")
+            .namespace(namespace_id)
+            .actor(user_id)
+            .object("workflow", db_result.id)
+            .meta(json!({
+                "key": db_result.key,
+                "version": db_result.active_version,
+                "wasm_size_bytes": wasm_bytes.len(),
+            }))
+        ).await;
+
+        // 3. add to runtime, the version is durable at this point and can be loaded again from the blob
+        let plugin_key = PluginKey::new(namespace_id, &db_result.key, &db_result.active_version);
+        if let Err(e) = self.wit_runtime.load_prepared(&plugin_key, prepared, compiled_schemas).await {
+            error!("Workflow '{}' was stored but could not be loaded into the runtime: {}", plugin_key, e);
+        }
+
+        Ok(db_result)
     }
 
     pub async fn update(&self) -> Result<Workflow, anyhow::Error> {
@@ -99,11 +119,15 @@ impl WorkflowService {
     }
     
 
-    // async fn get_by_key(&self, key: &str) -> Result<Option<Workflow>, anyhow::Error> {
-    //     self.repo.find_by_key(key).await
-    // }
+    pub async fn get_by_key(&self, ns_id: Uuid, key: &str) -> Result<Option<Workflow>, anyhow::Error> {
+        self.repo.find_by_key(ns_id, key).await
+    }
 
     pub async fn find_all(&self, ns_id: Uuid) -> Result<Vec<Workflow>, anyhow::Error> {
         self.repo.find_all(ns_id).await
     }
+
+    pub async fn find_version(&self, ns_id: Uuid, key: &str, version: &str) -> Result<Option<WorkflowVersion>, anyhow::Error> {
+        self.repo.find_version(ns_id, key, version).await
+    }
 }
\ No newline at end of file
@@ -30,6 +30,144 @@ pub struct AppConfig {
     pub space_secret: String,
     pub bucket_name: String,
     pub space_endpoint: String,
+    #[serde(default = "default_reconcile_interval_secs")]
+    pub reconcile_interval_secs: u64,
+    #[serde(default = "default_reconcile_grace_secs")]
+    pub reconcile_grace_secs: i64,
+    /// Hex encoded ed25519 secret key audit checkpoints are signed with; none are made without it.
+    #[serde(default)]
+    pub audit_signing_key: Option<String>,
+    /// Comma separated, hex encoded ed25519 public keys of signing keys rotated out, so their checkpoints still verify.
+    #[serde(default)]
+    pub audit_retired_public_keys: Option<String>,
+    #[serde(default = "default_audit_checkpoint_interval_secs")]
+    pub audit_checkpoint_interval_secs: u64,
+    /// HS256 secret, used to sign access tokens only when no signing key file is set.
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+    /// PEM file with the RSA or Ed25519 private key access tokens are signed with.
+    #[serde(default)]
+    pub jwt_signing_key_file: Option<String>,
+    /// Comma separated PEM files of earlier keys whose tokens are still accepted.
+    #[serde(default)]
+    pub jwt_verification_key_files: Option<String>,
+    #[serde(default = "default_access_token_ttl_secs")]
+    pub access_token_ttl_secs: i64,
+    #[serde(default = "default_refresh_token_ttl_secs")]
+    pub refresh_token_ttl_secs: i64,
+    /// Failed sign-ins allowed per email and per client IP within one window.
+    #[serde(default = "default_signin_max_failures")]
+    pub signin_max_failures: u32,
+    #[serde(default = "default_signin_window_secs")]
+    pub signin_window_secs: u64,
+    /// Comma separated addresses of the proxies in front, only their `X-Forwarded-For` is believed.
+    #[serde(default)]
+    pub trusted_proxies: Option<String>,
+    /// Single sign-on is enabled when an issuer is set; client id and redirect url are then required.
+    #[serde(default)]
+    pub oidc_issuer_url: Option<String>,
+    #[serde(default)]
+    pub oidc_client_id: Option<String>,
+    #[serde(default)]
+    pub oidc_client_secret: Option<String>,
+    #[serde(default)]
+    pub oidc_redirect_url: Option<String>,
+    #[serde(default = "default_oidc_scopes")]
+    pub oidc_scopes: String,
+    #[serde(default = "default_oidc_groups_claim")]
+    pub oidc_groups_claim: String,
+    /// JSON list of `{"group", "namespace", "role"}` rules granting IdP group members a namespace role.
+    #[serde(default)]
+    pub oidc_group_rules: Option<String>,
+    /// `open`, `invite_only`, `allowed_domains` or `disabled`.
+    #[serde(default = "default_registration_mode")]
+    pub registration_mode: String,
+    /// Comma separated email domains the `allowed_domains` mode lets sign up.
+    #[serde(default)]
+    pub registration_allowed_domains: Option<String>,
+    #[serde(default = "default_invitation_ttl_secs")]
+    pub invitation_ttl_secs: i64,
+    /// Made super admin on start while there is none; the account is created if needed.
+    #[serde(default)]
+    pub bootstrap_super_admin_email: Option<String>,
+    /// How long a claimed run stays with its executor without recording a step.
+    #[serde(default = "default_run_lease_secs")]
+    pub run_lease_secs: u64,
+    #[serde(default = "default_run_poll_interval_secs")]
+    pub run_poll_interval_secs: u64,
+    /// Runs one executor drives at the same time.
+    #[serde(default = "default_max_concurrent_runs")]
+    pub max_concurrent_runs: usize,
+    /// How long a workflow may take for one decision task before it is trapped and its run failed.
+    #[serde(default = "default_decision_timeout_secs")]
+    pub decision_timeout_secs: u64,
+    /// How long the response to an `Idempotency-Key` is kept for retries.
+    #[serde(default = "default_idempotency_key_ttl_secs")]
+    pub idempotency_key_ttl_secs: i64,
+}
+
+fn default_reconcile_interval_secs() -> u64 {
+    15 * 60
+}
+
+fn default_reconcile_grace_secs() -> i64 {
+    60 * 60
+}
+
+fn default_audit_checkpoint_interval_secs() -> u64 {
+    60 * 60
+}
+
+fn default_access_token_ttl_secs() -> i64 {
+    15 * 60
+}
+
+fn default_refresh_token_ttl_secs() -> i64 {
+    30 * 24 * 60 * 60
+}
+
+fn default_signin_max_failures() -> u32 {
+    10
+}
+
+fn default_signin_window_secs() -> u64 {
+    15 * 60
+}
+
+fn default_oidc_scopes() -> String {
+    "openid email profile".to_string()
+}
+
+fn default_oidc_groups_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_registration_mode() -> String {
+    "open".to_string()
+}
+
+fn default_invitation_ttl_secs() -> i64 {
+    7 * 24 * 60 * 60
+}
+
+fn default_run_lease_secs() -> u64 {
+    60
+}
+
+fn default_run_poll_interval_secs() -> u64 {
+    5
+}
+
+fn default_max_concurrent_runs() -> usize {
+    16
+}
+
+fn default_decision_timeout_secs() -> u64 {
+    30
+}
+
+fn default_idempotency_key_ttl_secs() -> i64 {
+    24 * 60 * 60
 }
 
 impl ProvideCredentials for AppConfig {
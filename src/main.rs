@@ -11,7 +11,7 @@
  * SPDX-License-Identifier: AGPL-3.0-or-later
  */
 
-use tracing::{error, info, Level};
+use tracing::{error, info, warn, Level};
 use tracing_subscriber::EnvFilter;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -32,13 +32,42 @@ use crate::adapters::postgres::namespace_repo::PostgresNamespaceRepository;
 use crate::adapters::wasmtime::wit_runtime::WitPluginRuntime;
 use crate::adapters::filesystem::plugin_auto_loader::PluginAutoLoader;
 
+use crate::adapters::s3::blob_store::S3BlobStore;
+
 use crate::core::services::namespace::NamespaceService;
+use crate::core::services::reconciler::BlobReconciler;
+use crate::core::services::audit::{self, AuditService, CheckpointSigner};
+use crate::core::services::api_key::ApiKeyService;
+use crate::core::services::session::SessionService;
+use crate::core::services::rate_limit::SignInLimiter;
+use crate::core::services::sso::SsoService;
+use crate::core::services::cluster::ClusterService;
+use crate::core::services::admin::AdminService;
+use crate::core::services::invitation::InvitationService;
+use crate::core::services::run::RunService;
+use crate::core::services::run_executor::RunExecutor;
+use crate::core::services::idempotency::IdempotencyService;
+use crate::core::domain::admin::BootstrapOutcome;
+use crate::core::domain::user::RegistrationMode;
+use crate::core::domain::namespace::NamespaceRole;
+use crate::core::domain::oidc::GroupRoleRule;
+use crate::adapters::oidc::provider::{OidcProvider, OidcSettings};
 use crate::core::services::{
     user::UserService,
     workflow::WorkflowService,
 };
 
 use crate::adapters::postgres::{
+    audit_log::PostgresAuditLog,
+    api_key_repo::PostgresApiKeyRepository,
+    session_repo::PostgresSessionRepository,
+    identity_repo::PostgresIdentityRepository,
+    admin_repo::PostgresAdminRepository,
+    cluster_bus::PostgresClusterBus,
+    invitation_repo::PostgresInvitationRepository,
+    run_repo::PostgresRunRepository,
+    idempotency_repo::PostgresIdempotencyRepository,
+    search_attribute_repo::PostgresSearchAttributeRepository,
     user_repo::PostgresUserRepository,
     workflow_repo::PostgresWorkflowRepository,
 };
@@ -54,6 +83,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Starting Xarxa...");
 
+    let token_keys = Arc::new(http::token_keys::TokenKeys::from_config(&config)?);
+
     let s3_config = config.clone();
     let http_config = config.clone();
 
@@ -76,21 +107,152 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let wit_runtime = Arc::new(WitPluginRuntime::new()?);
     // --- wit runtime end
     
+    // --- blob store ---
+    let blob_store = Arc::new(S3BlobStore::new(s3_client.clone(), config.bucket_name.clone()));
+    // --- blob store end ---
+
     // --- repos ---
     let workflows_repo = Arc::new(PostgresWorkflowRepository::new(pool.clone()));
     let users_repo = Arc::new(PostgresUserRepository::new(pool.clone()));
     let namespace_repo = Arc::new(PostgresNamespaceRepository::new(pool.clone()));
+    let audit_log = Arc::new(PostgresAuditLog::new(pool.clone()));
+    let api_key_repo = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
+    let session_repo = Arc::new(PostgresSessionRepository::new(pool.clone()));
+    let identity_repo = Arc::new(PostgresIdentityRepository::new(pool.clone()));
+    let admin_repo = Arc::new(PostgresAdminRepository::new(pool.clone()));
+    let cluster_bus = Arc::new(PostgresClusterBus::new(pool.clone()));
+    let invitation_repo = Arc::new(PostgresInvitationRepository::new(pool.clone()));
+    let run_repo = Arc::new(PostgresRunRepository::new(pool.clone()));
+    let idempotency_repo = Arc::new(PostgresIdempotencyRepository::new(pool.clone()));
+    let search_attribute_repo = Arc::new(PostgresSearchAttributeRepository::new(pool.clone()));
     // --- end repos ---
 
     // --- services ---
-    let workflows_service = Arc::new(WorkflowService::new(workflows_repo.clone(), s3_client.clone(), wit_runtime.clone()));
-    let user_service = Arc::new(UserService::new(users_repo.clone()));
-    let namespace_service = Arc::new(NamespaceService::new(namespace_repo));
+    let checkpoint_signer = match &config.audit_signing_key {
+        Some(key) => Some(CheckpointSigner::from_hex(key)?),
+        None => {
+            warn!("⚠️  No audit signing key configured, audit checkpoints are disabled");
+            None
+        }
+    };
+    let retired_audit_keys = audit::parse_retired_keys(config.audit_retired_public_keys.as_deref())?;
+    let audit_service = Arc::new(AuditService::new(audit_log, checkpoint_signer, retired_audit_keys));
+    let workflows_service = Arc::new(WorkflowService::new(workflows_repo.clone(), blob_store.clone(), wit_runtime.clone(), audit_service.clone()));
+    let registration = RegistrationMode::parse(&config.registration_mode, config.registration_allowed_domains.as_deref())?;
+    let user_service = Arc::new(UserService::new(
+        users_repo.clone(),
+        session_repo.clone(),
+        audit_service.clone(),
+        SignInLimiter::new(config.signin_max_failures, std::time::Duration::from_secs(config.signin_window_secs)),
+        registration.clone(),
+    ));
+    let namespace_service = Arc::new(NamespaceService::new(namespace_repo.clone(), users_repo.clone(), blob_store.clone(), wit_runtime.clone(), audit_service.clone()));
+    let api_key_service = Arc::new(ApiKeyService::new(api_key_repo));
+    let session_service = Arc::new(SessionService::new(
+        session_repo.clone(),
+        users_repo.clone(),
+        audit_service.clone(),
+        chrono::Duration::seconds(config.access_token_ttl_secs),
+        chrono::Duration::seconds(config.refresh_token_ttl_secs),
+    ));
+    let sso_service = match &config.oidc_issuer_url {
+        Some(issuer_url) => {
+            let settings = OidcSettings {
+                issuer_url: issuer_url.clone(),
+                client_id: config.oidc_client_id.clone().ok_or("OIDC_CLIENT_ID is required with OIDC_ISSUER_URL")?,
+                client_secret: config.oidc_client_secret.clone(),
+                redirect_url: config.oidc_redirect_url.clone().ok_or("OIDC_REDIRECT_URL is required with OIDC_ISSUER_URL")?,
+                scopes: config.oidc_scopes.clone(),
+                groups_claim: config.oidc_groups_claim.clone(),
+            };
+
+            let rules: Vec<GroupRoleRule> = match &config.oidc_group_rules {
+                Some(rules) => serde_json::from_str(rules)?,
+                None => Vec::new(),
+            };
+            if rules.iter().any(|r| r.role == NamespaceRole::Owner) {
+                return Err("OIDC group rules can not grant the owner role".into());
+            }
+
+            let provider = Arc::new(OidcProvider::discover(settings).await?);
+            Some(Arc::new(SsoService::new(
+                provider,
+                identity_repo,
+                users_repo.clone(),
+                namespace_repo.clone(),
+                audit_service.clone(),
+                registration,
+                rules,
+            )))
+        }
+        None => None,
+    };
+    let cluster_service = Arc::new(ClusterService::new(cluster_bus, wit_runtime.clone()));
+    let admin_service = Arc::new(AdminService::new(
+        admin_repo,
+        session_repo,
+        namespace_repo.clone(),
+        run_repo.clone(),
+        cluster_service.clone(),
+        user_service.clone(),
+        audit_service.clone(),
+    ));
+    let invitation_service = Arc::new(InvitationService::new(
+        invitation_repo,
+        namespace_repo.clone(),
+        user_service.clone(),
+        chrono::Duration::seconds(config.invitation_ttl_secs),
+    ));
+    let run_executor = Arc::new(RunExecutor::new(
+        run_repo.clone(),
+        search_attribute_repo.clone(),
+        blob_store.clone(),
+        wit_runtime.clone(),
+        std::time::Duration::from_secs(config.run_lease_secs),
+        std::time::Duration::from_secs(config.decision_timeout_secs),
+        config.max_concurrent_runs,
+    ));
+    let run_service = Arc::new(RunService::new(run_repo, workflows_repo.clone(), search_attribute_repo, run_executor.wakeup()));
+    let idempotency_service = Arc::new(IdempotencyService::new(idempotency_repo, chrono::Duration::seconds(config.idempotency_key_ttl_secs)));
+    let reconciler = BlobReconciler::new(
+        workflows_repo.clone(),
+        blob_store.clone(),
+        chrono::Duration::seconds(config.reconcile_grace_secs),
+    );
     // --- end services ---
 
+    // `xarxa bootstrap-admin <email>` makes the account a super admin, creating it if needed, and exits
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("bootstrap-admin") {
+        let email = args.get(2).ok_or("usage: xarxa bootstrap-admin <email>")?;
+
+        match admin_service.bootstrap_super_admin(email, false).await? {
+            BootstrapOutcome::Created { user, token } => println!("Created super admin {} ({}), token: {}", user.email, user.id, token),
+            BootstrapOutcome::Promoted(user) => println!("Promoted {} ({}) to super admin", user.email, user.id),
+            BootstrapOutcome::AlreadyPresent => println!("{} is already a super admin", email),
+        }
+        return Ok(());
+    }
+
+    if let Some(email) = &config.bootstrap_super_admin_email {
+        match admin_service.bootstrap_super_admin(email, true).await? {
+            BootstrapOutcome::Created { user, token } => {
+                // the token stays out of the logs, it is printed this once like `bootstrap-admin` does
+                warn!("⚠️  Created initial super admin {} ({}), its token is printed to stdout", user.email, user.id);
+                println!("Created initial super admin {} ({}), token: {} (shown once, rotate it after signing in)", user.email, user.id, token);
+            }
+            BootstrapOutcome::Promoted(user) => info!("Promoted {} ({}) to initial super admin", user.email, user.id),
+            BootstrapOutcome::AlreadyPresent => {}
+        }
+    }
+
     // The channels for graceful shutdown
     let (http_shutdown_tx, http_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
     let (loader_shutdown_tx, loader_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let (reconciler_shutdown_tx, reconciler_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let (checkpoints_shutdown_tx, checkpoints_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let (cluster_shutdown_tx, cluster_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let (runs_shutdown_tx, runs_shutdown_rx) = tokio::sync::oneshot::channel::<()>();
 
     // Run the HTTP server in separate runtime environment.
     let http_runtime = tokio::runtime::Builder::new_multi_thread()
@@ -100,6 +262,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .enable_all()
         .build()?;
 
+    let audit_to_checkpoints = audit_service.clone();
     let wit_to_http = wit_runtime.clone();
     let wit_to_loader = wit_runtime.clone();
 
@@ -109,6 +272,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             workflows_service.clone(), 
             user_service.clone(), 
             namespace_service.clone(),
+            audit_service.clone(),
+            api_key_service.clone(),
+            session_service.clone(),
+            sso_service.clone(),
+            admin_service.clone(),
+            invitation_service.clone(),
+            run_service.clone(),
+            idempotency_service.clone(),
+            token_keys,
             http_shutdown_rx, 
             wit_to_http, 
             http_config,
@@ -136,6 +308,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    let reconcile_interval = std::time::Duration::from_secs(config.reconcile_interval_secs);
+    let reconciler_handle = tokio::spawn(async move {
+        reconciler.run(reconcile_interval, reconciler_shutdown_rx).await;
+    });
+
+    let checkpoint_interval = std::time::Duration::from_secs(config.audit_checkpoint_interval_secs);
+    let checkpoints_handle = tokio::spawn(async move {
+        audit_to_checkpoints.run_checkpoints(checkpoint_interval, checkpoints_shutdown_rx).await;
+    });
+
+    let cluster_handle = tokio::spawn(async move {
+        cluster_service.run(cluster_shutdown_rx).await;
+    });
+
+    let run_poll_interval = std::time::Duration::from_secs(config.run_poll_interval_secs);
+    let runs_handle = tokio::spawn(async move {
+        run_executor.run(run_poll_interval, runs_shutdown_rx).await;
+    });
+
     info!("All runtimes started successfully");
     info!("HTTP server: http://localhost:4000");
     info!("Engine: Ready to execute WASM functions");
@@ -149,15 +340,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Send shutdown signal to all components
     let _ = http_shutdown_tx.send(());
     let _ = loader_shutdown_tx.send(());
+    let _ = reconciler_shutdown_tx.send(());
+    let _ = checkpoints_shutdown_tx.send(());
+    let _ = cluster_shutdown_tx.send(());
+    let _ = runs_shutdown_tx.send(());
 
     let shutdown_timeout = tokio::time::Duration::from_secs(10);
 
     match tokio::time::timeout(shutdown_timeout, async {
-        tokio::join!(http_handle, loader_handle)
+        tokio::join!(http_handle, loader_handle, reconciler_handle, checkpoints_handle, cluster_handle, runs_handle)
     }).await {
-        Ok(( http_result, loader)) => {
+        Ok(( http_result, loader, reconciler, checkpoints, cluster, runs)) => {
             info!("All components shutdown gracefully");
             
+            if let Err(e) = runs {
+                error!("Run executor task failed: {}", e);
+            }
+            if let Err(e) = cluster {
+                error!("Cluster event task failed: {}", e);
+            }
+            if let Err(e) = checkpoints {
+                error!("Audit checkpoint task failed: {}", e);
+            }
+            if let Err(e) = reconciler {
+                error!("Reconciler task failed: {}", e);
+            }
             if let Err(e) = loader {
                 error!("Engine task failed: {}", e);
             }